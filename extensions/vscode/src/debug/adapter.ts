@@ -1,26 +1,30 @@
 import * as vscode from 'vscode';
-import { DebugAdapterDescriptor, DebugAdapterInlineImplementation } from 'vscode';
-import { SorobanDebugSession } from '../dap/adapter';
+import { DebugAdapterDescriptor, DebugAdapterExecutable } from 'vscode';
+
+/** Command that serves the Debug Adapter Protocol over stdio */
+const DEBUGGER_COMMAND = 'soroban-debug';
 
 export class SorobanDebugAdapterDescriptorFactory
   implements vscode.DebugAdapterDescriptorFactory, vscode.Disposable {
-  
+
   private context: vscode.ExtensionContext;
-  private session: SorobanDebugSession | null = null;
 
   constructor(context: vscode.ExtensionContext) {
     this.context = context;
   }
 
+  /**
+   * Run `soroban-debug dap` as the adapter, so breakpoints with conditions,
+   * hit conditions, and changes made after launch all reach the debugger
+   */
   async createDebugAdapterDescriptor(
     session: vscode.DebugSession,
     executable: vscode.DebugAdapterExecutable | undefined
   ): Promise<DebugAdapterDescriptor | null> {
-    this.session = new SorobanDebugSession();
-    return new DebugAdapterInlineImplementation(this.session);
+    return new DebugAdapterExecutable(DEBUGGER_COMMAND, ['dap']);
   }
 
   dispose(): void {
-    this.session = null;
+    // Each session's adapter process exits when its session ends
   }
 }
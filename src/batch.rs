@@ -1,4 +1,7 @@
 use crate::runtime::executor::ContractExecutor;
+use crate::runtime::manifest::ContractManifest;
+use crate::simulator::SnapshotLoader;
+use crate::utils::ContractErrorInfo;
 use crate::Result;
 use anyhow::Context;
 use rayon::prelude::*;
@@ -12,8 +15,12 @@ use std::time::Instant;
 pub struct BatchItem {
     /// Arguments as JSON string
     pub args: String,
-    /// Optional expected result for assertion
-    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Optional expected result for assertion, as JSON text or a JSON value
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_expected"
+    )]
     pub expected: Option<String>,
     /// Optional label for this test case
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -29,6 +36,9 @@ pub struct BatchResult {
     pub result: String,
     pub success: bool,
     pub error: Option<String>,
+    /// Contract error raised by the call, named from the contract spec
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_error: Option<ContractErrorInfo>,
     pub expected: Option<String>,
     pub passed: bool,
     pub duration_ms: u128,
@@ -48,6 +58,8 @@ pub struct BatchSummary {
 pub struct BatchExecutor {
     wasm_bytes: Vec<u8>,
     function: String,
+    network_snapshot: Option<SnapshotLoader>,
+    manifest: Option<ContractManifest>,
 }
 
 impl BatchExecutor {
@@ -56,9 +68,23 @@ impl BatchExecutor {
         Self {
             wasm_bytes,
             function,
+            network_snapshot: None,
+            manifest: None,
         }
     }
 
+    /// Run every batch item on top of a network snapshot
+    pub fn with_network_snapshot(mut self, snapshot: SnapshotLoader) -> Self {
+        self.network_snapshot = Some(snapshot);
+        self
+    }
+
+    /// Register the manifest's contracts for every batch item
+    pub fn with_manifest(mut self, manifest: ContractManifest) -> Self {
+        self.manifest = Some(manifest);
+        self
+    }
+
     /// Load batch items from a JSON file
     pub fn load_batch_file<P: AsRef<Path>>(path: P) -> Result<Vec<BatchItem>> {
         let content = fs::read_to_string(path.as_ref())
@@ -85,28 +111,44 @@ impl BatchExecutor {
         Ok(results)
     }
 
+    fn create_executor(&self) -> Result<ContractExecutor> {
+        let mut executor = match &self.network_snapshot {
+            Some(snapshot) => {
+                ContractExecutor::with_network_snapshot(self.wasm_bytes.clone(), snapshot)?.0
+            }
+            None => ContractExecutor::new(self.wasm_bytes.clone())?,
+        };
+        if let Some(manifest) = &self.manifest {
+            executor.register_manifest(manifest)?;
+        }
+        Ok(executor)
+    }
+
     /// Execute a single batch item
     fn execute_single(&self, index: usize, item: &BatchItem) -> BatchResult {
         let start = Instant::now();
 
-        let executor_result = ContractExecutor::new(self.wasm_bytes.clone());
+        let executor_result = self.create_executor();
 
-        let (result_str, success, error) = match executor_result {
+        let (result_str, success, error, contract_error) = match executor_result {
             Ok(executor) => match executor.execute(&self.function, Some(&item.args)) {
-                Ok(result) => (result, true, None),
-                Err(e) => (String::new(), false, Some(format!("{:#}", e))),
+                Ok(result) => (result.result, true, None, result.error),
+                Err(e) => (String::new(), false, Some(format!("{:#}", e)), None),
             },
             Err(e) => (
                 String::new(),
                 false,
                 Some(format!("Failed to create executor: {:#}", e)),
+                None,
             ),
         };
 
         let duration_ms = start.elapsed().as_millis();
 
         let passed = if let Some(expected) = &item.expected {
-            success && result_str.trim() == expected.trim()
+            success
+                && (results_match(&result_str, expected)
+                    || contract_error.as_ref().is_some_and(|e| e.matches(expected)))
         } else {
             success
         };
@@ -118,6 +160,7 @@ impl BatchExecutor {
             result: result_str,
             success,
             error,
+            contract_error,
             expected: item.expected.clone(),
             passed,
             duration_ms,
@@ -209,6 +252,32 @@ impl BatchExecutor {
     }
 }
 
+/// Accept `expected` as a string or as any JSON value (stored as JSON text)
+fn deserialize_expected<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(
+        match Option::<serde_json::Value>::deserialize(deserializer)? {
+            None => None,
+            Some(serde_json::Value::String(s)) => Some(s),
+            Some(other) => Some(other.to_string()),
+        },
+    )
+}
+
+/// Compare a result with its expectation as JSON values, so formatting and
+/// key order do not matter, falling back to plain text for non-JSON strings.
+fn results_match(result: &str, expected: &str) -> bool {
+    let as_json = |s: &str| serde_json::from_str::<serde_json::Value>(s.trim()).ok();
+    match (as_json(result), as_json(expected)) {
+        (Some(result), Some(expected)) => result == expected,
+        // `"expected": "hello"` for a result of `"hello"`
+        (Some(serde_json::Value::String(result)), None) => result == expected.trim(),
+        _ => result.trim() == expected.trim(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,6 +298,33 @@ mod tests {
         assert_eq!(items[1].expected, None);
     }
 
+    #[test]
+    fn test_batch_item_expected_json_value() {
+        let json = r#"[
+            {"args": "[1]", "expected": {"owner": "GAAA", "count": 3}},
+            {"args": "[2]", "expected": 3}
+        ]"#;
+
+        let items: Vec<BatchItem> = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            items[0].expected.as_deref(),
+            Some(r#"{"count":3,"owner":"GAAA"}"#)
+        );
+        assert_eq!(items[1].expected.as_deref(), Some("3"));
+    }
+
+    #[test]
+    fn test_results_match_compares_json() {
+        assert!(results_match(r#"{"a":1,"b":2}"#, r#"{ "b": 2, "a": 1 }"#));
+        assert!(results_match("3", " 3 "));
+        assert!(results_match(r#""hello""#, "hello"));
+        assert!(!results_match("3", "4"));
+        assert!(results_match(
+            "Error: Contract error code: 1",
+            "Error: Contract error code: 1"
+        ));
+    }
+
     #[test]
     fn test_batch_summary() {
         let results = vec![
@@ -239,6 +335,7 @@ mod tests {
                 result: "ok".to_string(),
                 success: true,
                 error: None,
+                contract_error: None,
                 expected: None,
                 passed: true,
                 duration_ms: 10,
@@ -250,6 +347,7 @@ mod tests {
                 result: "fail".to_string(),
                 success: true,
                 error: None,
+                contract_error: None,
                 expected: Some("ok".to_string()),
                 passed: false,
                 duration_ms: 15,
@@ -80,8 +80,17 @@ pub enum Commands {
     /// Compare two execution trace JSON files side-by-side
     Compare(CompareArgs),
 
+    /// Step forwards and backwards through a session recorded with `run --record`
+    Replay(ReplayArgs),
+
     /// List exported functions of a contract (shorthand for `inspect --functions`)
     ListFunctions(ListFunctionsArgs),
+
+    /// Serve the Debug Adapter Protocol for editors such as VS Code, nvim-dap and Helix
+    Dap(DapArgs),
+
+    /// Serve a line-delimited JSON-RPC interface for test harnesses and scripts
+    Rpc(RpcArgs),
 }
 
 #[derive(Parser)]
@@ -102,14 +111,40 @@ pub struct RunArgs {
     #[arg(short, long)]
     pub storage: Option<String>,
 
-    /// Set breakpoint at function name
+    /// Set breakpoint at function name, or at CID::function to match one contract only.
+    /// "host:<name>" stops at calls of a host function such as host:require_auth.
+    /// Append "if <condition>", "hit <count>" or "log <message>" for conditional
+    /// breakpoints and log points, e.g. "transfer if amount > 1000".
+    /// With the default host backend only the called function is matched, before
+    /// the call, and nothing pauses; nested calls and pausing need --backend interpreter
     #[arg(short, long)]
     pub breakpoint: Vec<String>,
 
+    /// Pause when a storage key matching the pattern is read or written (repeatable).
+    /// Takes "<pattern> [read|write]" with --storage-filter patterns, e.g. "balance:* write".
+    /// Only fires with --backend interpreter
+    #[arg(long, value_name = "SPEC")]
+    pub watch: Vec<String>,
+
+    /// Pause at the failure point when the call panics or returns an error, to inspect
+    /// storage, events, budget and auth checks as they were. Only pauses with
+    /// --backend interpreter
+    #[arg(long)]
+    pub break_on_error: bool,
+
+    /// Record the call's host calls, storage changes, events and budget to a file
+    /// that `replay` can step through. Only records with --backend interpreter
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<PathBuf>,
+
     /// Network snapshot file to load before execution
     #[arg(long)]
     pub network_snapshot: Option<PathBuf>,
 
+    /// Manifest (JSON) of additional contracts to register, e.g. cross-contract callees
+    #[arg(long)]
+    pub manifest: Option<PathBuf>,
+
     /// Enable verbose output
     #[arg(short, long)]
     pub verbose: bool,
@@ -141,9 +176,17 @@ pub struct RunArgs {
     #[arg(long)]
     pub step_instructions: bool,
 
-    /// Step mode for instruction debugging (into, over, out, block)
+    /// Step mode for instruction debugging (into, over, out, block, line,
+    /// line-into)
     #[arg(long, default_value = "into")]
     pub step_mode: String,
+
+    /// Execution backend: "host" runs the call on the host VM in one shot,
+    /// "interpreter" runs it in the built-in interpreter so breakpoints pause
+    /// mid-call and can be stepped
+    #[arg(long, default_value = "host", value_parser = ["host", "interpreter"])]
+    pub backend: String,
+
     /// Execute contract in dry-run mode: simulate execution without persisting storage changes
     #[arg(long)]
     pub dry_run: bool,
@@ -173,6 +216,18 @@ pub struct RunArgs {
     /// Append to output file instead of overwriting
     #[arg(long, requires = "save_output")]
     pub append: bool,
+
+    /// Show the events emitted during execution
+    #[arg(long)]
+    pub show_events: bool,
+
+    /// Show the authorization tree of the call
+    #[arg(long)]
+    pub show_auth: bool,
+
+    /// Output format ("text" or "json")
+    #[arg(long)]
+    pub format: Option<String>,
 }
 
 impl RunArgs {
@@ -214,6 +269,22 @@ pub struct InteractiveArgs {
     /// Network snapshot file to load before starting interactive session
     #[arg(long)]
     pub network_snapshot: Option<PathBuf>,
+
+    /// Manifest (JSON) of additional contracts to register, e.g. cross-contract callees
+    #[arg(long)]
+    pub manifest: Option<PathBuf>,
+
+    /// Execution backend for calls made from the session (host, interpreter)
+    #[arg(long, default_value = "host", value_parser = ["host", "interpreter"])]
+    pub backend: String,
+
+    /// Pause at the failure point when a call fails. Only pauses with --backend interpreter
+    #[arg(long)]
+    pub break_on_error: bool,
+
+    /// Use the line-based prompt instead of the full-screen interface
+    #[arg(long)]
+    pub plain: bool,
 }
 
 impl InteractiveArgs {
@@ -311,6 +382,35 @@ pub struct CompareArgs {
     pub output: Option<PathBuf>,
 }
 
+#[derive(Parser)]
+pub struct ReplayArgs {
+    /// Session file written by `run --record`
+    #[arg(value_name = "FILE")]
+    pub recording: PathBuf,
+
+    /// Stop at a function or host:<name> when continuing in either direction
+    #[arg(short, long)]
+    pub breakpoint: Vec<String>,
+
+    /// Stop at reads or writes of matching storage keys, as in `run --watch`
+    #[arg(long, value_name = "SPEC")]
+    pub watch: Vec<String>,
+}
+
+#[derive(Parser)]
+pub struct DapArgs {
+    /// Wait for one client on this TCP port of 127.0.0.1 instead of using stdin and stdout
+    #[arg(long)]
+    pub port: Option<u16>,
+}
+
+#[derive(Parser)]
+pub struct RpcArgs {
+    /// Wait for one client on this Unix socket instead of using stdin and stdout
+    #[arg(long, value_name = "PATH")]
+    pub socket: Option<PathBuf>,
+}
+
 #[derive(Parser)]
 pub struct CompletionsArgs {
     /// Shell to generate completion script for
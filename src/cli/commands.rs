@@ -1,20 +1,28 @@
 use crate::cli::args::{
-    CompareArgs, InspectArgs, InteractiveArgs, ListFunctionsArgs, OptimizeArgs, ProfileArgs,
-    RunArgs, UpgradeCheckArgs, Verbosity,
+    CompareArgs, DapArgs, InspectArgs, InteractiveArgs, ListFunctionsArgs, OptimizeArgs,
+    ProfileArgs, ReplayArgs, RpcArgs, RunArgs, UpgradeCheckArgs, Verbosity,
 };
-use crate::debugger::engine::DebuggerEngine;
+use crate::debugger::breakpoint::{split_qualified, Breakpoint};
+use crate::debugger::engine::{DebuggerEngine, ExecutionBackend, HostCallRecord};
 use crate::debugger::instruction_pointer::StepMode;
+use crate::debugger::recording::{Recording, Replay};
+use crate::debugger::watchpoint::{Watchpoint, WatchpointManager};
+use crate::debugger::BreakpointManager;
+use crate::inspector::StorageInspector;
 use crate::logging;
 use crate::repeat::RepeatRunner;
-use crate::runtime::executor::ContractExecutor;
+use crate::runtime::executor::{ContractExecutor, ExecutionResult};
+use crate::runtime::manifest::ContractManifest;
 use crate::simulator::SnapshotLoader;
 use crate::ui::formatter::Formatter;
+use crate::ui::pause_prompt::PausePrompt;
+use crate::ui::replay_prompt::ReplayPrompt;
 use crate::ui::tui::DebuggerUI;
 use crate::Result;
 use anyhow::Context;
 use std::fs;
 use std::fs::OpenOptions;
-use std::io::Write;
+use std::io::{self, IsTerminal, Write};
 use std::path::Path;
 
 fn print_info(message: impl AsRef<str>) {
@@ -44,6 +52,66 @@ fn write_to_file(path: &Path, content: &str, append: bool) -> Result<()> {
     Ok(())
 }
 
+/// Load the network snapshot given on the command line, if any.
+fn load_network_snapshot(snapshot_path: Option<&Path>) -> Result<Option<SnapshotLoader>> {
+    let Some(snapshot_path) = snapshot_path else {
+        return Ok(None);
+    };
+    print_info(format!("\nLoading network snapshot: {:?}", snapshot_path));
+    logging::log_loading_snapshot(&snapshot_path.to_string_lossy());
+    Ok(Some(SnapshotLoader::from_file(snapshot_path)?))
+}
+
+/// Load the contract manifest given on the command line, if any.
+fn load_manifest(manifest_path: Option<&Path>) -> Result<Option<ContractManifest>> {
+    let Some(manifest_path) = manifest_path else {
+        return Ok(None);
+    };
+    print_info(format!("Loading contract manifest: {:?}", manifest_path));
+    Ok(Some(ContractManifest::from_file(manifest_path)?))
+}
+
+/// Initial storage given with `--storage`, or imported with
+/// `--import-storage`, which takes precedence.
+fn load_initial_storage(args: &RunArgs) -> Result<Option<String>> {
+    if let Some(import_path) = &args.import_storage {
+        print_info(format!("Importing storage from: {:?}", import_path));
+        let imported = crate::inspector::storage::StorageState::load(import_path)?;
+        print_success(format!("Imported {} storage entries", imported.len()));
+        return Ok(Some(imported.to_initial_storage_json()?));
+    }
+    args.storage.as_deref().map(parse_storage).transpose()
+}
+
+/// Create the executor, on top of the network snapshot if one was loaded,
+/// and register the manifest's contracts next to it.
+fn create_executor(
+    wasm_bytes: Vec<u8>,
+    network_snapshot: Option<&SnapshotLoader>,
+    manifest: Option<&ContractManifest>,
+) -> Result<ContractExecutor> {
+    let mut executor = match network_snapshot {
+        Some(loader) => {
+            let (executor, loaded_snapshot) =
+                ContractExecutor::with_network_snapshot(wasm_bytes, loader)?;
+            logging::log_display(loaded_snapshot.format_summary(), logging::LogLevel::Info);
+            executor
+        }
+        None => ContractExecutor::new(wasm_bytes)?,
+    };
+
+    if let Some(manifest) = manifest {
+        executor.register_manifest(manifest)?;
+        for contract in executor.contracts() {
+            print_success(format!(
+                "Registered contract {} at {}",
+                contract.name, contract.contract_id
+            ));
+        }
+    }
+    Ok(executor)
+}
+
 /// Execute batch mode with parallel execution
 fn run_batch(args: &RunArgs, batch_file: &std::path::Path) -> Result<()> {
     print_info(format!("Loading contract: {:?}", args.contract));
@@ -62,13 +130,8 @@ fn run_batch(args: &RunArgs, batch_file: &std::path::Path) -> Result<()> {
     let batch_items = crate::batch::BatchExecutor::load_batch_file(batch_file)?;
     print_success(format!("Loaded {} test cases", batch_items.len()));
 
-    if let Some(snapshot_path) = &args.network_snapshot {
-        print_info(format!("\nLoading network snapshot: {:?}", snapshot_path));
-        logging::log_loading_snapshot(&snapshot_path.to_string_lossy());
-        let loader = SnapshotLoader::from_file(snapshot_path)?;
-        let loaded_snapshot = loader.apply_to_environment()?;
-        logging::log_display(loaded_snapshot.format_summary(), logging::LogLevel::Info);
-    }
+    let network_snapshot = load_network_snapshot(args.network_snapshot.as_deref())?;
+    let manifest = load_manifest(args.manifest.as_deref())?;
 
     print_info(format!(
         "\nExecuting {} test cases in parallel for function: {}",
@@ -77,7 +140,13 @@ fn run_batch(args: &RunArgs, batch_file: &std::path::Path) -> Result<()> {
     ));
     logging::log_execution_start(&args.function, None);
 
-    let executor = crate::batch::BatchExecutor::new(wasm_bytes, args.function.clone());
+    let mut executor = crate::batch::BatchExecutor::new(wasm_bytes, args.function.clone());
+    if let Some(loader) = network_snapshot {
+        executor = executor.with_network_snapshot(loader);
+    }
+    if let Some(manifest) = manifest {
+        executor = executor.with_manifest(manifest);
+    }
     let results = executor.execute_batch(batch_items)?;
     let summary = crate::batch::BatchExecutor::summarize(&results);
 
@@ -110,13 +179,36 @@ fn run_batch(args: &RunArgs, batch_file: &std::path::Path) -> Result<()> {
     Ok(())
 }
 
+/// Print the host call log, grouped by the contract making the calls
+fn display_host_calls(host_calls: &[HostCallRecord]) {
+    print_info(format!("\n--- Host Calls ({}) ---", host_calls.len()));
+    let mut contract = None;
+    for record in host_calls {
+        if contract != Some(&record.contract_id) {
+            print_info(format!("  [{}]", record.contract_id));
+            contract = Some(&record.contract_id);
+        }
+        println!("    {}", record.call);
+    }
+}
+
 /// Execute the run command.
-pub fn run(args: RunArgs, _verbosity: Verbosity) -> Result<()> {
+pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
     // Handle batch execution mode
     if let Some(batch_file) = &args.batch_args {
         return run_batch(&args, batch_file);
     }
 
+    // Reject malformed conditions before anything runs
+    let breakpoints = args
+        .breakpoint
+        .iter()
+        .map(|spec| spec.parse::<Breakpoint>())
+        .collect::<Result<Vec<_>>>()?;
+    for spec in &args.watch {
+        spec.parse::<Watchpoint>()?;
+    }
+
     if args.dry_run {
         return run_dry_run(&args);
     }
@@ -133,13 +225,8 @@ pub fn run(args: RunArgs, _verbosity: Verbosity) -> Result<()> {
     ));
     logging::log_contract_loaded(wasm_bytes.len());
 
-    if let Some(snapshot_path) = &args.network_snapshot {
-        print_info(format!("\nLoading network snapshot: {:?}", snapshot_path));
-        logging::log_loading_snapshot(&snapshot_path.to_string_lossy());
-        let loader = SnapshotLoader::from_file(snapshot_path)?;
-        let loaded_snapshot = loader.apply_to_environment()?;
-        logging::log_display(loaded_snapshot.format_summary(), logging::LogLevel::Info);
-    }
+    let network_snapshot = load_network_snapshot(args.network_snapshot.as_deref())?;
+    let manifest = load_manifest(args.manifest.as_deref())?;
 
     let parsed_args = if let Some(args_json) = &args.args {
         Some(parse_args(args_json)?)
@@ -147,23 +234,17 @@ pub fn run(args: RunArgs, _verbosity: Verbosity) -> Result<()> {
         None
     };
 
-    let mut initial_storage = if let Some(storage_json) = &args.storage {
-        Some(parse_storage(storage_json)?)
-    } else {
-        None
-    };
-
-    // Import storage if specified
-    if let Some(import_path) = &args.import_storage {
-        print_info(format!("Importing storage from: {:?}", import_path));
-        let imported = crate::inspector::storage::StorageState::import_from_file(import_path)?;
-        print_success(format!("Imported {} storage entries", imported.len()));
-        initial_storage = Some(serde_json::to_string(&imported)?);
-    }
+    let initial_storage = load_initial_storage(&args)?;
 
     if let Some(n) = args.repeat {
         logging::log_repeat_execution(&args.function, n as usize);
-        let runner = RepeatRunner::new(wasm_bytes, args.breakpoint, initial_storage);
+        let mut runner = RepeatRunner::new(wasm_bytes, args.breakpoint, initial_storage);
+        if let Some(loader) = network_snapshot {
+            runner = runner.with_network_snapshot(loader);
+        }
+        if let Some(manifest) = manifest {
+            runner = runner.with_manifest(manifest);
+        }
         let stats = runner.run(&args.function, parsed_args.as_deref(), n)?;
         stats.display();
         return Ok(());
@@ -176,7 +257,11 @@ pub fn run(args: RunArgs, _verbosity: Verbosity) -> Result<()> {
     }
     logging::log_execution_start(&args.function, parsed_args.as_deref());
 
-    let mut executor = ContractExecutor::new(wasm_bytes.clone())?;
+    let mut executor = create_executor(
+        wasm_bytes.clone(),
+        network_snapshot.as_ref(),
+        manifest.as_ref(),
+    )?;
     if let Some(storage) = initial_storage {
         executor.set_initial_storage(storage)?;
     }
@@ -187,37 +272,65 @@ pub fn run(args: RunArgs, _verbosity: Verbosity) -> Result<()> {
     let mut memory_tracker = crate::inspector::budget::MemoryTracker::new(initial_memory);
     let mut instruction_counter = crate::inspector::instructions::InstructionCounter::new();
 
-    let mut engine = DebuggerEngine::new(executor, args.breakpoint);
+    let mut engine = DebuggerEngine::new(executor, args.breakpoint.clone());
+    for spec in &args.watch {
+        engine.watchpoints_mut().add(spec)?;
+    }
 
     if args.generate_test {
         engine.enable_test_generation(args.test_output_dir);
     }
 
-    // Execute with debugging
-    println!("\n--- Execution Start ---\n");
-    let execution_result = engine.execute(&args.function, parsed_args.as_deref())?;
-    println!("\n--- Execution Complete ---\n");
+    let backend: ExecutionBackend = args.backend.parse()?;
+    let log_host_calls = args.verbose || verbosity == Verbosity::Verbose;
+    if backend == ExecutionBackend::Interpreter {
+        use_interpreter(
+            &mut engine,
+            &wasm_bytes,
+            args.step_instructions,
+            args.break_on_error,
+        )?;
+        if log_host_calls {
+            engine.enable_host_call_log();
+        }
+        if args.record.is_some() {
+            engine.enable_recording();
+        }
+    } else {
+        let primary = engine.executor().contract_id();
+        if breakpoints.iter().any(|bp| {
+            matches!(split_qualified(&bp.location), (Some(contract), _) if contract != primary)
+        }) {
+            print_warning("Breakpoints in other contracts only fire with --backend interpreter");
+        }
+        if breakpoints.iter().any(|bp| bp.host_function().is_none()) {
+            print_warning(
+                "With the host backend, breakpoints only match the called function and do not \
+                 pause; nested calls and pausing need --backend interpreter",
+            );
+        }
+        if !args.watch.is_empty() {
+            print_warning("Watchpoints only fire with --backend interpreter");
+        }
+        if breakpoints.iter().any(|bp| bp.host_function().is_some()) {
+            print_warning("Host function breakpoints only fire with --backend interpreter");
+        }
+        if args.break_on_error {
+            print_warning("--break-on-error only pauses with --backend interpreter");
+        }
+        if args.record.is_some() {
+            print_warning("--record only records with --backend interpreter");
+        }
+        if log_host_calls {
+            print_warning("Host calls are only listed with --backend interpreter");
+        }
+    }
 
-    if args.json {
-        let json_output = serde_json::json!({
-            "result": execution_result.result,
-            "execution_time_ms": execution_result.execution_time_ms,
-        });
-        println!("{}", serde_json::to_string_pretty(&json_output)?);
-    if args.instruction_debug {
+    if args.instruction_debug && backend == ExecutionBackend::Interpreter {
+        print_warning("--instruction-debug is ignored: the interpreter backend steps live");
+    } else if args.instruction_debug {
         print_info("Enabling instruction-level debugging...");
         engine.enable_instruction_debug(&wasm_bytes)?;
-
-        if args.step_instructions {
-            let step_mode = parse_step_mode(&args.step_mode);
-            print_info(format!(
-                "Starting instruction stepping in '{}' mode",
-                args.step_mode
-            ));
-            engine.start_instruction_stepping(step_mode)?;
-            run_instruction_stepping(&mut engine, &args.function, parsed_args.as_deref())?;
-            return Ok(());
-        }
     }
 
     print_info("\n--- Execution Start ---\n");
@@ -226,6 +339,39 @@ pub fn run(args: RunArgs, _verbosity: Verbosity) -> Result<()> {
     let result = engine.execute(&args.function, parsed_args.as_deref())?;
     instruction_counter.end_function(engine.executor().host());
 
+    if let (Some(path), Some(recording)) = (&args.record, engine.recording()) {
+        recording.save(path)?;
+        print_success(format!(
+            "Recorded {} host calls to {:?}",
+            recording.steps.len(),
+            path
+        ));
+    }
+
+    if args.json {
+        let json_output = serde_json::json!({
+            "result": result
+                .value
+                .clone()
+                .unwrap_or_else(|| serde_json::Value::String(result.result.clone())),
+            "error": result.error,
+            "execution_time_ms": result.execution_time_ms,
+        });
+        println!("{}", serde_json::to_string_pretty(&json_output)?);
+    }
+
+    if args.instruction_debug && args.step_instructions && backend != ExecutionBackend::Interpreter
+    {
+        let step_mode = parse_step_mode(&args.step_mode);
+        print_info(format!(
+            "Starting instruction stepping in '{}' mode",
+            args.step_mode
+        ));
+        engine.start_instruction_stepping(step_mode)?;
+        run_instruction_stepping(&mut engine, &result)?;
+        return Ok(());
+    }
+
     if let Ok(diagnostic_events) = engine.executor().get_diagnostic_events() {
         let mut previous_memory = initial_memory;
         for (idx, _event) in diagnostic_events.iter().enumerate() {
@@ -245,14 +391,18 @@ pub fn run(args: RunArgs, _verbosity: Verbosity) -> Result<()> {
 
     memory_tracker.record_snapshot(engine.executor().host(), "after_execution");
     print_success("\n--- Execution Complete ---\n");
-    print_success(format!("Result: {:?}", result));
-    logging::log_execution_complete(&result);
+    if let Some(host_calls) = engine.host_calls() {
+        display_host_calls(host_calls);
+    }
+    print_success(format!("Result: {}", result.result));
+    logging::log_execution_complete(&result.result);
 
     // Export storage if specified
     if let Some(export_path) = &args.export_storage {
         print_info(format!("Exporting storage to: {:?}", export_path));
         let storage_snapshot = engine.executor().get_storage_snapshot()?;
-        crate::inspector::storage::StorageState::export_to_file(&storage_snapshot, export_path)?;
+        crate::inspector::storage::StorageState::from_entries(&storage_snapshot)
+            .save(export_path)?;
         print_success(format!(
             "Exported {} storage entries",
             storage_snapshot.len()
@@ -283,9 +433,13 @@ pub fn run(args: RunArgs, _verbosity: Verbosity) -> Result<()> {
                 }
                 print_info(format!(
                     "  Contract: {}",
-                    event.contract_id.as_deref().unwrap_or("<none>")
+                    event
+                        .contract_id
+                        .as_deref()
+                        .map(|id| engine.executor().describe_contract(id))
+                        .unwrap_or_else(|| "<none>".to_string())
                 ));
-                print_info(format!("  Topics: {:?}", event.topics));
+                print_info(format!("  Topics: [{}]", event.topics_text()));
                 print_info(format!("  Data: {}", event.data));
             }
         }
@@ -298,9 +452,26 @@ pub fn run(args: RunArgs, _verbosity: Verbosity) -> Result<()> {
             .map_err(|e| anyhow::anyhow!("Invalid storage filter: {}", e))?;
 
         print_info("\n--- Storage ---");
-        let inspector = crate::inspector::StorageInspector::new();
-        inspector.display_filtered(&storage_filter);
-        print_info("(Storage view is currently placeholder data)");
+        let executor = engine.executor();
+        let entries: Vec<_> = executor
+            .get_workspace_storage_snapshot()?
+            .into_iter()
+            .filter(|entry| storage_filter.matches(&entry.key))
+            .collect();
+        if entries.is_empty() {
+            print_warning(format!(
+                "No storage entries matched the filter: {}",
+                storage_filter.summary()
+            ));
+        } else {
+            StorageInspector::display_entries_by_contract(&entries, |id| {
+                executor.describe_contract(id)
+            });
+        }
+    }
+
+    if !args.watch.is_empty() && backend == ExecutionBackend::Interpreter {
+        engine.storage_accesses().display_access_report();
     }
 
     let mut json_auth = None;
@@ -359,7 +530,7 @@ pub fn run(args: RunArgs, _verbosity: Verbosity) -> Result<()> {
         content
     } else {
         let mut text_output = Vec::new();
-        text_output.push(format!("Result: {:?}", result));
+        text_output.push(format!("Result: {}", result.result));
 
         let memory_text = format!(
             "\n=== Memory Allocation Summary ===\nPeak Memory Usage: {} bytes\nAllocation Count: {}\nTotal Allocated Bytes: {} bytes\nInitial Memory: {} bytes\nFinal Memory: {} bytes\nMemory Delta: {} bytes",
@@ -380,7 +551,7 @@ pub fn run(args: RunArgs, _verbosity: Verbosity) -> Result<()> {
                     "  Contract: {}",
                     event.contract_id.as_deref().unwrap_or("<none>")
                 ));
-                text_output.push(format!("  Topics: {:?}", event.topics));
+                text_output.push(format!("  Topics: [{}]", event.topics_text()));
                 text_output.push(format!("  Data: {}", event.data));
             }
         }
@@ -409,6 +580,9 @@ pub fn run(args: RunArgs, _verbosity: Verbosity) -> Result<()> {
 }
 
 /// Execute run command in dry-run mode.
+///
+/// The executor is built like [`run`]'s; the storage changes of every
+/// contract are reported, then rolled back.
 fn run_dry_run(args: &RunArgs) -> Result<()> {
     print_info(format!("[DRY RUN] Loading contract: {:?}", args.contract));
 
@@ -420,15 +594,8 @@ fn run_dry_run(args: &RunArgs) -> Result<()> {
         wasm_bytes.len()
     ));
 
-    if let Some(snapshot_path) = &args.network_snapshot {
-        print_info(format!(
-            "\n[DRY RUN] Loading network snapshot: {:?}",
-            snapshot_path
-        ));
-        let loader = SnapshotLoader::from_file(snapshot_path)?;
-        let loaded_snapshot = loader.apply_to_environment()?;
-        print_info(format!("[DRY RUN] {}", loaded_snapshot.format_summary()));
-    }
+    let network_snapshot = load_network_snapshot(args.network_snapshot.as_deref())?;
+    let manifest = load_manifest(args.manifest.as_deref())?;
 
     let parsed_args = if let Some(args_json) = &args.args {
         Some(parse_args(args_json)?)
@@ -436,13 +603,42 @@ fn run_dry_run(args: &RunArgs) -> Result<()> {
         None
     };
 
-    let initial_storage = if let Some(storage_json) = &args.storage {
-        Some(parse_storage(storage_json)?)
-    } else {
-        println!("Result: {}", execution_result.result);
-        println!("Execution Time: {:.2}ms", execution_result.execution_time_ms);
+    let initial_storage = load_initial_storage(args)?;
+
+    let mut executor = create_executor(wasm_bytes, network_snapshot.as_ref(), manifest.as_ref())?;
+    if let Some(storage) = initial_storage {
+        executor.set_initial_storage(storage)?;
     }
 
+    let checkpoint = executor.snapshot_storage()?;
+
+    print_info("\n[DRY RUN] Starting debugger...");
+    print_info(format!("[DRY RUN] Function: {}", args.function));
+    if let Some(ref parsed) = parsed_args {
+        print_info(format!("[DRY RUN] Arguments: {}", parsed));
+    }
+
+    let mut engine = DebuggerEngine::new(executor, args.breakpoint.clone());
+    let report = engine.execute_with_report(&args.function, parsed_args.as_deref())?;
+
+    print_success("\n[DRY RUN] --- Execution Complete ---\n");
+    println!("[DRY RUN] Result: {}", report.result.result);
+    println!(
+        "[DRY RUN] Execution Time: {:.2}ms",
+        report.result.execution_time_ms
+    );
+    println!(
+        "[DRY RUN] Budget: {} CPU instructions, {} bytes memory",
+        report.cpu_instructions, report.memory_bytes
+    );
+    StorageInspector::display_diff(&report.storage);
+
+    engine.executor_mut().restore_storage(&checkpoint)?;
+    print_success(format!(
+        "\n[DRY RUN] Rolled back to checkpoint ({} ledger entries); no changes were kept",
+        checkpoint.ledger_entry_count()
+    ));
+
     Ok(())
 }
 
@@ -463,27 +659,78 @@ pub fn interactive(args: InteractiveArgs, _verbosity: Verbosity) -> Result<()> {
     ));
     logging::log_contract_loaded(wasm_bytes.len());
 
-    if let Some(snapshot_path) = &args.network_snapshot {
-        print_info(format!("\nLoading network snapshot: {:?}", snapshot_path));
-        logging::log_loading_snapshot(&snapshot_path.to_string_lossy());
-        let loader = SnapshotLoader::from_file(snapshot_path)?;
-        let loaded_snapshot = loader.apply_to_environment()?;
-        logging::log_display(loaded_snapshot.format_summary(), logging::LogLevel::Info);
-    }
+    let network_snapshot = load_network_snapshot(args.network_snapshot.as_deref())?;
 
-    let executor = ContractExecutor::new(wasm_bytes)?;
-    let engine = DebuggerEngine::new(executor, vec![]);
+    let manifest = load_manifest(args.manifest.as_deref())?;
+    let executor = create_executor(
+        wasm_bytes.clone(),
+        network_snapshot.as_ref(),
+        manifest.as_ref(),
+    )?;
+    let mut engine = DebuggerEngine::new(executor, vec![]);
 
-    print_info("\nStarting interactive mode...");
-    print_info("Type 'help' for available commands\n");
-    logging::log_interactive_mode_start();
+    let backend: ExecutionBackend = args.backend.parse()?;
+    if backend == ExecutionBackend::Interpreter {
+        use_interpreter(&mut engine, &wasm_bytes, false, args.break_on_error)?;
+    } else if args.break_on_error {
+        print_warning("--break-on-error only pauses with --backend interpreter");
+    }
 
+    // The full-screen interface needs a terminal to draw on and read keys from
+    let full_screen = !args.plain && io::stdin().is_terminal() && io::stdout().is_terminal();
+    logging::log_interactive_mode_start();
     let mut ui = DebuggerUI::new(engine)?;
-    ui.run()?;
+    if full_screen {
+        ui.run_full_screen(&wasm_bytes)?;
+    } else {
+        print_info("\nStarting interactive mode...");
+        print_info("Type 'help' for available commands\n");
+        ui.run()?;
+    }
+
+    Ok(())
+}
 
+/// Run calls in the interpreter, pausing at the console prompt. With
+/// `step_on_entry` every call pauses on its first instruction, with
+/// `break_on_error` at the point where a call fails.
+fn use_interpreter(
+    engine: &mut DebuggerEngine,
+    wasm_bytes: &[u8],
+    step_on_entry: bool,
+    break_on_error: bool,
+) -> Result<()> {
+    engine.enable_interpreter(wasm_bytes)?;
+    if let Some(interpreter) = engine.interpreter_mut() {
+        interpreter.set_step_on_entry(step_on_entry);
+        interpreter.set_break_on_error(break_on_error);
+    }
+    engine.set_pause_handler(PausePrompt::new());
+    print_info("Using the interpreter backend: breakpoints pause inside the call");
     Ok(())
 }
 
+/// Execute the dap command. Stdout carries the protocol, so nothing else
+/// is printed to it.
+pub fn dap(args: DapArgs) -> Result<()> {
+    match args.port {
+        Some(port) => crate::dap::serve_tcp(port),
+        None => crate::dap::serve_stdio(),
+    }
+}
+
+/// Execute the rpc command. Stdout carries the protocol, so nothing else
+/// is printed to it.
+pub fn rpc(args: RpcArgs) -> Result<()> {
+    match args.socket {
+        #[cfg(unix)]
+        Some(path) => crate::rpc::serve_unix(&path),
+        #[cfg(not(unix))]
+        Some(_) => anyhow::bail!("--socket needs a platform with Unix sockets"),
+        None => crate::rpc::serve_stdio(),
+    }
+}
+
 /// Execute the inspect command.
 pub fn inspect(args: InspectArgs, _verbosity: Verbosity) -> Result<()> {
     print_info(format!("Inspecting contract: {:?}", args.contract));
@@ -522,6 +769,27 @@ pub fn inspect(args: InspectArgs, _verbosity: Verbosity) -> Result<()> {
         }
     }
 
+    let spec = crate::utils::ContractSpec::from_wasm(&wasm_bytes)?;
+    let error_enums: Vec<_> = spec.error_enums().collect();
+    if !error_enums.is_empty() {
+        println!("\n{}", "-".repeat(54));
+        println!("  Contract Errors");
+        println!("{}", "-".repeat(54));
+
+        for error_enum in error_enums {
+            let enum_name = error_enum.name.to_utf8_string_lossy();
+            for case in error_enum.cases.iter() {
+                let doc = case.doc.to_utf8_string_lossy();
+                let label = format!("{}::{}", enum_name, case.name.to_utf8_string_lossy());
+                if doc.trim().is_empty() {
+                    println!("  {:>4}  {}", case.value, label);
+                } else {
+                    println!("  {:>4}  {}  - {}", case.value, label, doc.trim());
+                }
+            }
+        }
+    }
+
     if args.metadata {
         println!("\n{}", "-".repeat(54));
         println!("  Contract Metadata");
@@ -613,13 +881,7 @@ pub fn optimize(args: OptimizeArgs, _verbosity: Verbosity) -> Result<()> {
     ));
     logging::log_contract_loaded(wasm_bytes.len());
 
-    if let Some(snapshot_path) = &args.network_snapshot {
-        print_info(format!("\nLoading network snapshot: {:?}", snapshot_path));
-        logging::log_loading_snapshot(&snapshot_path.to_string_lossy());
-        let loader = SnapshotLoader::from_file(snapshot_path)?;
-        let loaded_snapshot = loader.apply_to_environment()?;
-        logging::log_display(loaded_snapshot.format_summary(), logging::LogLevel::Info);
-    }
+    let network_snapshot = load_network_snapshot(args.network_snapshot.as_deref())?;
 
     let functions_to_analyze = if args.function.is_empty() {
         print_warning("No functions specified, analyzing all exported functions...");
@@ -628,7 +890,7 @@ pub fn optimize(args: OptimizeArgs, _verbosity: Verbosity) -> Result<()> {
         args.function.clone()
     };
 
-    let mut executor = ContractExecutor::new(wasm_bytes)?;
+    let mut executor = create_executor(wasm_bytes, network_snapshot.as_ref(), None)?;
     if let Some(storage_json) = &args.storage {
         let storage = parse_storage(storage_json)?;
         executor.set_initial_storage(storage)?;
@@ -815,13 +1077,42 @@ pub fn compare(args: CompareArgs) -> Result<()> {
     Ok(())
 }
 
-/// Run instruction-level stepping mode.
-fn run_instruction_stepping(
-    engine: &mut DebuggerEngine,
-    function: &str,
-    args: Option<&str>,
-) -> Result<()> {
+/// Execute the replay command.
+pub fn replay(args: ReplayArgs) -> Result<()> {
+    let mut breakpoints = BreakpointManager::new();
+    for spec in &args.breakpoint {
+        breakpoints.add_spec(spec)?;
+    }
+    let mut watchpoints = WatchpointManager::new();
+    for spec in &args.watch {
+        watchpoints.add(spec)?;
+    }
+
+    print_info(format!("Loading recording: {:?}", args.recording));
+    let recording = Recording::load(&args.recording)?;
+    print_info(format!(
+        "{} of {} in {}: {} host calls",
+        recording.function,
+        recording.args.as_deref().unwrap_or("[]"),
+        recording.contract_id,
+        recording.steps.len()
+    ));
+    print_info("Type 'help' for available commands");
+
+    ReplayPrompt::new(Replay::new(recording), breakpoints, watchpoints).run();
+    Ok(())
+}
+
+/// Run instruction-level stepping mode over the path `result`'s call took.
+fn run_instruction_stepping(engine: &mut DebuggerEngine, result: &ExecutionResult) -> Result<()> {
+    let recorded = engine
+        .state()
+        .lock()
+        .map(|state| state.trace().len())
+        .unwrap_or(0);
+
     println!("\n=== Instruction Stepping Mode ===");
+    println!("Recorded {} executed instructions", recorded);
     println!("Type 'help' for available commands\n");
 
     display_instruction_context(engine, 3);
@@ -835,7 +1126,23 @@ fn run_instruction_stepping(
         let input = input.trim().to_lowercase();
 
         match input.as_str() {
-            "n" | "next" | "s" | "step" | "into" | "" => match engine.step_into() {
+            "n" | "next" => match engine.step_over_line() {
+                Ok(true) => {
+                    println!("{}", stepped_to(engine, "Stepped to next source line"));
+                    display_instruction_context(engine, 3);
+                }
+                Ok(false) => println!("Cannot step: execution finished or error occurred"),
+                Err(e) => println!("Error stepping: {}", e),
+            },
+            "s" | "step" => match engine.step_into_line() {
+                Ok(true) => {
+                    println!("{}", stepped_to(engine, "Stepped into next source line"));
+                    display_instruction_context(engine, 3);
+                }
+                Ok(false) => println!("Cannot step: execution finished or error occurred"),
+                Err(e) => println!("Error stepping: {}", e),
+            },
+            "into" | "" => match engine.step_into() {
                 Ok(true) => {
                     println!("Stepped to next instruction");
                     display_instruction_context(engine, 3);
@@ -880,8 +1187,7 @@ fn run_instruction_stepping(
             "c" | "continue" => {
                 println!("Continuing execution...");
                 engine.continue_execution()?;
-                let result = engine.execute(function, args)?;
-                println!("Execution completed. Result: {:?}", result);
+                println!("Execution completed. Result: {}", result.result);
                 break;
             }
             "i" | "info" => display_instruction_info(engine),
@@ -910,9 +1216,19 @@ fn run_instruction_stepping(
     Ok(())
 }
 
+/// Message for a line step, which steps by instruction without debug info
+fn stepped_to(engine: &DebuggerEngine, message: &str) -> String {
+    if engine.source_map().is_some() {
+        message.to_string()
+    } else {
+        "Stepped by instruction: the contract has no debug info".to_string()
+    }
+}
+
 fn display_instruction_context(engine: &DebuggerEngine, context_size: usize) {
     let context = engine.get_instruction_context(context_size);
-    let formatted = Formatter::format_instruction_context(&context, context_size);
+    let formatted =
+        Formatter::format_instruction_context(&context, context_size, engine.source_map());
     println!("{}", formatted);
 }
 
@@ -935,13 +1251,15 @@ fn display_instruction_info(engine: &DebuggerEngine) {
             )
         );
 
+        // Progress through the recorded trace when there is one
+        let (total, current) = if state.trace().is_empty() {
+            (state.instructions().len(), ip.current_index())
+        } else {
+            (state.trace().len(), ip.trace_position())
+        };
         println!(
             "{}",
-            Formatter::format_instruction_stats(
-                state.instructions().len(),
-                ip.current_index(),
-                state.step_count(),
-            )
+            Formatter::format_instruction_stats(total, current, state.step_count())
         );
 
         if let Some(current_inst) = state.current_instruction() {
@@ -965,6 +1283,8 @@ fn parse_step_mode(step_mode: &str) -> StepMode {
         "over" => StepMode::StepOver,
         "out" => StepMode::StepOut,
         "block" => StepMode::StepBlock,
+        "line" => StepMode::StepOverLine,
+        "line-into" => StepMode::StepIntoLine,
         _ => StepMode::StepInto,
     }
 }
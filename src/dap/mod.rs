@@ -0,0 +1,30 @@
+//! Debug Adapter Protocol server
+//!
+//! `soroban-debug dap` lets any DAP client (VS Code, nvim-dap, Helix) drive
+//! the debugger over stdio or a TCP connection. See [`Session`] for the
+//! requests it serves.
+
+pub mod protocol;
+pub mod session;
+
+pub use protocol::{Connection, Request};
+pub use session::Session;
+
+use crate::Result;
+use std::io::{self, BufReader};
+use std::net::TcpListener;
+
+/// Serve one client over stdin and stdout
+pub fn serve_stdio() -> Result<()> {
+    Session::new(Connection::new(io::stdin().lock(), io::stdout())).run()
+}
+
+/// Wait for one client on `127.0.0.1:port` and serve it
+pub fn serve_tcp(port: u16) -> Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    eprintln!("Listening for a DAP client on {}", listener.local_addr()?);
+    let (stream, peer) = listener.accept()?;
+    tracing::info!("DAP client connected from {}", peer);
+    let reader = BufReader::new(stream.try_clone()?);
+    Session::new(Connection::new(reader, stream)).run()
+}
@@ -0,0 +1,213 @@
+//! Message framing and envelopes of the Debug Adapter Protocol
+//!
+//! Every message is a JSON object preceded by a `Content-Length` header and
+//! a blank line. Requests come from the client; the adapter answers each
+//! with a response carrying the request's `seq`, and sends events of its own.
+
+use crate::{DebuggerError, Result};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+
+const CONTENT_LENGTH: &str = "Content-Length:";
+
+/// A request read from the client
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub seq: i64,
+    pub command: String,
+    /// The request's `arguments`, or `null` if it has none
+    pub arguments: Value,
+}
+
+impl Request {
+    fn from_message(message: Value) -> Result<Self> {
+        let seq = message.get("seq").and_then(Value::as_i64).unwrap_or(0);
+        let command = message
+            .get("command")
+            .and_then(Value::as_str)
+            .ok_or_else(|| protocol_error("request without a command"))?
+            .to_string();
+        let arguments = message.get("arguments").cloned().unwrap_or(Value::Null);
+        Ok(Self {
+            seq,
+            command,
+            arguments,
+        })
+    }
+
+    /// A string argument, if present
+    pub fn str_arg(&self, name: &str) -> Option<&str> {
+        self.arguments.get(name).and_then(Value::as_str)
+    }
+
+    /// An integer argument, if present
+    pub fn i64_arg(&self, name: &str) -> Option<i64> {
+        self.arguments.get(name).and_then(Value::as_i64)
+    }
+}
+
+/// Both directions of a DAP connection
+pub struct Connection {
+    reader: Box<dyn BufRead>,
+    writer: Box<dyn Write>,
+    /// Sequence number of the last message sent
+    seq: i64,
+}
+
+impl Connection {
+    pub fn new(reader: impl BufRead + 'static, writer: impl Write + 'static) -> Self {
+        Self {
+            reader: Box::new(reader),
+            writer: Box::new(writer),
+            seq: 0,
+        }
+    }
+
+    /// Read the next request, or `None` once the client has closed the
+    /// connection. Responses and events from the client are skipped.
+    pub fn read_request(&mut self) -> Result<Option<Request>> {
+        loop {
+            let Some(message) = read_message(&mut self.reader)? else {
+                return Ok(None);
+            };
+            if message.get("type").and_then(Value::as_str) == Some("request") {
+                return Request::from_message(message).map(Some);
+            }
+        }
+    }
+
+    /// Answer a request successfully
+    pub fn respond(&mut self, request: &Request, body: Value) -> Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request.seq,
+            "success": true,
+            "command": request.command,
+            "body": body,
+        }))
+    }
+
+    /// Answer a request with an error the client shows to the user
+    pub fn respond_error(&mut self, request: &Request, message: &str) -> Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": request.seq,
+            "success": false,
+            "command": request.command,
+            "message": message,
+            "body": { "error": { "id": 1, "format": message, "showUser": true } },
+        }))
+    }
+
+    /// Send an event
+    pub fn event(&mut self, event: &str, body: Value) -> Result<()> {
+        self.send(json!({ "type": "event", "event": event, "body": body }))
+    }
+
+    /// Send text for the client's debug console
+    pub fn output(&mut self, category: &str, text: &str) -> Result<()> {
+        self.event(
+            "output",
+            json!({ "category": category, "output": format!("{}\n", text) }),
+        )
+    }
+
+    fn send(&mut self, mut message: Value) -> Result<()> {
+        self.seq += 1;
+        message["seq"] = json!(self.seq);
+        write_message(&mut self.writer, &message)
+    }
+}
+
+/// Read one framed message, or `None` at the end of the stream
+pub fn read_message(reader: &mut impl BufRead) -> Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return match length {
+                None => Ok(None),
+                Some(_) => Err(protocol_error("stream ended inside a message header")),
+            };
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some(value) = line.strip_prefix(CONTENT_LENGTH) {
+            let value = value.trim();
+            length = Some(
+                value
+                    .parse::<usize>()
+                    .map_err(|_| protocol_error(&format!("bad Content-Length '{}'", value)))?,
+            );
+        }
+    }
+
+    let mut body = vec![0; length.unwrap_or(0)];
+    reader.read_exact(&mut body)?;
+    let message = serde_json::from_slice(&body)
+        .map_err(|e| protocol_error(&format!("message is not JSON: {}", e)))?;
+    Ok(Some(message))
+}
+
+/// Write one framed message
+pub fn write_message(writer: &mut impl Write, message: &Value) -> Result<()> {
+    let body = serde_json::to_string(message)?;
+    write!(writer, "{} {}\r\n\r\n{}", CONTENT_LENGTH, body.len(), body)?;
+    writer.flush()?;
+    Ok(())
+}
+
+fn protocol_error(problem: &str) -> anyhow::Error {
+    DebuggerError::ExecutionError(format!("DAP protocol error: {}", problem)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_round_trips_framed_messages() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &json!({"seq": 1, "type": "request"})).unwrap();
+        write_message(&mut buffer, &json!({"seq": 2, "text": "héllo"})).unwrap();
+        assert!(buffer.starts_with(b"Content-Length: 26\r\n\r\n{"));
+
+        let mut reader = Cursor::new(buffer);
+        let first = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(first["seq"], 1);
+        let second = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(second["text"], "héllo");
+        assert!(read_message(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rejects_bad_headers() {
+        let mut reader = Cursor::new(b"Content-Length: ten\r\n\r\n{}".to_vec());
+        assert!(read_message(&mut reader).is_err());
+        let mut reader = Cursor::new(b"Content-Length: 2\r\n".to_vec());
+        assert!(read_message(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_skips_client_responses() {
+        let mut input = Vec::new();
+        write_message(&mut input, &json!({"seq": 1, "type": "response"})).unwrap();
+        write_message(
+            &mut input,
+            &json!({"seq": 2, "type": "request", "command": "threads"}),
+        )
+        .unwrap();
+        let mut connection = Connection::new(Cursor::new(input), Vec::new());
+        let request = connection.read_request().unwrap().unwrap();
+        assert_eq!(request.seq, 2);
+        assert_eq!(request.command, "threads");
+        assert!(request.arguments.is_null());
+        assert!(connection.read_request().unwrap().is_none());
+    }
+}
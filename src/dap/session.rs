@@ -0,0 +1,1010 @@
+//! A debug session driven by a DAP client
+//!
+//! The launched call runs in the interpreter, so breakpoints and steps pause
+//! inside it with live state. While the call is paused, [`DapPause`] serves
+//! the client's requests; before and after it, [`Session`] does.
+
+use crate::dap::protocol::{Connection, Request};
+use crate::debugger::condition::{evaluate_path, Condition, ConditionScope};
+use crate::debugger::{Breakpoint, DebuggerEngine};
+use crate::inspector::events::EventInspector;
+use crate::inspector::StorageInspector;
+use crate::runtime::executor::ContractExecutor;
+use crate::runtime::interpreter::{
+    BreakpointChanges, PauseContext, PauseHandler, PauseReason, ResumeAction, WasmValue,
+};
+use crate::runtime::manifest::ContractManifest;
+use crate::runtime::{Instruction, InstructionParser};
+use crate::simulator::SnapshotLoader;
+use crate::ui::pause_prompt::{describe_access, describe_reason, describe_trap, frame_name};
+use crate::utils::scval::scval_to_json;
+use crate::utils::{ContractSpec, SourceLocation, SourceMap};
+use crate::Result;
+use anyhow::Context;
+use serde_json::{json, Value};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+/// A call runs on a single thread
+const THREAD_ID: i64 = 1;
+/// Exception breakpoint filter that pauses where a call fails
+const ERROR_FILTER: &str = "error";
+/// Why line breakpoints set before `launch` are not bound yet
+const NOT_LAUNCHED: &str = "Bound once the contract is launched";
+/// Why line breakpoints in a contract without line tables never bind
+const NO_LINE_TABLES: &str =
+    "The contract has no DWARF line tables; build it with debug info or use function breakpoints";
+
+/// Serves one client from `initialize` to `disconnect`
+pub struct Session {
+    connection: Rc<RefCell<Connection>>,
+    /// Set once the client disconnects, possibly while the call is paused
+    disconnected: Rc<Cell<bool>>,
+    engine: Option<DebuggerEngine>,
+    launch: Option<Launch>,
+    function_breakpoints: Vec<Breakpoint>,
+    source_breakpoints: Rc<RefCell<SourceBreakpoints>>,
+    break_on_error: bool,
+    configured: bool,
+    ran: bool,
+}
+
+/// The call a `launch` request asks for
+struct Launch {
+    function: String,
+    args: Option<String>,
+    stop_on_entry: bool,
+}
+
+impl Session {
+    pub fn new(connection: Connection) -> Self {
+        Self {
+            connection: Rc::new(RefCell::new(connection)),
+            disconnected: Rc::new(Cell::new(false)),
+            engine: None,
+            launch: None,
+            function_breakpoints: Vec::new(),
+            source_breakpoints: Rc::new(RefCell::new(SourceBreakpoints::default())),
+            break_on_error: false,
+            configured: false,
+            ran: false,
+        }
+    }
+
+    /// Serve requests until the client disconnects or closes the connection
+    pub fn run(mut self) -> Result<()> {
+        loop {
+            let request = self.connection.borrow_mut().read_request()?;
+            let Some(request) = request else {
+                return Ok(());
+            };
+            self.handle(&request)?;
+            if self.disconnected.get() {
+                return Ok(());
+            }
+        }
+    }
+
+    fn handle(&mut self, request: &Request) -> Result<()> {
+        match request.command.as_str() {
+            "initialize" => {
+                self.respond(request, capabilities())?;
+                self.connection.borrow_mut().event("initialized", json!({}))
+            }
+            "launch" => {
+                match self.launch(request) {
+                    Ok(()) => self.respond(request, Value::Null)?,
+                    Err(e) => return self.respond_error(request, &format!("{:#}", e)),
+                }
+                // Line breakpoints set before the launch can bind now
+                let (breakpoints, _) = self
+                    .source_breakpoints
+                    .borrow()
+                    .bind_all(source_lines(self.engine.as_ref()));
+                for breakpoint in breakpoints {
+                    self.connection.borrow_mut().event(
+                        "breakpoint",
+                        json!({ "reason": "changed", "breakpoint": breakpoint }),
+                    )?;
+                }
+                // Clients may finish configuring before launching
+                if self.configured {
+                    self.run_call()?;
+                }
+                Ok(())
+            }
+            "setBreakpoints" => {
+                let body = self
+                    .source_breakpoints
+                    .borrow_mut()
+                    .set(request, source_lines(self.engine.as_ref()));
+                self.respond(request, body)
+            }
+            "setFunctionBreakpoints" => {
+                let (breakpoints, body) = function_breakpoints(request);
+                self.function_breakpoints = breakpoints;
+                self.respond(request, body)
+            }
+            "setExceptionBreakpoints" => {
+                self.break_on_error = breaks_on_error(request);
+                self.respond(request, Value::Null)
+            }
+            "configurationDone" => {
+                self.respond(request, Value::Null)?;
+                self.configured = true;
+                if self.launch.is_some() {
+                    self.run_call()?;
+                }
+                Ok(())
+            }
+            "threads" => self.respond(request, threads()),
+            "stackTrace" => self.respond(request, json!({ "stackFrames": [], "totalFrames": 0 })),
+            "terminate" => {
+                self.respond(request, Value::Null)?;
+                self.connection.borrow_mut().event("terminated", json!({}))
+            }
+            "disconnect" => {
+                self.disconnected.set(true);
+                self.respond(request, Value::Null)
+            }
+            "continue" | "next" | "stepIn" | "stepOut" | "scopes" | "variables" | "evaluate"
+            | "disassemble" => self.respond_error(request, "The call is not paused"),
+            other => self.respond_error(request, &format!("Unsupported request '{}'", other)),
+        }
+    }
+
+    /// Load the contract named in a `launch` request and remember the call
+    fn launch(&mut self, request: &Request) -> Result<()> {
+        if self.engine.is_some() {
+            anyhow::bail!("A contract has already been launched in this session");
+        }
+        let contract = request
+            .str_arg("contractPath")
+            .context("launch needs a contractPath")?;
+        let function = request
+            .str_arg("entrypoint")
+            .context("launch needs an entrypoint")?;
+        let args = match request.arguments.get("args") {
+            None | Some(Value::Null) => None,
+            Some(Value::String(json)) => {
+                serde_json::from_str::<Value>(json)
+                    .with_context(|| format!("Invalid JSON arguments: {}", json))?;
+                Some(json.clone())
+            }
+            Some(args) => Some(args.to_string()),
+        };
+
+        let wasm_bytes = fs::read(contract)
+            .with_context(|| format!("Failed to read WASM file: {:?}", contract))?;
+        let mut executor = match request.str_arg("snapshotPath") {
+            Some(path) => {
+                let loader = SnapshotLoader::from_file(path)?;
+                ContractExecutor::with_network_snapshot(wasm_bytes.clone(), &loader)?.0
+            }
+            None => ContractExecutor::new(wasm_bytes.clone())?,
+        };
+        if let Some(path) = request.str_arg("manifestPath") {
+            executor.register_manifest(&ContractManifest::from_file(path)?)?;
+        }
+
+        let mut engine = DebuggerEngine::new(executor, vec![]);
+        engine.set_quiet(true);
+        engine.enable_interpreter(&wasm_bytes)?;
+        self.engine = Some(engine);
+        self.launch = Some(Launch {
+            function: function.to_string(),
+            args,
+            stop_on_entry: request
+                .arguments
+                .get("stopOnEntry")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+        });
+        Ok(())
+    }
+
+    /// Run the launched call, pausing as the client asks, then report how it
+    /// ended
+    fn run_call(&mut self) -> Result<()> {
+        let (Some(engine), Some(launch)) = (self.engine.as_mut(), self.launch.as_ref()) else {
+            return Ok(());
+        };
+        if self.ran {
+            return Ok(());
+        }
+        self.ran = true;
+
+        engine.breakpoints_mut().clear();
+        for breakpoint in &self.function_breakpoints {
+            engine.breakpoints_mut().insert(breakpoint.clone());
+        }
+        let (_, line_offsets) = self
+            .source_breakpoints
+            .borrow()
+            .bind_all(source_lines(Some(engine)));
+        let source_map = engine
+            .interpreter()
+            .and_then(|interpreter| interpreter.source_map())
+            .cloned();
+        if let Some(interpreter) = engine.interpreter_mut() {
+            interpreter.set_line_breakpoints(line_offsets);
+            interpreter.set_break_on_error(self.break_on_error);
+            interpreter.set_step_on_entry(launch.stop_on_entry);
+        }
+        engine.set_pause_handler(DapPause::new(
+            self.connection.clone(),
+            self.disconnected.clone(),
+            launch.stop_on_entry,
+            source_map,
+            self.source_breakpoints.clone(),
+        ));
+
+        let result = engine.execute(&launch.function, launch.args.as_deref());
+        if self.disconnected.get() {
+            return Ok(());
+        }
+
+        let mut connection = self.connection.borrow_mut();
+        let failed = match &result {
+            Ok(result) => {
+                connection.output("console", &format!("Result: {}", result.result))?;
+                result.result.starts_with("Error")
+            }
+            Err(e) => {
+                connection.output("stderr", &format!("Execution failed: {:#}", e))?;
+                true
+            }
+        };
+        if let Ok(events) = EventInspector::get_events(engine.executor().host()) {
+            for event in events {
+                connection.output(
+                    "console",
+                    &format!(
+                        "Event {} topics: [{}] data: {}",
+                        event.contract_id.as_deref().unwrap_or("<host>"),
+                        event.topics_text(),
+                        event.data
+                    ),
+                )?;
+            }
+        }
+        connection.event("exited", json!({ "exitCode": i32::from(failed) }))?;
+        connection.event("terminated", json!({}))
+    }
+
+    fn respond(&self, request: &Request, body: Value) -> Result<()> {
+        self.connection.borrow_mut().respond(request, body)
+    }
+
+    fn respond_error(&self, request: &Request, message: &str) -> Result<()> {
+        self.connection.borrow_mut().respond_error(request, message)
+    }
+}
+
+fn capabilities() -> Value {
+    json!({
+        "supportsConfigurationDoneRequest": true,
+        "supportsFunctionBreakpoints": true,
+        "supportsConditionalBreakpoints": true,
+        "supportsHitConditionalBreakpoints": true,
+        "supportsEvaluateForHovers": true,
+        "supportsDisassembleRequest": true,
+        "supportsSteppingGranularity": true,
+        "supportsTerminateRequest": true,
+        "exceptionBreakpointFilters": [{
+            "filter": ERROR_FILTER,
+            "label": "Call failures",
+            "description": "Pause where the call fails, before it unwinds",
+            "default": false,
+        }],
+    })
+}
+
+fn threads() -> Value {
+    json!({ "threads": [{ "id": THREAD_ID, "name": "contract call" }] })
+}
+
+/// The function breakpoints of a `setFunctionBreakpoints` request, and the
+/// response telling which of them are valid
+fn function_breakpoints(request: &Request) -> (Vec<Breakpoint>, Value) {
+    let requested = request
+        .arguments
+        .get("breakpoints")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    let mut breakpoints = Vec::new();
+    let results: Vec<Value> = requested
+        .iter()
+        .map(|spec| match function_breakpoint(spec) {
+            Ok(breakpoint) => {
+                breakpoints.push(breakpoint);
+                json!({ "verified": true })
+            }
+            Err(e) => json!({ "verified": false, "message": e.to_string() }),
+        })
+        .collect();
+    (breakpoints, json!({ "breakpoints": results }))
+}
+
+/// Whether a `setExceptionBreakpoints` request turns on the error filter
+fn breaks_on_error(request: &Request) -> bool {
+    request
+        .arguments
+        .get("filters")
+        .and_then(Value::as_array)
+        .is_some_and(|filters| filters.iter().any(|f| f == ERROR_FILTER))
+}
+
+/// A function breakpoint from `setFunctionBreakpoints`, with its condition
+/// and hit condition
+fn function_breakpoint(spec: &Value) -> Result<Breakpoint> {
+    let name = spec
+        .get("name")
+        .and_then(Value::as_str)
+        .filter(|name| !name.trim().is_empty())
+        .context("Function breakpoints need a function name")?;
+    let mut breakpoint = Breakpoint::new(name.trim());
+    if let Some(condition) = spec.get("condition").and_then(Value::as_str) {
+        breakpoint = breakpoint.with_condition(condition)?;
+    }
+    if let Some(hit_condition) = spec.get("hitCondition").and_then(Value::as_str) {
+        breakpoint = breakpoint.with_hit_condition(hit_condition)?;
+    }
+    Ok(breakpoint)
+}
+
+/// Line tables of the launched contract, for binding line breakpoints, or
+/// why there are none
+fn source_lines(engine: Option<&DebuggerEngine>) -> std::result::Result<&SourceMap, &'static str> {
+    let interpreter = engine
+        .and_then(DebuggerEngine::interpreter)
+        .ok_or(NOT_LAUNCHED)?;
+    interpreter.source_map().ok_or(NO_LINE_TABLES)
+}
+
+/// Line breakpoints the client set, by source path
+#[derive(Default)]
+struct SourceBreakpoints {
+    by_source: HashMap<String, Vec<SourceBreakpoint>>,
+    next_id: i64,
+}
+
+/// A line breakpoint from `setBreakpoints`
+struct SourceBreakpoint {
+    id: i64,
+    line: usize,
+    /// Whether it has a condition, hit condition or log message, which only
+    /// function breakpoints support
+    conditional: bool,
+}
+
+impl SourceBreakpoints {
+    /// Replace the breakpoints of the source a `setBreakpoints` request
+    /// names, answering how each of them binds
+    fn set(&mut self, request: &Request, lines: std::result::Result<&SourceMap, &str>) -> Value {
+        let path = request
+            .arguments
+            .get("source")
+            .and_then(|source| source.get("path"))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let requested = request
+            .arguments
+            .get("breakpoints")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        let breakpoints: Vec<SourceBreakpoint> = requested
+            .iter()
+            .map(|spec| {
+                self.next_id += 1;
+                SourceBreakpoint {
+                    id: self.next_id,
+                    line: spec.get("line").and_then(Value::as_u64).unwrap_or(0) as usize,
+                    conditional: ["condition", "hitCondition", "logMessage"]
+                        .iter()
+                        .any(|key| spec.get(key).is_some()),
+                }
+            })
+            .collect();
+        let results: Vec<Value> = breakpoints
+            .iter()
+            .map(|breakpoint| breakpoint.bind(&path, lines).0)
+            .collect();
+        self.by_source.insert(path, breakpoints);
+        json!({ "breakpoints": results })
+    }
+
+    /// Every line breakpoint as it binds, and the instruction offsets the
+    /// bound ones pause at
+    fn bind_all(&self, lines: std::result::Result<&SourceMap, &str>) -> (Vec<Value>, Vec<usize>) {
+        let mut results = Vec::new();
+        let mut offsets = Vec::new();
+        for (path, breakpoints) in &self.by_source {
+            for breakpoint in breakpoints {
+                let (result, bound) = breakpoint.bind(path, lines);
+                results.push(result);
+                offsets.extend(bound);
+            }
+        }
+        (results, offsets)
+    }
+}
+
+impl SourceBreakpoint {
+    /// The breakpoint as the client sees it once bound through the line
+    /// tables, and the offsets of the instructions it pauses at
+    fn bind(
+        &self,
+        path: &str,
+        lines: std::result::Result<&SourceMap, &str>,
+    ) -> (Value, Vec<usize>) {
+        let unverified = |message: &str| {
+            let breakpoint = json!({
+                "id": self.id,
+                "verified": false,
+                "line": self.line,
+                "message": message,
+            });
+            (breakpoint, Vec::new())
+        };
+        if self.conditional {
+            return unverified(
+                "Conditions, hit counts and log messages are only supported on function breakpoints",
+            );
+        }
+        if path.is_empty() {
+            return unverified("Line breakpoints need a source path");
+        }
+        let map = match lines {
+            Ok(map) => map,
+            Err(message) => return unverified(message),
+        };
+        match map.line_offsets(path, self.line) {
+            Some((line, offsets)) => (
+                json!({ "id": self.id, "verified": true, "line": line }),
+                offsets,
+            ),
+            None => unverified("No code at or after this line"),
+        }
+    }
+}
+
+/// Variables the client can expand, valid until the call resumes
+enum Container {
+    Locals,
+    Stack,
+    Globals,
+    Arguments,
+    /// Storage of a contract, by strkey
+    Storage(String),
+    /// Fields of a struct or map, or elements of a vec
+    Value(Value),
+}
+
+/// Answers the client's requests while the call is paused
+struct DapPause {
+    connection: Rc<RefCell<Connection>>,
+    disconnected: Rc<Cell<bool>>,
+    stop_on_entry: bool,
+    /// Whether the call has paused before
+    started: bool,
+    /// Decoded instructions by contract ID, for `disassemble`
+    instructions: HashMap<String, Vec<Instruction>>,
+    /// Contract specs by contract ID, for naming arguments
+    specs: HashMap<String, ContractSpec>,
+    /// Containers handed out since the pause, by variables reference - 1
+    containers: Vec<Container>,
+    /// Line tables of the launched contract, for line breakpoints
+    source_map: Option<SourceMap>,
+    source_breakpoints: Rc<RefCell<SourceBreakpoints>>,
+    /// Breakpoints the client changed during this pause
+    changes: BreakpointChanges,
+}
+
+impl DapPause {
+    fn new(
+        connection: Rc<RefCell<Connection>>,
+        disconnected: Rc<Cell<bool>>,
+        stop_on_entry: bool,
+        source_map: Option<SourceMap>,
+        source_breakpoints: Rc<RefCell<SourceBreakpoints>>,
+    ) -> Self {
+        Self {
+            connection,
+            disconnected,
+            stop_on_entry,
+            started: false,
+            instructions: HashMap::new(),
+            specs: HashMap::new(),
+            containers: Vec::new(),
+            source_map,
+            source_breakpoints,
+            changes: BreakpointChanges::default(),
+        }
+    }
+
+    fn stopped(&mut self, context: &PauseContext) -> Result<()> {
+        let first = !std::mem::replace(&mut self.started, true);
+        let reason = match context.reason() {
+            PauseReason::Step if first && self.stop_on_entry => "entry",
+            PauseReason::Step => "step",
+            PauseReason::Breakpoint | PauseReason::HostCall => "function breakpoint",
+            PauseReason::LineBreakpoint => "breakpoint",
+            PauseReason::Watchpoint => "data breakpoint",
+            PauseReason::Error => "exception",
+        };
+        let frame = context.current_frame();
+        let detail = context
+            .storage_access()
+            .map(describe_access)
+            .or_else(|| context.host_call().map(|call| format!("Calling {}", call)))
+            .or_else(|| context.trap().map(|trap| describe_trap(context, trap)));
+        self.connection.borrow_mut().event(
+            "stopped",
+            json!({
+                "reason": reason,
+                "description": format!(
+                    "{} in {}",
+                    describe_reason(context.reason()),
+                    frame_name(&frame)
+                ),
+                "text": detail,
+                "threadId": THREAD_ID,
+                "allThreadsStopped": true,
+            }),
+        )
+    }
+
+    /// Serve one request, returning how to resume if it resumes the call
+    fn serve(&mut self, context: &PauseContext, request: &Request) -> Result<Option<ResumeAction>> {
+        // Steps go by source line unless the client asks for instructions
+        let by_instruction =
+            request.arguments.get("granularity").and_then(Value::as_str) == Some("instruction");
+        let resume = match request.command.as_str() {
+            "continue" => Some(ResumeAction::Continue),
+            "next" if by_instruction => Some(ResumeAction::StepOver),
+            "next" => Some(ResumeAction::StepOverLine),
+            "stepIn" if by_instruction => Some(ResumeAction::StepInto),
+            "stepIn" => Some(ResumeAction::StepIntoLine),
+            "stepOut" => Some(ResumeAction::StepOut),
+            // Stop the call, then report it ended like any other
+            "terminate" => Some(ResumeAction::Abort),
+            "disconnect" => {
+                self.disconnected.set(true);
+                Some(ResumeAction::Abort)
+            }
+            _ => None,
+        };
+        if let Some(action) = resume {
+            let body = match action {
+                ResumeAction::Continue => json!({ "allThreadsContinued": true }),
+                _ => Value::Null,
+            };
+            self.connection.borrow_mut().respond(request, body)?;
+            return Ok(Some(action));
+        }
+
+        let body = match request.command.as_str() {
+            "threads" => Ok(threads()),
+            "pause" => Ok(Value::Null),
+            "stackTrace" => Ok(stack_trace(context, request)),
+            "scopes" => self.scopes(context, request),
+            "variables" => self.variables(context, request),
+            "evaluate" => self.evaluate(context, request),
+            "disassemble" => self.disassemble(context, request),
+            "setBreakpoints" => Ok(self.set_breakpoints(request)),
+            "setFunctionBreakpoints" => {
+                let (breakpoints, body) = function_breakpoints(request);
+                self.changes.functions =
+                    Some(breakpoints.iter().map(ToString::to_string).collect());
+                Ok(body)
+            }
+            "setExceptionBreakpoints" => {
+                self.changes.break_on_error = Some(breaks_on_error(request));
+                Ok(Value::Null)
+            }
+            other => Err(format!("Unsupported request '{}'", other)),
+        };
+        let mut connection = self.connection.borrow_mut();
+        match body {
+            Ok(body) => connection.respond(request, body)?,
+            Err(message) => connection.respond_error(request, &message)?,
+        }
+        Ok(None)
+    }
+
+    /// Replace the line breakpoints of a source, binding them in the
+    /// launched contract
+    fn set_breakpoints(&mut self, request: &Request) -> Value {
+        let lines = self.source_map.as_ref().ok_or(NO_LINE_TABLES);
+        let mut source_breakpoints = self.source_breakpoints.borrow_mut();
+        let body = source_breakpoints.set(request, lines);
+        self.changes.lines = Some(source_breakpoints.bind_all(lines).1);
+        body
+    }
+
+    fn scopes(
+        &mut self,
+        context: &PauseContext,
+        request: &Request,
+    ) -> std::result::Result<Value, String> {
+        let frames = context.frames();
+        let frame = usize::try_from(request.i64_arg("frameId").unwrap_or(0))
+            .ok()
+            .and_then(|id| frames.get(id).map(|frame| (id, frame)));
+        let Some((id, frame)) = frame else {
+            return Err("Unknown stack frame".to_string());
+        };
+
+        let mut scopes = Vec::new();
+        // Only the paused frame's values are visible
+        if id == 0 {
+            if context.export_name().is_some() || context.host_call().is_some() {
+                scopes.push(self.scope("Arguments", "arguments", Container::Arguments));
+            }
+            scopes.push(self.scope("Locals", "locals", Container::Locals));
+            scopes.push(self.scope("Stack", "registers", Container::Stack));
+            scopes.push(self.scope("Globals", "registers", Container::Globals));
+        }
+        let storage = Container::Storage(frame.contract_id.clone());
+        scopes.push(self.scope("Storage", "locals", storage));
+        Ok(json!({ "scopes": scopes }))
+    }
+
+    fn scope(&mut self, name: &str, hint: &str, container: Container) -> Value {
+        json!({
+            "name": name,
+            "presentationHint": hint,
+            "variablesReference": self.add(container),
+            "expensive": false,
+        })
+    }
+
+    fn add(&mut self, container: Container) -> usize {
+        self.containers.push(container);
+        self.containers.len()
+    }
+
+    fn variables(
+        &mut self,
+        context: &PauseContext,
+        request: &Request,
+    ) -> std::result::Result<Value, String> {
+        let index = usize::try_from(request.i64_arg("variablesReference").unwrap_or(0))
+            .ok()
+            .and_then(|reference| reference.checked_sub(1));
+        let Some(container) = index.and_then(|index| self.containers.get(index)) else {
+            return Err("Unknown variables reference".to_string());
+        };
+
+        let variables = match container {
+            Container::Locals => wasm_variables("local", context.locals()),
+            Container::Stack => wasm_variables("stack", context.stack()),
+            Container::Globals => wasm_variables("global", context.globals()),
+            Container::Arguments => {
+                let args = self.arguments(context);
+                self.json_variables(args)
+            }
+            Container::Storage(contract_id) => {
+                let storage = contract_storage(context, contract_id);
+                let mut entries: Vec<_> = storage.into_iter().collect();
+                entries.sort();
+                let entries = entries
+                    .into_iter()
+                    .map(|(key, value)| (key, storage_value(value)))
+                    .collect();
+                self.json_variables(entries)
+            }
+            Container::Value(Value::Array(items)) => {
+                let items = items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| (format!("[{}]", i), item.clone()))
+                    .collect();
+                self.json_variables(items)
+            }
+            Container::Value(Value::Object(fields)) => {
+                let fields = fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.clone()))
+                    .collect();
+                self.json_variables(fields)
+            }
+            Container::Value(_) => Vec::new(),
+        };
+        Ok(json!({ "variables": variables }))
+    }
+
+    fn json_variables(&mut self, entries: Vec<(String, Value)>) -> Vec<Value> {
+        entries
+            .into_iter()
+            .map(|(name, value)| {
+                let (rendered, reference) = self.render(value);
+                json!({ "name": name, "value": rendered, "variablesReference": reference })
+            })
+            .collect()
+    }
+
+    /// Text for a value, and a reference to expand it by if it has fields
+    /// or elements
+    fn render(&mut self, value: Value) -> (String, usize) {
+        let rendered = match &value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let expandable = match &value {
+            Value::Array(items) => !items.is_empty(),
+            Value::Object(fields) => !fields.is_empty(),
+            _ => false,
+        };
+        let reference = if expandable {
+            self.add(Container::Value(value))
+        } else {
+            0
+        };
+        (rendered, reference)
+    }
+
+    /// Arguments of the host call about to be made or of the paused
+    /// exported function, by parameter name
+    fn arguments(&mut self, context: &PauseContext) -> Vec<(String, Value)> {
+        if let Some(call) = context.host_call() {
+            return call
+                .args
+                .iter()
+                .map(|(name, value)| (name.to_string(), scval_to_json(value)))
+                .collect();
+        }
+        let (Some(function), Ok(args)) = (context.export_name(), context.arguments()) else {
+            return Vec::new();
+        };
+        self.specs
+            .entry(context.contract_id().to_string())
+            .or_insert_with(|| ContractSpec::from_wasm(context.wasm()).unwrap_or_default())
+            .named_args(function, &args)
+    }
+
+    /// Evaluate a path such as `amount` or `storage.COUNTER`, a condition
+    /// such as `amount > 100`, or a WASM value such as `local0`
+    fn evaluate(
+        &mut self,
+        context: &PauseContext,
+        request: &Request,
+    ) -> std::result::Result<Value, String> {
+        let expression = request.str_arg("expression").unwrap_or("").trim();
+        if let Some(value) = wasm_value(context, expression) {
+            let (value, ty) = wasm_text(value);
+            return Ok(json!({ "result": value, "type": ty, "variablesReference": 0 }));
+        }
+
+        let scope = ConditionScope::new(
+            self.arguments(context),
+            contract_storage(context, context.contract_id()),
+        );
+        let value = match evaluate_path(expression, &scope) {
+            Ok(value) => value,
+            Err(path_error) => match Condition::parse(expression) {
+                Ok(condition) => Value::Bool(condition.evaluate(&scope)?),
+                Err(_) => return Err(path_error),
+            },
+        };
+        let (result, reference) = self.render(value);
+        Ok(json!({ "result": result, "variablesReference": reference }))
+    }
+
+    /// Decoded instructions around an address of the paused contract
+    fn disassemble(
+        &mut self,
+        context: &PauseContext,
+        request: &Request,
+    ) -> std::result::Result<Value, String> {
+        let reference = request.str_arg("memoryReference").unwrap_or("0");
+        let base = parse_address(reference)
+            .ok_or_else(|| format!("Invalid memory reference '{}'", reference))?
+            .saturating_add(request.i64_arg("offset").unwrap_or(0));
+        let count = request.i64_arg("instructionCount").unwrap_or(0).max(0);
+
+        let instructions = self
+            .instructions
+            .entry(context.contract_id().to_string())
+            .or_insert_with(|| {
+                InstructionParser::new()
+                    .parse(context.wasm())
+                    .map(<[Instruction]>::to_vec)
+                    .unwrap_or_default()
+            });
+        let at = instructions.partition_point(|instruction| (instruction.offset as i64) < base);
+        let start = at as i64 + request.i64_arg("instructionOffset").unwrap_or(0);
+        let lines: Vec<Value> = (start..start + count)
+            .map(|index| {
+                let instruction = usize::try_from(index)
+                    .ok()
+                    .and_then(|index| instructions.get(index));
+                match instruction {
+                    Some(instruction) => {
+                        let operands = instruction.operands();
+                        let mut line = json!({
+                            "address": format!("{:#x}", instruction.offset),
+                            "instruction": format!("{} {}", instruction.name(), operands)
+                                .trim_end(),
+                        });
+                        let location = context
+                            .source_map()
+                            .and_then(|map| map.get_location(instruction.offset));
+                        if let Some(location) = location {
+                            line["location"] = source(&location);
+                            line["line"] = json!(location.line);
+                            line["column"] = json!(location.column.max(1));
+                        }
+                        line
+                    }
+                    // Padding before the first or after the last instruction
+                    None => json!({
+                        "address": format!("{:#x}", index.max(0)),
+                        "instruction": "",
+                        "presentationHint": "invalid",
+                    }),
+                }
+            })
+            .collect();
+        Ok(json!({ "instructions": lines }))
+    }
+}
+
+impl PauseHandler for DapPause {
+    fn on_pause(&mut self, context: &PauseContext) -> ResumeAction {
+        self.containers.clear();
+        if let Err(e) = self.stopped(context) {
+            tracing::warn!("Lost the DAP client: {}", e);
+            self.disconnected.set(true);
+            return ResumeAction::Abort;
+        }
+        loop {
+            let request = match self.connection.borrow_mut().read_request() {
+                Ok(Some(request)) => request,
+                // Nobody is left to resume the call
+                Ok(None) | Err(_) => {
+                    self.disconnected.set(true);
+                    return ResumeAction::Abort;
+                }
+            };
+            match self.serve(context, &request) {
+                Ok(Some(action)) => return action,
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::warn!("Lost the DAP client: {}", e);
+                    self.disconnected.set(true);
+                    return ResumeAction::Abort;
+                }
+            }
+        }
+    }
+
+    fn on_output(&mut self, message: &str) {
+        let _ = self.connection.borrow_mut().output("console", message);
+    }
+
+    fn take_breakpoint_changes(&mut self) -> Option<BreakpointChanges> {
+        let changes = std::mem::take(&mut self.changes);
+        (!changes.is_empty()).then_some(changes)
+    }
+}
+
+fn stack_trace(context: &PauseContext, request: &Request) -> Value {
+    let frames = context.frames();
+    let total = frames.len();
+    let start = request.i64_arg("startFrame").unwrap_or(0).max(0) as usize;
+    let levels = match request.i64_arg("levels").unwrap_or(0) {
+        levels if levels > 0 => levels as usize,
+        _ => total,
+    };
+    let stack_frames: Vec<Value> = frames
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(levels)
+        .map(|(id, frame)| {
+            let mut entry = json!({
+                "id": id,
+                "name": frame_name(frame),
+                "line": 0,
+                "column": 0,
+                "moduleId": frame.contract_id,
+            });
+            if let Some(location) = &frame.location {
+                entry["source"] = source(location);
+                entry["line"] = json!(location.line);
+                entry["column"] = json!(location.column.max(1));
+            }
+            // Disassembly covers the paused contract only
+            if frame.contract_id == context.contract_id() {
+                entry["instructionPointerReference"] = json!(format!("{:#x}", frame.offset));
+            }
+            entry
+        })
+        .collect();
+    json!({ "stackFrames": stack_frames, "totalFrames": total })
+}
+
+/// A DAP `Source` for a file of a source location. Relative paths are
+/// taken from the working directory.
+fn source(location: &SourceLocation) -> Value {
+    let path = Path::new(&location.file);
+    let path = std::env::current_dir()
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    json!({
+        "name": path.file_name().map(|name| name.to_string_lossy()),
+        "path": path,
+    })
+}
+
+fn wasm_variables(prefix: &str, values: &[WasmValue]) -> Vec<Value> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let (value, ty) = wasm_text(value);
+            json!({
+                "name": format!("{}{}", prefix, i),
+                "value": value,
+                "type": ty,
+                "variablesReference": 0,
+            })
+        })
+        .collect()
+}
+
+fn wasm_text(value: &WasmValue) -> (String, &'static str) {
+    match value {
+        WasmValue::I32(v) => (v.to_string(), "i32"),
+        WasmValue::I64(v) => (v.to_string(), "i64"),
+    }
+}
+
+/// The local, operand stack slot or global an expression such as `local2`
+/// names
+fn wasm_value<'a>(context: &'a PauseContext, expression: &str) -> Option<&'a WasmValue> {
+    let (values, index) = if let Some(index) = expression.strip_prefix("local") {
+        (context.locals(), index)
+    } else if let Some(index) = expression.strip_prefix("stack") {
+        (context.stack(), index)
+    } else if let Some(index) = expression.strip_prefix("global") {
+        (context.globals(), index)
+    } else {
+        return None;
+    };
+    values.get(index.parse::<usize>().ok()?)
+}
+
+/// Flattened storage of one contract in the call
+fn contract_storage(context: &PauseContext, contract_id: &str) -> HashMap<String, String> {
+    StorageInspector::capture_snapshot(context.host())
+        .map(|entries| {
+            let own: Vec<_> = entries
+                .into_iter()
+                .filter(|entry| entry.contract == contract_id)
+                .collect();
+            StorageInspector::to_flat_map(&own)
+        })
+        .unwrap_or_default()
+}
+
+/// A flattened storage value as JSON, as conditions see it
+fn storage_value(value: String) -> Value {
+    serde_json::from_str(&value).unwrap_or(Value::String(value))
+}
+
+/// Parse a decimal or `0x`-prefixed hexadecimal address
+fn parse_address(s: &str) -> Option<i64> {
+    match s.strip_prefix("0x") {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
@@ -1,36 +1,353 @@
-use std::collections::HashSet;
+use crate::debugger::condition::{
+    render_template, validate_template, Condition, ConditionScope, HitCondition,
+};
+use crate::runtime::interpreter::is_host_function;
+use crate::{DebuggerError, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
-/// Manages breakpoints during debugging
+/// Separates the contract ID from the function in a qualified breakpoint
+pub const CONTRACT_SEPARATOR: &str = "::";
+
+/// Marks a breakpoint on a host function, e.g. `host:require_auth`
+pub const HOST_PREFIX: &str = "host:";
+
+/// Keywords that start the optional clauses of a breakpoint spec
+const CLAUSE_KEYWORDS: [&str; 3] = ["if", "hit", "log"];
+
+/// A function breakpoint.
+///
+/// Written as `<location> [if <condition>] [hit <count>] [log <message>]`,
+/// e.g. `transfer if amount > 1000 hit >= 2`. The condition is checked on
+/// entry against the decoded arguments and storage; entries where it holds
+/// count as hits, and the hit condition picks which of those stop. A
+/// breakpoint with a log message prints it instead of stopping.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breakpoint {
+    /// Function name, `host:<host function>`, or either qualified as
+    /// `CID::...`
+    pub location: String,
+    pub condition: Option<Condition>,
+    pub hit_condition: Option<HitCondition>,
+    /// Template with `{path}` placeholders, see
+    /// [`render_template`](crate::debugger::condition::render_template)
+    pub log_message: Option<String>,
+    hits: u64,
+}
+
+impl Breakpoint {
+    /// An unconditional breakpoint
+    pub fn new(location: &str) -> Self {
+        Self {
+            location: location.to_string(),
+            condition: None,
+            hit_condition: None,
+            log_message: None,
+            hits: 0,
+        }
+    }
+
+    /// Stop only when `condition` holds
+    pub fn with_condition(mut self, condition: &str) -> Result<Self> {
+        self.condition = Some(Condition::parse(condition)?);
+        Ok(self)
+    }
+
+    /// Stop only on the hits `hit_condition` allows
+    pub fn with_hit_condition(mut self, hit_condition: &str) -> Result<Self> {
+        self.hit_condition = Some(HitCondition::parse(hit_condition)?);
+        Ok(self)
+    }
+
+    /// Print `message` instead of stopping
+    pub fn with_log_message(mut self, message: &str) -> Result<Self> {
+        validate_template(message)?;
+        self.log_message = Some(message.to_string());
+        Ok(self)
+    }
+
+    /// Times the breakpoint was reached with its condition holding
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Whether this is a log point rather than a stopping breakpoint
+    pub fn is_log_point(&self) -> bool {
+        self.log_message.is_some()
+    }
+
+    /// Whether the breakpoint is placed on `function` of `contract_id`
+    pub fn matches(&self, contract_id: &str, function: &str) -> bool {
+        match split_qualified(&self.location) {
+            (Some(contract), name) => contract == contract_id && name == function,
+            (None, name) => name == function,
+        }
+    }
+
+    /// The host function this breakpoint stops at, if it is a host call
+    /// breakpoint
+    pub fn host_function(&self) -> Option<&str> {
+        split_qualified(&self.location).1.strip_prefix(HOST_PREFIX)
+    }
+
+    /// Whether evaluating the breakpoint needs arguments and storage
+    fn needs_scope(&self) -> bool {
+        self.condition.is_some() || self.log_message.is_some()
+    }
+}
+
+impl FromStr for Breakpoint {
+    type Err = anyhow::Error;
+
+    fn from_str(spec: &str) -> Result<Self> {
+        let clauses = split_clauses(spec);
+        let location = clauses[0].1;
+        if location.is_empty() || location.contains(char::is_whitespace) {
+            return Err(DebuggerError::BreakpointError(format!(
+                "Invalid breakpoint '{}' (expected '<function> [if <condition>] [hit <count>] [log <message>]')",
+                spec.trim()
+            ))
+            .into());
+        }
+
+        let mut breakpoint = Breakpoint::new(location);
+        if let Some(function) = breakpoint.host_function() {
+            if !is_host_function(function) {
+                return Err(DebuggerError::BreakpointError(format!(
+                    "Unknown host function '{}' in breakpoint '{}'",
+                    function,
+                    spec.trim()
+                ))
+                .into());
+            }
+        }
+        for (keyword, body) in &clauses[1..] {
+            let repeated = match *keyword {
+                "if" => breakpoint.condition.is_some(),
+                "hit" => breakpoint.hit_condition.is_some(),
+                _ => false,
+            };
+            if repeated {
+                return Err(DebuggerError::BreakpointError(format!(
+                    "Breakpoint '{}' has more than one '{}' clause",
+                    spec.trim(),
+                    keyword
+                ))
+                .into());
+            }
+            breakpoint = match *keyword {
+                "if" => breakpoint.with_condition(body)?,
+                "hit" => breakpoint.with_hit_condition(body)?,
+                _ => breakpoint.with_log_message(body)?,
+            };
+        }
+        Ok(breakpoint)
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.location)?;
+        if let Some(condition) = &self.condition {
+            write!(f, " if {}", condition)?;
+        }
+        if let Some(hit_condition) = &self.hit_condition {
+            write!(f, " hit {}", hit_condition)?;
+        }
+        if let Some(message) = &self.log_message {
+            write!(f, " log {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Split a spec into its location and `(keyword, body)` clauses. Keywords
+/// only count as whole words outside quotes, and `log` takes the rest of
+/// the spec as its message.
+fn split_clauses(spec: &str) -> Vec<(&str, &str)> {
+    let spec = spec.trim();
+    let mut clauses = vec![("", 0, 0)];
+    let mut quote = None;
+    let mut word_start = true;
+    for (i, c) in spec.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => quote = Some(c),
+            None if word_start => {
+                let rest = &spec[i..];
+                let keyword = CLAUSE_KEYWORDS.iter().find(|keyword| {
+                    rest.strip_prefix(**keyword).is_some_and(|after| {
+                        after.is_empty() || after.starts_with(char::is_whitespace)
+                    })
+                });
+                if let Some(keyword) = keyword {
+                    if let Some(last) = clauses.last_mut() {
+                        last.2 = i;
+                    }
+                    clauses.push((*keyword, i + keyword.len(), spec.len()));
+                    if *keyword == "log" {
+                        break;
+                    }
+                }
+            }
+            None => {}
+        }
+        word_start = c.is_whitespace();
+    }
+    if let Some(last) = clauses.last_mut() {
+        if last.0 != "log" {
+            last.2 = spec.len();
+        }
+    }
+    clauses
+        .into_iter()
+        .map(|(keyword, start, end)| (keyword, spec[start..end.max(start)].trim()))
+        .collect()
+}
+
+/// Outcome of entering a function with breakpoints
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BreakpointHit {
+    /// Whether execution should stop
+    pub pause: bool,
+    /// Rendered messages of the log points hit
+    pub logs: Vec<String>,
+    /// Conditions that could not be evaluated; these stop as if they held
+    pub errors: Vec<String>,
+}
+
+/// Manages breakpoints during debugging.
+///
+/// Breakpoints are keyed by location: a function name, which matches that
+/// function in any contract, or `CID::function`, which only matches it in
+/// the contract with strkey `CID`.
 pub struct BreakpointManager {
-    breakpoints: HashSet<String>,
+    breakpoints: HashMap<String, Breakpoint>,
 }
 
 impl BreakpointManager {
     /// Create a new breakpoint manager
     pub fn new() -> Self {
         Self {
-            breakpoints: HashSet::new(),
+            breakpoints: HashMap::new(),
         }
     }
 
-    /// Add a breakpoint at a function name
+    /// Add an unconditional breakpoint at a function name
     pub fn add(&mut self, function: &str) {
-        self.breakpoints.insert(function.to_string());
+        self.insert(Breakpoint::new(function));
+    }
+
+    /// Add a breakpoint written as `<location> [if ...] [hit ...] [log ...]`
+    pub fn add_spec(&mut self, spec: &str) -> Result<()> {
+        self.insert(spec.parse()?);
+        Ok(())
+    }
+
+    /// Add a breakpoint, replacing any other at the same location
+    pub fn insert(&mut self, breakpoint: Breakpoint) {
+        self.breakpoints
+            .insert(breakpoint.location.clone(), breakpoint);
     }
 
     /// Remove a breakpoint
     pub fn remove(&mut self, function: &str) -> bool {
-        self.breakpoints.remove(function)
+        self.breakpoints.remove(function).is_some()
+    }
+
+    /// Breakpoint at a location
+    pub fn get(&self, location: &str) -> Option<&Breakpoint> {
+        self.breakpoints.get(location)
     }
 
-    /// Check if execution should break at this function
+    /// Check if a breakpoint is set at this function
     pub fn should_break(&self, function: &str) -> bool {
-        self.breakpoints.contains(function)
+        self.breakpoints.contains_key(function)
     }
 
-    /// List all breakpoints
+    /// Check if a breakpoint is set on `function` of the contract
+    /// `contract_id`, whatever its conditions
+    pub fn should_break_at(&self, contract_id: &str, function: &str) -> bool {
+        self.breakpoints
+            .values()
+            .any(|breakpoint| breakpoint.matches(contract_id, function))
+    }
+
+    /// Record entering a function known by any of `names` in `contract_id`,
+    /// checking conditions, counting hits and rendering log points. `scope`
+    /// is only built when a matching breakpoint needs it.
+    pub fn hit(
+        &mut self,
+        contract_id: &str,
+        names: &[&str],
+        scope: impl FnOnce() -> ConditionScope,
+    ) -> BreakpointHit {
+        let mut matching: Vec<&mut Breakpoint> = self
+            .breakpoints
+            .values_mut()
+            .filter(|breakpoint| {
+                names
+                    .iter()
+                    .any(|name| breakpoint.matches(contract_id, name))
+            })
+            .collect();
+        matching.sort_by(|a, b| a.location.cmp(&b.location));
+
+        let scope = matching
+            .iter()
+            .any(|breakpoint| breakpoint.needs_scope())
+            .then(scope)
+            .unwrap_or_default();
+
+        let mut hit = BreakpointHit::default();
+        for breakpoint in matching {
+            if let Some(condition) = &breakpoint.condition {
+                match condition.evaluate(&scope) {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    Err(e) => {
+                        hit.errors.push(format!(
+                            "{}: condition '{}': {}",
+                            breakpoint.location, condition, e
+                        ));
+                        hit.pause = true;
+                        continue;
+                    }
+                }
+            }
+            breakpoint.hits += 1;
+            if let Some(hit_condition) = breakpoint.hit_condition {
+                if !hit_condition.is_met(breakpoint.hits) {
+                    continue;
+                }
+            }
+            match &breakpoint.log_message {
+                Some(message) => hit.logs.push(render_template(message, &scope)),
+                None => hit.pause = true,
+            }
+        }
+        hit
+    }
+
+    /// List all breakpoints in spec form, sorted by location
     pub fn list(&self) -> Vec<String> {
-        self.breakpoints.iter().cloned().collect()
+        let mut breakpoints: Vec<&Breakpoint> = self.breakpoints.values().collect();
+        breakpoints.sort_by(|a, b| a.location.cmp(&b.location));
+        breakpoints.iter().map(ToString::to_string).collect()
+    }
+
+    /// Locations of all breakpoints
+    pub fn locations(&self) -> Vec<String> {
+        self.breakpoints.keys().cloned().collect()
+    }
+
+    /// Whether any breakpoint stops at a host function
+    pub fn has_host_breakpoints(&self) -> bool {
+        self.breakpoints
+            .values()
+            .any(|breakpoint| breakpoint.host_function().is_some())
     }
 
     /// Clear all breakpoints
@@ -49,6 +366,15 @@ impl BreakpointManager {
     }
 }
 
+/// Split a breakpoint location into its optional contract ID and function
+/// name
+pub fn split_qualified(spec: &str) -> (Option<&str>, &str) {
+    match spec.split_once(CONTRACT_SEPARATOR) {
+        Some((contract, function)) => (Some(contract), function),
+        None => (None, spec),
+    }
+}
+
 impl Default for BreakpointManager {
     fn default() -> Self {
         Self::new()
@@ -75,6 +401,39 @@ mod tests {
         assert!(!manager.should_break("transfer"));
     }
 
+    #[test]
+    fn test_qualified_breakpoint_matches_one_contract() {
+        let mut manager = BreakpointManager::new();
+        manager.add("CTOKEN::transfer");
+        manager.add("mint");
+        assert!(manager.should_break_at("CTOKEN", "transfer"));
+        assert!(!manager.should_break_at("COTHER", "transfer"));
+        assert!(manager.should_break_at("COTHER", "mint"));
+        assert!(!manager.should_break("transfer"));
+        assert_eq!(
+            split_qualified("CTOKEN::transfer"),
+            (Some("CTOKEN"), "transfer")
+        );
+        assert_eq!(split_qualified("transfer"), (None, "transfer"));
+    }
+
+    #[test]
+    fn test_host_function_breakpoints() {
+        let breakpoint: Breakpoint = "CTOKEN::host:require_auth".parse().unwrap();
+        assert_eq!(breakpoint.host_function(), Some("require_auth"));
+        assert!(breakpoint.matches("CTOKEN", "host:require_auth"));
+        assert_eq!(Breakpoint::new("transfer").host_function(), None);
+        assert!("host:require_oath".parse::<Breakpoint>().is_err());
+
+        let mut manager = BreakpointManager::new();
+        manager.add("transfer");
+        assert!(!manager.has_host_breakpoints());
+        manager
+            .add_spec("host:put_contract_data if k == 'count'")
+            .unwrap();
+        assert!(manager.has_host_breakpoints());
+    }
+
     #[test]
     fn test_list_breakpoints() {
         let mut manager = BreakpointManager::new();
@@ -85,4 +444,66 @@ mod tests {
         assert!(list.contains(&"transfer".to_string()));
         assert!(list.contains(&"mint".to_string()));
     }
+
+    #[test]
+    fn test_parses_breakpoint_clauses() {
+        let bp: Breakpoint =
+            "transfer if amount > 1000 && to == 'hit log' hit >= 2 log sent {amount} if ok"
+                .parse()
+                .unwrap();
+        assert_eq!(bp.location, "transfer");
+        assert_eq!(
+            bp.condition.as_ref().unwrap().to_string(),
+            "amount > 1000 && to == 'hit log'"
+        );
+        assert_eq!(bp.hit_condition, Some(HitCondition::AtLeast(2)));
+        assert_eq!(bp.log_message.as_deref(), Some("sent {amount} if ok"));
+
+        let plain: Breakpoint = "CTOKEN::mint".parse().unwrap();
+        assert_eq!(plain, Breakpoint::new("CTOKEN::mint"));
+        assert_eq!(plain.to_string(), "CTOKEN::mint");
+
+        assert!("transfer amount > 1".parse::<Breakpoint>().is_err());
+        assert!("transfer if".parse::<Breakpoint>().is_err());
+        assert!("transfer hit 1 hit 2".parse::<Breakpoint>().is_err());
+    }
+
+    fn scope(amount: i64) -> impl FnOnce() -> ConditionScope {
+        move || {
+            ConditionScope::new(
+                vec![("amount".to_string(), serde_json::json!(amount))],
+                HashMap::new(),
+            )
+        }
+    }
+
+    #[test]
+    fn test_conditional_breakpoint_counts_only_matching_hits() {
+        let mut manager = BreakpointManager::new();
+        manager.add_spec("transfer if amount > 1000 hit 2").unwrap();
+
+        assert!(!manager.hit("C1", &["transfer"], scope(5000)).pause);
+        assert!(!manager.hit("C1", &["transfer"], scope(10)).pause);
+        assert!(manager.hit("C1", &["transfer"], scope(2000)).pause);
+        assert!(!manager.hit("C1", &["transfer"], scope(3000)).pause);
+        assert_eq!(manager.get("transfer").unwrap().hits(), 3);
+    }
+
+    #[test]
+    fn test_log_point_does_not_pause() {
+        let mut manager = BreakpointManager::new();
+        manager.add_spec("transfer log moving {amount}").unwrap();
+        let hit = manager.hit("C1", &["transfer"], scope(7));
+        assert!(!hit.pause);
+        assert_eq!(hit.logs, vec!["moving 7".to_string()]);
+    }
+
+    #[test]
+    fn test_condition_error_pauses() {
+        let mut manager = BreakpointManager::new();
+        manager.add_spec("transfer if amout > 1").unwrap();
+        let hit = manager.hit("C1", &["transfer"], scope(7));
+        assert!(hit.pause);
+        assert_eq!(hit.errors.len(), 1);
+    }
 }
@@ -0,0 +1,745 @@
+//! Breakpoint conditions and log-point templates
+//!
+//! A condition is a small boolean expression over the arguments of the
+//! function being entered and the contract's storage:
+//!
+//! - Arguments by parameter name (`amount`), with `.field` and `[index]`
+//!   to reach into structs, maps and vecs (`order.price`, `path[0]`)
+//! - Storage entries as `storage.KEY` or `storage["KEY"]`; missing entries
+//!   are `null`, which is neither less nor greater than anything
+//! - Number, string (`'...'` or `"..."`), `true`, `false` and `null`
+//!   literals
+//! - `==`, `!=`, `<`, `<=`, `>`, `>=`, `&&`, `||`, `!` and parentheses
+//!
+//! Values are the bare JSON the spec renders (see
+//! [`ContractSpec::value_to_json`](crate::utils::ContractSpec::value_to_json)),
+//! so `i128` amounts beyond 64 bits, which render as strings, still
+//! compare as numbers.
+
+use crate::{DebuggerError, Result};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Root of storage paths in conditions and templates
+const STORAGE_ROOT: &str = "storage";
+
+/// Values a condition is evaluated against
+#[derive(Debug, Clone, Default)]
+pub struct ConditionScope {
+    /// Arguments by parameter name, in declaration order
+    pub args: Vec<(String, Value)>,
+    /// Storage values of the contract by key
+    pub storage: HashMap<String, Value>,
+}
+
+impl ConditionScope {
+    /// Build a scope from a flat `key -> value` storage map, as produced by
+    /// [`StorageInspector::to_flat_map`](crate::inspector::StorageInspector::to_flat_map)
+    pub fn new(args: Vec<(String, Value)>, storage: HashMap<String, String>) -> Self {
+        let storage = storage
+            .into_iter()
+            .map(|(key, value)| {
+                let value = serde_json::from_str(&value).unwrap_or(Value::String(value));
+                (key, value)
+            })
+            .collect();
+        Self { args, storage }
+    }
+
+    fn arg(&self, name: &str) -> Option<&Value> {
+        self.args
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// A parsed breakpoint condition
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    source: String,
+    expr: Expr,
+}
+
+impl Condition {
+    /// Parse a condition expression
+    pub fn parse(source: &str) -> Result<Self> {
+        let expr = Parser::new(source)?.parse_complete(Parser::expr)?;
+        Ok(Self {
+            source: source.trim().to_string(),
+            expr,
+        })
+    }
+
+    /// Evaluate the condition; comparisons that cannot be made, such as a
+    /// missing argument, are errors rather than `false`
+    pub fn evaluate(&self, scope: &ConditionScope) -> std::result::Result<bool, String> {
+        self.expr.eval(scope).map(|value| truthy(&value))
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// When a breakpoint whose condition holds actually stops, based on how
+/// many times it has been hit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HitCondition {
+    /// `N` or `== N`: only the Nth hit
+    Equal(u64),
+    /// `>= N`: the Nth hit and every one after it
+    AtLeast(u64),
+    /// `> N`: every hit after the Nth
+    After(u64),
+    /// `% N`: every Nth hit
+    Multiple(u64),
+}
+
+impl HitCondition {
+    /// Parse a hit condition such as `3`, `>= 3` or `% 2`
+    pub fn parse(source: &str) -> Result<Self> {
+        let source = source.trim();
+        let (make, count): (fn(u64) -> Self, &str) = if let Some(n) = source.strip_prefix(">=") {
+            (Self::AtLeast, n)
+        } else if let Some(n) = source.strip_prefix("==") {
+            (Self::Equal, n)
+        } else if let Some(n) = source.strip_prefix('>') {
+            (Self::After, n)
+        } else if let Some(n) = source.strip_prefix('%') {
+            (Self::Multiple, n)
+        } else {
+            (Self::Equal, source)
+        };
+        let count = count.trim().parse().map_err(|_| {
+            DebuggerError::BreakpointError(format!(
+                "Invalid hit condition '{}' (expected N, == N, >= N, > N or % N)",
+                source
+            ))
+        })?;
+        match make(count) {
+            Self::Multiple(0) => Err(DebuggerError::BreakpointError(
+                "Hit condition '% 0' never holds".to_string(),
+            )
+            .into()),
+            condition => Ok(condition),
+        }
+    }
+
+    /// Whether the breakpoint stops on its `hits`th hit, counting from 1
+    pub fn is_met(&self, hits: u64) -> bool {
+        match *self {
+            Self::Equal(n) => hits == n,
+            Self::AtLeast(n) => hits >= n,
+            Self::After(n) => hits > n,
+            Self::Multiple(n) => hits.is_multiple_of(n),
+        }
+    }
+}
+
+impl fmt::Display for HitCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Equal(n) => write!(f, "== {}", n),
+            Self::AtLeast(n) => write!(f, ">= {}", n),
+            Self::After(n) => write!(f, "> {}", n),
+            Self::Multiple(n) => write!(f, "% {}", n),
+        }
+    }
+}
+
+/// Check a log-point template, whose `{expr}` placeholders are paths such
+/// as `{amount}` or `{storage.COUNTER}`; `{{` and `}}` are literal braces
+pub fn validate_template(template: &str) -> Result<()> {
+    for piece in template_pieces(template)? {
+        if let TemplatePiece::Path(path) = piece {
+            Parser::new(path)?.parse_complete(Parser::path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Fill in the placeholders of a log-point template. Placeholders that
+/// cannot be evaluated render as `<error: ...>` so the message still prints.
+pub fn render_template(template: &str, scope: &ConditionScope) -> String {
+    let pieces = match template_pieces(template) {
+        Ok(pieces) => pieces,
+        Err(_) => return template.to_string(),
+    };
+    pieces
+        .into_iter()
+        .map(|piece| match piece {
+            TemplatePiece::Text(text) => text,
+            TemplatePiece::Path(path) => match evaluate_path(path, scope) {
+                Ok(Value::String(s)) => s,
+                Ok(value) => value.to_string(),
+                Err(e) => format!("<error: {}>", e),
+            },
+        })
+        .collect()
+}
+
+/// Evaluate a path such as `amount`, `order.price` or `storage.COUNTER` to
+/// the value it names
+pub fn evaluate_path(path: &str, scope: &ConditionScope) -> std::result::Result<Value, String> {
+    Parser::new(path)
+        .and_then(|parser| parser.parse_complete(Parser::path))
+        .map_err(|e| e.to_string())
+        .and_then(|expr| expr.eval(scope))
+}
+
+enum TemplatePiece<'a> {
+    Text(String),
+    Path(&'a str),
+}
+
+fn template_pieces(template: &str) -> Result<Vec<TemplatePiece<'_>>> {
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        text.push_str(&rest[..i]);
+        let tail = &rest[i..];
+        if let Some(after) = tail.strip_prefix("{{") {
+            text.push('{');
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("}}") {
+            text.push('}');
+            rest = after;
+        } else if tail.starts_with('}') {
+            return Err(template_error(template, "unmatched '}'"));
+        } else {
+            let end = tail
+                .find('}')
+                .ok_or_else(|| template_error(template, "unclosed '{'"))?;
+            if !text.is_empty() {
+                pieces.push(TemplatePiece::Text(std::mem::take(&mut text)));
+            }
+            pieces.push(TemplatePiece::Path(tail[1..end].trim()));
+            rest = &tail[end + 1..];
+        }
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        pieces.push(TemplatePiece::Text(text));
+    }
+    Ok(pieces)
+}
+
+fn template_error(template: &str, problem: &str) -> anyhow::Error {
+    DebuggerError::BreakpointError(format!("Invalid log message '{}': {}", template, problem))
+        .into()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Literal(Value),
+    Path(Vec<Segment>),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Field(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Expr {
+    fn eval(&self, scope: &ConditionScope) -> std::result::Result<Value, String> {
+        match self {
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Path(segments) => resolve(segments, scope),
+            Expr::Not(inner) => Ok(Value::Bool(!truthy(&inner.eval(scope)?))),
+            Expr::And(lhs, rhs) => Ok(Value::Bool(
+                truthy(&lhs.eval(scope)?) && truthy(&rhs.eval(scope)?),
+            )),
+            Expr::Or(lhs, rhs) => Ok(Value::Bool(
+                truthy(&lhs.eval(scope)?) || truthy(&rhs.eval(scope)?),
+            )),
+            Expr::Compare(lhs, op, rhs) => {
+                let (lhs, rhs) = (lhs.eval(scope)?, rhs.eval(scope)?);
+                let holds = match op {
+                    CompareOp::Eq => equal(&lhs, &rhs),
+                    CompareOp::Ne => !equal(&lhs, &rhs),
+                    _ if lhs.is_null() || rhs.is_null() => false,
+                    _ => {
+                        let ordering = order(&lhs, &rhs)
+                            .ok_or_else(|| format!("cannot compare {} with {}", lhs, rhs))?;
+                        match op {
+                            CompareOp::Lt => ordering.is_lt(),
+                            CompareOp::Le => ordering.is_le(),
+                            CompareOp::Gt => ordering.is_gt(),
+                            _ => ordering.is_ge(),
+                        }
+                    }
+                };
+                Ok(Value::Bool(holds))
+            }
+        }
+    }
+}
+
+fn resolve(segments: &[Segment], scope: &ConditionScope) -> std::result::Result<Value, String> {
+    let (root, rest) = match segments {
+        [Segment::Field(name), rest @ ..] => (name.as_str(), rest),
+        _ => return Err("a path must start with a name".to_string()),
+    };
+
+    let (mut value, rest) = match scope.arg(root) {
+        Some(value) => (value, rest),
+        None if root == STORAGE_ROOT => {
+            let key = match rest.first() {
+                Some(Segment::Field(key)) => key.clone(),
+                Some(Segment::Index(index)) => index.to_string(),
+                None => return Err("storage needs a key, e.g. storage.COUNTER".to_string()),
+            };
+            // Entries not written yet read as null
+            let value = scope.storage.get(&key).unwrap_or(&Value::Null);
+            (value, &rest[1..])
+        }
+        None => return Err(format!("unknown argument '{}'", root)),
+    };
+
+    for segment in rest {
+        let inner = unannotated(value);
+        value = match (segment, inner) {
+            (Segment::Field(field), Value::Object(fields)) => fields
+                .get(field)
+                .ok_or_else(|| format!("no field '{}' in {}", field, inner))?,
+            (Segment::Index(index), Value::Array(items)) => items
+                .get(*index)
+                .ok_or_else(|| format!("index {} out of range in {}", index, inner))?,
+            (Segment::Field(field), _) => {
+                return Err(format!("cannot read field '{}' of {}", field, inner))
+            }
+            (Segment::Index(index), _) => {
+                return Err(format!("cannot index {} with [{}]", inner, index))
+            }
+        };
+    }
+    Ok(unannotated(value).clone())
+}
+
+/// Strip a `{"type": ..., "value": ...}` annotation
+fn unannotated(value: &Value) -> &Value {
+    match value {
+        Value::Object(fields) if fields.len() == 2 && fields.contains_key("type") => {
+            fields.get("value").unwrap_or(value)
+        }
+        _ => value,
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64() != Some(0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(_) => true,
+    }
+}
+
+/// Read a value as a number, accepting the decimal strings large integers
+/// render as
+fn number(value: &Value) -> Option<Number> {
+    match value {
+        Value::Number(n) => n
+            .as_i64()
+            .map(|i| Number::Int(i as i128))
+            .or_else(|| n.as_u64().map(|u| Number::Int(u as i128)))
+            .or_else(|| n.as_f64().map(Number::Float)),
+        Value::String(s) => s.parse().ok().map(Number::Int),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Number {
+    Int(i128),
+    Float(f64),
+}
+
+impl Number {
+    fn cmp(self, other: Self) -> Option<Ordering> {
+        match (self, other) {
+            (Number::Int(a), Number::Int(b)) => Some(a.cmp(&b)),
+            (a, b) => a.as_f64().partial_cmp(&b.as_f64()),
+        }
+    }
+
+    fn as_f64(self) -> f64 {
+        match self {
+            Number::Int(i) => i as f64,
+            Number::Float(f) => f,
+        }
+    }
+}
+
+fn equal(lhs: &Value, rhs: &Value) -> bool {
+    match (number(lhs), number(rhs)) {
+        (Some(a), Some(b)) => a.cmp(b) == Some(Ordering::Equal),
+        _ => lhs == rhs,
+    }
+}
+
+fn order(lhs: &Value, rhs: &Value) -> Option<Ordering> {
+    if let (Some(a), Some(b)) = (number(lhs), number(rhs)) {
+        return a.cmp(b);
+    }
+    match (lhs, rhs) {
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(Value),
+    Str(String),
+    Dot,
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    Not,
+    And,
+    Or,
+    Compare(CompareOp),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (token, len) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('=', Some('=')) => (Token::Compare(CompareOp::Eq), 2),
+            ('!', Some('=')) => (Token::Compare(CompareOp::Ne), 2),
+            ('<', Some('=')) => (Token::Compare(CompareOp::Le), 2),
+            ('>', Some('=')) => (Token::Compare(CompareOp::Ge), 2),
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('<', _) => (Token::Compare(CompareOp::Lt), 1),
+            ('>', _) => (Token::Compare(CompareOp::Gt), 1),
+            ('!', _) => (Token::Not, 1),
+            ('.', _) => (Token::Dot, 1),
+            ('[', _) => (Token::LBracket, 1),
+            (']', _) => (Token::RBracket, 1),
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            ('\'' | '"', _) => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == c)
+                    .ok_or_else(|| condition_error(source, "unterminated string"))?;
+                let text: String = chars[i + 1..i + 1 + end].iter().collect();
+                (Token::Str(text), end + 2)
+            }
+            (c, next)
+                if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) =>
+            {
+                let len = 1 + chars[i + 1..]
+                    .iter()
+                    .take_while(|ch| ch.is_ascii_digit() || **ch == '.')
+                    .count();
+                let text: String = chars[i..i + len].iter().collect();
+                let value = serde_json::from_str::<Value>(&text)
+                    .ok()
+                    .filter(Value::is_number)
+                    .or_else(|| {
+                        text.parse::<i128>()
+                            .ok()
+                            .map(|n| Value::String(n.to_string()))
+                    })
+                    .ok_or_else(|| condition_error(source, &format!("bad number '{}'", text)))?;
+                (Token::Number(value), len)
+            }
+            (c, _) if c.is_alphabetic() || c == '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|ch| ch.is_alphanumeric() || **ch == '_')
+                    .count();
+                (Token::Ident(chars[i..i + len].iter().collect()), len)
+            }
+            (c, _) => {
+                return Err(condition_error(
+                    source,
+                    &format!("unexpected character '{}'", c),
+                ))
+            }
+        };
+        tokens.push(token);
+        i += len;
+    }
+    Ok(tokens)
+}
+
+fn condition_error(source: &str, problem: &str) -> anyhow::Error {
+    DebuggerError::BreakpointError(format!(
+        "Invalid condition '{}': {}",
+        source.trim(),
+        problem
+    ))
+    .into()
+}
+
+/// Recursive descent parser; `||` binds loosest, then `&&`, then
+/// comparisons, then `!`
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Result<Self> {
+        Ok(Self {
+            source,
+            tokens: tokenize(source)?,
+            pos: 0,
+        })
+    }
+
+    fn parse_complete(mut self, rule: fn(&mut Self) -> Result<Expr>) -> Result<Expr> {
+        if self.tokens.is_empty() {
+            return Err(self.error("expected an expression"));
+        }
+        let expr = rule(&mut self)?;
+        match self.peek() {
+            None => Ok(expr),
+            Some(token) => Err(self.error(&format!("unexpected {:?}", token))),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, problem: &str) -> anyhow::Error {
+        condition_error(self.source, problem)
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let mut lhs = self.and()?;
+        while self.eat(&Token::Or) {
+            lhs = Expr::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut lhs = self.comparison()?;
+        while self.eat(&Token::And) {
+            lhs = Expr::And(Box::new(lhs), Box::new(self.comparison()?));
+        }
+        Ok(lhs)
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let lhs = self.unary()?;
+        if let Some(Token::Compare(op)) = self.peek().cloned() {
+            self.pos += 1;
+            let rhs = self.unary()?;
+            return Ok(Expr::Compare(Box::new(lhs), op, Box::new(rhs)));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.eat(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat(&Token::LParen) {
+            let inner = self.expr()?;
+            if !self.eat(&Token::RParen) {
+                return Err(self.error("expected ')'"));
+            }
+            return Ok(inner);
+        }
+        match self.peek() {
+            Some(Token::Number(_)) | Some(Token::Str(_)) => match self.next() {
+                Some(Token::Number(value)) => Ok(Expr::Literal(value)),
+                Some(Token::Str(text)) => Ok(Expr::Literal(Value::String(text))),
+                _ => unreachable!(),
+            },
+            Some(Token::Ident(name)) if name == "true" || name == "false" => {
+                let value = name == "true";
+                self.pos += 1;
+                Ok(Expr::Literal(Value::Bool(value)))
+            }
+            Some(Token::Ident(name)) if name == "null" => {
+                self.pos += 1;
+                Ok(Expr::Literal(Value::Null))
+            }
+            _ => self.path(),
+        }
+    }
+
+    fn path(&mut self) -> Result<Expr> {
+        let mut segments = match self.next() {
+            Some(Token::Ident(name)) => vec![Segment::Field(name)],
+            _ => return Err(self.error("expected a value or argument name")),
+        };
+        loop {
+            if self.eat(&Token::Dot) {
+                match self.next() {
+                    Some(Token::Ident(name)) => segments.push(Segment::Field(name)),
+                    Some(Token::Number(Value::Number(n))) if n.is_u64() => {
+                        segments.push(Segment::Index(n.as_u64().unwrap_or_default() as usize))
+                    }
+                    _ => return Err(self.error("expected a field name after '.'")),
+                }
+            } else if self.eat(&Token::LBracket) {
+                let segment = match self.next() {
+                    Some(Token::Str(key)) => Segment::Field(key),
+                    Some(Token::Number(Value::Number(n))) if n.is_u64() => {
+                        Segment::Index(n.as_u64().unwrap_or_default() as usize)
+                    }
+                    _ => return Err(self.error("expected an index or quoted key in '[...]'")),
+                };
+                if !self.eat(&Token::RBracket) {
+                    return Err(self.error("expected ']'"));
+                }
+                segments.push(segment);
+            } else {
+                return Ok(Expr::Path(segments));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn scope() -> ConditionScope {
+        ConditionScope::new(
+            vec![
+                ("amount".to_string(), json!(1500)),
+                ("to".to_string(), json!("GABC")),
+                (
+                    "big".to_string(),
+                    json!("170141183460469231731687303715884105727"),
+                ),
+                ("order".to_string(), json!({"price": 7, "path": ["a", "b"]})),
+            ],
+            HashMap::from([
+                (
+                    "COUNTER".to_string(),
+                    r#"{"type":"u32","value":3}"#.to_string(),
+                ),
+                ("admin".to_string(), "GADMIN".to_string()),
+            ]),
+        )
+    }
+
+    fn eval(source: &str) -> std::result::Result<bool, String> {
+        Condition::parse(source).unwrap().evaluate(&scope())
+    }
+
+    #[test]
+    fn test_compares_arguments() {
+        assert_eq!(eval("amount > 1000"), Ok(true));
+        assert_eq!(eval("amount <= 1000"), Ok(false));
+        assert_eq!(eval("to == 'GABC'"), Ok(true));
+        assert_eq!(eval("big > 1000 && amount != 1"), Ok(true));
+        assert_eq!(eval("!(amount > 1000) || order.price == 7"), Ok(true));
+        assert_eq!(eval("order.path[1] == \"b\""), Ok(true));
+    }
+
+    #[test]
+    fn test_reads_storage() {
+        assert_eq!(eval("storage.COUNTER >= 3"), Ok(true));
+        assert_eq!(eval("storage[\"admin\"] == 'GADMIN'"), Ok(true));
+        assert_eq!(eval("storage.MISSING == null"), Ok(true));
+        assert_eq!(eval("storage.MISSING > 1"), Ok(false));
+    }
+
+    #[test]
+    fn test_unknown_argument_is_an_error() {
+        assert!(eval("amout > 1000").unwrap_err().contains("amout"));
+    }
+
+    #[test]
+    fn test_rejects_malformed_conditions() {
+        assert!(Condition::parse("amount >").is_err());
+        assert!(Condition::parse("amount > 1000)").is_err());
+        assert!(Condition::parse("to == 'open").is_err());
+        assert!(Condition::parse("").is_err());
+    }
+
+    #[test]
+    fn test_hit_conditions() {
+        assert_eq!(HitCondition::parse("3").unwrap(), HitCondition::Equal(3));
+        assert_eq!(
+            HitCondition::parse(">= 2").unwrap(),
+            HitCondition::AtLeast(2)
+        );
+        let every_other = HitCondition::parse("% 2").unwrap();
+        assert!(!every_other.is_met(1));
+        assert!(every_other.is_met(2));
+        assert!(HitCondition::parse(">").is_err());
+        assert!(HitCondition::parse("% 0").is_err());
+    }
+
+    #[test]
+    fn test_renders_log_templates() {
+        assert_eq!(
+            render_template("{to} gets {amount} ({storage.COUNTER}) {{x}}", &scope()),
+            "GABC gets 1500 (3) {x}"
+        );
+        assert!(render_template("{nope}", &scope()).starts_with("<error:"));
+        assert!(validate_template("{amount").is_err());
+        assert!(validate_template("{amount >}").is_err());
+    }
+
+    #[test]
+    fn test_evaluates_paths() {
+        assert_eq!(evaluate_path("order.path[1]", &scope()), Ok(json!("b")));
+        assert_eq!(evaluate_path("storage.COUNTER", &scope()), Ok(json!(3)));
+        assert!(evaluate_path("amount > 1", &scope()).is_err());
+    }
+}
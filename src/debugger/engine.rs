@@ -1,24 +1,106 @@
-use crate::debugger::breakpoint::BreakpointManager;
+use crate::debugger::breakpoint::{BreakpointHit, BreakpointManager, HOST_PREFIX};
+use crate::debugger::condition::ConditionScope;
 use crate::debugger::instruction_pointer::StepMode;
+use crate::debugger::recording::Recording;
 use crate::debugger::state::DebugState;
 use crate::debugger::stepper::Stepper;
-use crate::runtime::executor::ContractExecutor;
+use crate::debugger::trace::InstructionTrace;
+use crate::debugger::watchpoint::WatchpointManager;
+use crate::inspector::events::{CallEvent, ContractEvent, EventInspector};
+use crate::inspector::storage::{StorageDiff, StorageInspector};
+use crate::inspector::{BudgetInfo, BudgetInspector};
+use crate::runtime::executor::{ContractExecutor, ExecutionResult};
 use crate::runtime::instruction::Instruction;
-use crate::runtime::instrumentation::Instrumenter;
+use crate::runtime::instrumentation::{Instrumenter, Probe, MAX_PROBES};
+use crate::runtime::interpreter::{
+    AccessKind, BreakpointChanges, HostCall, Interpreter, PauseContext, PauseHandler, ResumeAction,
+};
+use crate::utils::scval::{scval_to_json, scval_to_string};
+use crate::utils::{ContractSpec, SourceMap};
 use crate::Result;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use tracing::info;
+use tracing::{info, warn};
+
+/// How [`DebuggerEngine::execute`] runs contract calls
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExecutionBackend {
+    /// The host's own VM, in one shot
+    #[default]
+    Host,
+    /// The built-in interpreter, which can pause mid-function
+    Interpreter,
+}
+
+impl std::str::FromStr for ExecutionBackend {
+    type Err = crate::DebuggerError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "host" => Ok(Self::Host),
+            "interpreter" => Ok(Self::Interpreter),
+            _ => Err(crate::DebuggerError::InvalidArguments(format!(
+                "Unknown execution backend '{}' (expected 'host' or 'interpreter')",
+                s
+            ))),
+        }
+    }
+}
+
+/// A host function call made by an interpreted contract
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostCallRecord {
+    /// Contract that made the call
+    pub contract_id: String,
+    /// Interpreted frames below the caller, across contracts
+    pub depth: usize,
+    pub call: HostCall,
+}
+
+/// What a call made through [`DebuggerEngine::execute_with_report`] did
+#[derive(Debug, Serialize)]
+pub struct CallReport {
+    /// The return value, or the error the call failed with
+    pub result: ExecutionResult,
+    /// Events emitted by the call
+    pub events: Vec<ContractEvent>,
+    /// Storage changes of the primary and registered contracts
+    pub storage: StorageDiff,
+    pub cpu_instructions: u64,
+    pub memory_bytes: u64,
+}
 
 /// Core debugging engine that orchestrates execution and debugging.
 pub struct DebuggerEngine {
     executor: ContractExecutor,
     breakpoints: BreakpointManager,
+    watchpoints: WatchpointManager,
+    /// Storage reads and writes seen by interpreted calls while watching
+    storage_accesses: StorageInspector,
+    /// Host calls of the last interpreted call, when the log is enabled
+    host_calls: Option<Vec<HostCallRecord>>,
+    record: bool,
+    /// Session of the last interpreted call, when recording
+    recording: Option<Recording>,
     state: Arc<Mutex<DebugState>>,
     stepper: Stepper,
     instrumenter: Instrumenter,
+    /// Instrumented build of the primary contract that traces each call
+    /// while instruction debugging
+    instrumented_wasm: Option<Vec<u8>>,
+    /// Source locations of the primary contract while instruction debugging,
+    /// if it was built with debug info
+    source_map: Option<SourceMap>,
+    /// Set when calls run in the built-in interpreter
+    interpreter: Option<Interpreter>,
+    pause_handler: Option<Box<dyn PauseHandler>>,
     paused: bool,
+    /// Set when failures and the call stack are not printed to stdout
+    quiet: bool,
     instruction_debug_enabled: bool,
+    /// Budget the last call used, read as soon as it returned
+    call_budget: Option<BudgetInfo>,
     generate_test: bool,
     test_output_dir: Option<std::path::PathBuf>,
 }
@@ -29,18 +111,31 @@ impl DebuggerEngine {
         let mut breakpoints = BreakpointManager::new();
 
         for bp in initial_breakpoints {
-            breakpoints.add(&bp);
-            info!("Breakpoint set at function: {}", bp);
+            match breakpoints.add_spec(&bp) {
+                Ok(()) => info!("Breakpoint set at function: {}", bp),
+                Err(e) => warn!("Ignoring breakpoint: {}", e),
+            }
         }
 
         Self {
             executor,
             breakpoints,
+            watchpoints: WatchpointManager::new(),
+            storage_accesses: StorageInspector::new(),
+            host_calls: None,
+            record: false,
+            recording: None,
             state: Arc::new(Mutex::new(DebugState::new())),
             stepper: Stepper::new(),
             instrumenter: Instrumenter::new(),
+            instrumented_wasm: None,
+            source_map: None,
+            interpreter: None,
+            pause_handler: None,
             paused: false,
+            quiet: false,
             instruction_debug_enabled: false,
+            call_budget: None,
             generate_test: false,
             test_output_dir: None,
         }
@@ -53,6 +148,11 @@ impl DebuggerEngine {
     }
 
     /// Enable instruction-level debugging.
+    ///
+    /// Each execution first runs an instrumented build of the primary
+    /// contract on a throwaway copy of the ledger, recording the instructions
+    /// the call actually runs. The call itself still runs the original code,
+    /// so the probes cost it no budget.
     pub fn enable_instruction_debug(&mut self, wasm_bytes: &[u8]) -> Result<()> {
         let instructions = self
             .instrumenter
@@ -66,18 +166,98 @@ impl DebuggerEngine {
         }
 
         self.instrumenter.enable();
+        let instrumented = self
+            .instrumenter
+            .instrument(wasm_bytes)
+            .map_err(|e| anyhow::anyhow!("Failed to instrument contract: {}", e))?;
+        self.instrumented_wasm = Some(instrumented);
+        self.source_map = SourceMap::from_wasm(wasm_bytes);
+
         self.instruction_debug_enabled = true;
         Ok(())
     }
 
     /// Disable instruction-level debugging.
-    pub fn disable_instruction_debug(&mut self) {
+    pub fn disable_instruction_debug(&mut self) -> Result<()> {
         self.instrumenter.disable();
         self.instrumenter.remove_hook();
+        self.instrumented_wasm = None;
+        self.source_map = None;
         if let Ok(mut state) = self.state.lock() {
             state.disable_instruction_debug();
         }
         self.instruction_debug_enabled = false;
+        Ok(())
+    }
+
+    /// Source locations of the contract being instruction-debugged, if it
+    /// was built with debug info
+    pub fn source_map(&self) -> Option<&SourceMap> {
+        self.source_map.as_ref()
+    }
+
+    /// Instrumenter driving instruction-level debugging.
+    pub fn instrumenter_mut(&mut self) -> &mut Instrumenter {
+        &mut self.instrumenter
+    }
+
+    /// Budget the last call used, before the engine inspected its outcome.
+    /// Every call starts the budget over.
+    pub fn call_budget(&self) -> Option<&BudgetInfo> {
+        self.call_budget.as_ref()
+    }
+
+    /// Probes fired by the last instrumented execution.
+    pub fn instruction_trace(&self) -> &[Probe] {
+        self.instrumenter.trace()
+    }
+
+    /// Run subsequent calls in the built-in interpreter.
+    ///
+    /// Breakpoints then pause inside the call, with the pause handler
+    /// deciding how to continue.
+    pub fn enable_interpreter(&mut self, wasm_bytes: &[u8]) -> Result<()> {
+        let interpreter = Interpreter::new(wasm_bytes)
+            .map_err(|e| anyhow::anyhow!("Failed to load contract into interpreter: {}", e))?;
+        self.interpreter = Some(interpreter);
+        info!("Execution backend: interpreter");
+        Ok(())
+    }
+
+    /// Run subsequent calls on the host's VM again.
+    pub fn disable_interpreter(&mut self) {
+        self.interpreter = None;
+    }
+
+    /// Backend used by [`execute`](Self::execute).
+    pub fn backend(&self) -> ExecutionBackend {
+        if self.interpreter.is_some() {
+            ExecutionBackend::Interpreter
+        } else {
+            ExecutionBackend::Host
+        }
+    }
+
+    /// Interpreter running calls, when the interpreter backend is enabled.
+    pub fn interpreter(&self) -> Option<&Interpreter> {
+        self.interpreter.as_ref()
+    }
+
+    /// Interpreter running calls, when the interpreter backend is enabled.
+    pub fn interpreter_mut(&mut self) -> Option<&mut Interpreter> {
+        self.interpreter.as_mut()
+    }
+
+    /// Set the handler that decides how to continue whenever an interpreted
+    /// call pauses. Without one, interpreted calls never stop.
+    pub fn set_pause_handler(&mut self, handler: impl PauseHandler + 'static) {
+        self.pause_handler = Some(Box::new(handler));
+    }
+
+    /// Stop printing failed calls and the call stack to stdout, for front
+    /// ends that use stdout for something else, such as the DAP server.
+    pub fn set_quiet(&mut self, quiet: bool) {
+        self.quiet = quiet;
     }
 
     /// Check if instruction-level debugging is enabled.
@@ -86,7 +266,11 @@ impl DebuggerEngine {
     }
 
     /// Execute a contract function with debugging
-    pub fn execute(&mut self, function: &str, args: Option<&str>) -> Result<crate::runtime::executor::ExecutionResult> {
+    pub fn execute(
+        &mut self,
+        function: &str,
+        args: Option<&str>,
+    ) -> Result<crate::runtime::executor::ExecutionResult> {
         info!("Executing function: {}", function);
 
         if let Ok(mut state) = self.state.lock() {
@@ -95,26 +279,108 @@ impl DebuggerEngine {
             state.call_stack_mut().push(function.to_string(), None);
         }
 
-        if self.breakpoints.should_break(function) {
-            self.pause_at_function(function);
+        // The interpreter pauses at breakpoints itself, with live state
+        let contract_id = self.executor.contract_id();
+        if self.interpreter.is_none() && self.breakpoints.should_break_at(&contract_id, function) {
+            let executor = &self.executor;
+            let hit = self.breakpoints.hit(&contract_id, &[function], || {
+                entry_scope(executor, function, args)
+            });
+            let handler = &mut self.pause_handler;
+            let mut output = |message: &str| match handler.as_deref_mut() {
+                Some(handler) => handler.on_output(message),
+                None => println!("{}", message),
+            };
+            if report_hit(&hit, &mut output) {
+                output(&format!(
+                    "[BREAKPOINT] {}: the host backend runs the call to completion without pausing",
+                    function
+                ));
+                self.pause_at_function(function);
+            }
         }
 
         // Capture initial storage if test generation is enabled
         let storage_before = if self.generate_test {
-            crate::inspector::storage::StorageInspector::capture_snapshot(self.executor.host())
+            StorageInspector::to_flat_map(&self.executor.get_storage_snapshot()?)
         } else {
             HashMap::new()
         };
 
+        if let Some(instrumented) = &self.instrumented_wasm {
+            let probes = self
+                .executor
+                .trace_instrumented(instrumented, function, args)?;
+            info!("Recorded {} instruction probes", probes.len());
+            if probes.len() >= MAX_PROBES {
+                warn!(
+                    "Instruction trace stopped after {} probes; later instructions are not recorded",
+                    MAX_PROBES
+                );
+            }
+            self.instrumenter.record_trace(probes);
+            let trace = InstructionTrace::from_probes(
+                self.instrumenter.trace(),
+                self.instrumenter.instructions(),
+            );
+            if let Ok(mut state) = self.state.lock() {
+                state.set_trace(trace);
+            }
+        }
+
+        self.executor.reset_budget()?;
         let start_time = std::time::Instant::now();
-        let result = self.executor.execute(function, args);
+        let result = match self.interpreter.as_mut() {
+            Some(interpreter) => {
+                let mut recording = if self.record {
+                    Some(Recording::start(&self.executor, function, args)?)
+                } else {
+                    None
+                };
+                interpreter.set_breakpoints(self.breakpoints.locations());
+                interpreter.set_watch_storage(!self.watchpoints.is_empty() || self.record);
+                interpreter.set_trace_host_calls(
+                    self.host_calls.is_some()
+                        || self.record
+                        || self.breakpoints.has_host_breakpoints(),
+                );
+                if let Some(host_calls) = self.host_calls.as_mut() {
+                    host_calls.clear();
+                }
+                let mut run_through = |_: &PauseContext| ResumeAction::Continue;
+                let handler: &mut dyn PauseHandler = match self.pause_handler.as_deref_mut() {
+                    Some(handler) => handler,
+                    None => &mut run_through,
+                };
+                let mut filter = PauseFilter {
+                    breakpoints: &mut self.breakpoints,
+                    specs: HashMap::new(),
+                    watchpoints: &self.watchpoints,
+                    accesses: &mut self.storage_accesses,
+                    host_calls: self.host_calls.as_mut(),
+                    recording: recording.as_mut(),
+                    handler,
+                };
+                let result =
+                    self.executor
+                        .execute_interpreted(interpreter, function, args, &mut filter);
+                if let Some(mut recording) = recording {
+                    recording.finish(self.executor.host(), &result);
+                    self.recording = Some(recording);
+                }
+                result
+            }
+            None => self.executor.execute(function, args),
+        };
         let duration = start_time.elapsed();
+        self.call_budget = Some(BudgetInspector::get_cpu_usage(self.executor.host()));
 
         // Capture final storage and generate test if enabled
         if self.generate_test {
-            let storage_after = crate::inspector::storage::StorageInspector::capture_snapshot(self.executor.host());
+            let storage_after =
+                StorageInspector::to_flat_map(&self.executor.get_storage_snapshot()?);
             let output_str = match &result {
-                Ok(out) => out.clone(),
+                Ok(out) => out.result.clone(),
                 Err(e) => format!("Error: {}", e),
             };
 
@@ -125,7 +391,9 @@ impl DebuggerEngine {
             };
 
             let codegen = crate::codegen::TestGenerator::new(
-                self.test_output_dir.clone().unwrap_or_else(|| std::path::PathBuf::from("tests/generated"))
+                self.test_output_dir
+                    .clone()
+                    .unwrap_or_else(|| std::path::PathBuf::from("tests/generated")),
             );
 
             // Paths handling
@@ -148,11 +416,13 @@ impl DebuggerEngine {
         self.update_call_stack(duration)?;
 
         if let Err(ref e) = result {
-            println!("\n[ERROR] Execution failed: {}", e);
-            if let Ok(state) = self.state.lock() {
-                state.call_stack().display();
+            if !self.quiet {
+                println!("\n[ERROR] Execution failed: {}", e);
+                if let Ok(state) = self.state.lock() {
+                    state.call_stack().display();
+                }
             }
-        } else if self.is_paused() {
+        } else if self.is_paused() && !self.quiet {
             if let Ok(state) = self.state.lock() {
                 state.call_stack().display();
             }
@@ -161,8 +431,39 @@ impl DebuggerEngine {
         result
     }
 
+    /// Execute a contract function like [`execute`](Self::execute), and
+    /// report the events it emitted, the storage it changed and the budget
+    /// it used
+    pub fn execute_with_report(
+        &mut self,
+        function: &str,
+        args: Option<&str>,
+    ) -> Result<CallReport> {
+        let storage_before = self.executor.get_workspace_storage_snapshot()?;
+        let events_before = self.executor.get_events()?;
+
+        let result = self.execute(function, args)?;
+
+        let events = self.executor.get_events()?;
+        let events = match events.strip_prefix(events_before.as_slice()) {
+            Some(new_events) => new_events.to_vec(),
+            None => events,
+        };
+        let storage_after = self.executor.get_workspace_storage_snapshot()?;
+        let budget = self.call_budget.clone().unwrap_or_default();
+        Ok(CallReport {
+            result,
+            events,
+            storage: StorageInspector::diff_entries(&storage_before, &storage_after),
+            cpu_instructions: budget.cpu_instructions,
+            memory_bytes: budget.memory_bytes,
+        })
+    }
+
     fn update_call_stack(&mut self, total_duration: std::time::Duration) -> Result<()> {
         let events = self.executor.get_diagnostic_events()?;
+        let calls = EventInspector::call_events(&events);
+        let primary = self.executor.contract_id();
 
         let current_func = if let Ok(state) = self.state.lock() {
             state.current_function().unwrap_or("entry").to_string()
@@ -173,19 +474,37 @@ impl DebuggerEngine {
         if let Ok(mut state) = self.state.lock() {
             let stack = state.call_stack_mut();
             stack.clear();
-            stack.push(current_func, None);
-
-            for event in events {
-                let event_str = format!("{:?}", event);
-                if event_str.contains("ContractCall")
-                    || (event_str.contains("call") && event.contract_id.is_some())
-                {
-                    let contract_id = event.contract_id.as_ref().map(|cid| format!("{:?}", cid));
-                    stack.push("nested_call".to_string(), contract_id);
-                } else if (event_str.contains("ContractReturn") || event_str.contains("return"))
-                    && stack.get_stack().len() > 1
-                {
-                    stack.pop();
+            stack.push(
+                current_func.clone(),
+                Some(self.executor.describe_contract(&primary)),
+            );
+
+            let mut entered_root = false;
+            for call in calls {
+                match call {
+                    CallEvent::Call {
+                        contract_id,
+                        function,
+                    } => {
+                        // The invocation of the root frame itself
+                        if !entered_root
+                            && stack.get_stack().len() == 1
+                            && contract_id == primary
+                            && function == current_func
+                        {
+                            entered_root = true;
+                            continue;
+                        }
+                        stack.push(
+                            function,
+                            Some(self.executor.describe_contract(&contract_id)),
+                        );
+                    }
+                    CallEvent::Return { .. } => {
+                        if stack.get_stack().len() > 1 {
+                            stack.pop();
+                        }
+                    }
                 }
             }
 
@@ -258,6 +577,40 @@ impl DebuggerEngine {
         Ok(stepped)
     }
 
+    /// Step to the next source line, entering calls. Steps one instruction
+    /// when the contract has no debug info.
+    pub fn step_into_line(&mut self) -> Result<bool> {
+        if !self.instruction_debug_enabled {
+            return Err(anyhow::anyhow!("Instruction debugging not enabled"));
+        }
+
+        let stepped = if let Ok(mut state) = self.state.lock() {
+            self.stepper
+                .step_into_line(&mut state, self.source_map.as_ref())
+        } else {
+            false
+        };
+        self.paused = stepped;
+        Ok(stepped)
+    }
+
+    /// Step to the next source line, stepping over calls. Steps over one
+    /// instruction when the contract has no debug info.
+    pub fn step_over_line(&mut self) -> Result<bool> {
+        if !self.instruction_debug_enabled {
+            return Err(anyhow::anyhow!("Instruction debugging not enabled"));
+        }
+
+        let stepped = if let Ok(mut state) = self.state.lock() {
+            self.stepper
+                .step_over_line(&mut state, self.source_map.as_ref())
+        } else {
+            false
+        };
+        self.paused = stepped;
+        Ok(stepped)
+    }
+
     /// Step backwards to previous instruction.
     pub fn step_back(&mut self) -> Result<bool> {
         if !self.instruction_debug_enabled {
@@ -296,13 +649,20 @@ impl DebuggerEngine {
         Ok(())
     }
 
+    /// Record a breakpoint hit on entering `function` with the host backend.
+    ///
+    /// The host runs a call in one shot, so this only marks the engine as
+    /// paused and shows the call stack; the call itself does not stop. Only
+    /// the interpreter backend pauses inside calls.
     fn pause_at_function(&mut self, function: &str) {
         crate::logging::log_breakpoint(function);
         self.paused = true;
 
         if let Ok(mut state) = self.state.lock() {
             state.set_current_function(function.to_string());
-            state.call_stack().display();
+            if !self.quiet {
+                state.call_stack().display();
+            }
         }
     }
 
@@ -333,10 +693,46 @@ impl DebuggerEngine {
         }
     }
 
+    pub fn breakpoints(&self) -> &BreakpointManager {
+        &self.breakpoints
+    }
+
     pub fn breakpoints_mut(&mut self) -> &mut BreakpointManager {
         &mut self.breakpoints
     }
 
+    /// Record every host function call interpreted calls make
+    pub fn enable_host_call_log(&mut self) {
+        self.host_calls.get_or_insert_with(Vec::new);
+    }
+
+    /// Host function calls of the last interpreted call, in order, if the
+    /// log is enabled
+    pub fn host_calls(&self) -> Option<&[HostCallRecord]> {
+        self.host_calls.as_deref()
+    }
+
+    /// Record each interpreted call as a session that can be saved and
+    /// replayed
+    pub fn enable_recording(&mut self) {
+        self.record = true;
+    }
+
+    /// Session of the last interpreted call, if recording is enabled
+    pub fn recording(&self) -> Option<&Recording> {
+        self.recording.as_ref()
+    }
+
+    pub fn watchpoints_mut(&mut self) -> &mut WatchpointManager {
+        &mut self.watchpoints
+    }
+
+    /// Read and write counts of the storage keys interpreted calls accessed
+    /// while watchpoints were set
+    pub fn storage_accesses(&self) -> &StorageInspector {
+        &self.storage_accesses
+    }
+
     pub fn executor(&self) -> &ContractExecutor {
         &self.executor
     }
@@ -356,3 +752,151 @@ impl DebuggerEngine {
         Ok(())
     }
 }
+
+/// Applies breakpoint conditions, hit counts and log points, and storage
+/// watchpoints, to interpreted calls before a pause reaches the pause
+/// handler. Also keeps the host call log and the session recording.
+struct PauseFilter<'a> {
+    breakpoints: &'a mut BreakpointManager,
+    /// Specs of the contracts breakpoints were reached in, by contract ID
+    specs: HashMap<String, ContractSpec>,
+    watchpoints: &'a WatchpointManager,
+    accesses: &'a mut StorageInspector,
+    host_calls: Option<&'a mut Vec<HostCallRecord>>,
+    recording: Option<&'a mut Recording>,
+    handler: &'a mut dyn PauseHandler,
+}
+
+impl PauseHandler for PauseFilter<'_> {
+    fn on_pause(&mut self, context: &PauseContext) -> ResumeAction {
+        self.handler.on_pause(context)
+    }
+
+    fn take_breakpoint_changes(&mut self) -> Option<BreakpointChanges> {
+        let mut changes = self.handler.take_breakpoint_changes()?;
+        // Conditions, hit counts and log points stay with the manager; the
+        // interpreter only needs the locations
+        if let Some(specs) = changes.functions.take() {
+            self.breakpoints.clear();
+            for spec in &specs {
+                if let Err(e) = self.breakpoints.add_spec(spec) {
+                    warn!("Ignoring breakpoint '{}': {}", spec, e);
+                }
+            }
+            changes.functions = Some(self.breakpoints.locations());
+            changes.trace_host_calls = Some(
+                self.host_calls.is_some()
+                    || self.recording.is_some()
+                    || self.breakpoints.has_host_breakpoints(),
+            );
+        }
+        Some(changes)
+    }
+
+    fn on_breakpoint(&mut self, context: &PauseContext) -> bool {
+        let frame = context.current_frame();
+        let export = context.export_name();
+        let names: Vec<&str> = export
+            .into_iter()
+            .chain(frame.function_name.as_deref())
+            .collect();
+        let specs = &mut self.specs;
+        let hit = self.breakpoints.hit(context.contract_id(), &names, || {
+            let spec = specs
+                .entry(context.contract_id().to_string())
+                .or_insert_with(|| ContractSpec::from_wasm(context.wasm()).unwrap_or_default());
+            let args = match (export, context.arguments()) {
+                (Some(function), Ok(args)) => spec.named_args(function, &args),
+                _ => Vec::new(),
+            };
+            ConditionScope::new(args, contract_storage(context))
+        });
+        let handler = &mut self.handler;
+        report_hit(&hit, &mut |message| handler.on_output(message))
+            && self.handler.on_breakpoint(context)
+    }
+
+    fn on_host_call(&mut self, context: &PauseContext) -> bool {
+        let Some(call) = context.host_call() else {
+            return false;
+        };
+        if let Some(host_calls) = self.host_calls.as_mut() {
+            host_calls.push(HostCallRecord {
+                contract_id: context.contract_id().to_string(),
+                depth: context.depth(),
+                call: call.clone(),
+            });
+        }
+        if let Some(recording) = self.recording.as_mut() {
+            recording.record_host_call(context.host(), &context.frames(), call);
+        }
+        let location = format!("{}{}", HOST_PREFIX, call.function);
+        let hit = self
+            .breakpoints
+            .hit(context.contract_id(), &[&location], || {
+                let args = call
+                    .args
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), scval_to_json(value)))
+                    .collect();
+                ConditionScope::new(args, contract_storage(context))
+            });
+        let handler = &mut self.handler;
+        report_hit(&hit, &mut |message| handler.on_output(message))
+            && self.handler.on_host_call(context)
+    }
+
+    fn on_storage_access(&mut self, context: &PauseContext) -> bool {
+        let Some(access) = context.storage_access() else {
+            return false;
+        };
+        if let Some(recording) = self.recording.as_mut() {
+            recording.record_storage_access(access);
+        }
+        let key = scval_to_string(&access.key);
+        match access.kind {
+            AccessKind::Read => self.accesses.track_read(&key),
+            AccessKind::Write => self.accesses.track_write(&key),
+        }
+        self.watchpoints.matching(access.kind, &key).is_some()
+            && self.handler.on_storage_access(context)
+    }
+}
+
+/// Flattened storage of the contract paused in
+fn contract_storage(context: &PauseContext) -> HashMap<String, String> {
+    StorageInspector::capture_snapshot(context.host())
+        .map(|entries| {
+            let own: Vec<_> = entries
+                .into_iter()
+                .filter(|entry| entry.contract == context.contract_id())
+                .collect();
+            StorageInspector::to_flat_map(&own)
+        })
+        .unwrap_or_default()
+}
+
+/// Arguments and storage of the primary contract on entering `function`
+fn entry_scope(executor: &ContractExecutor, function: &str, args: Option<&str>) -> ConditionScope {
+    let args = executor.named_args(function, args).unwrap_or_else(|e| {
+        warn!("Breakpoint conditions cannot see the arguments: {}", e);
+        Vec::new()
+    });
+    let storage = executor
+        .get_storage_snapshot()
+        .map(|entries| StorageInspector::to_flat_map(&entries))
+        .unwrap_or_default();
+    ConditionScope::new(args, storage)
+}
+
+/// Show the log points and condition errors of a breakpoint hit, returning
+/// whether to pause
+fn report_hit(hit: &BreakpointHit, output: &mut dyn FnMut(&str)) -> bool {
+    for message in &hit.logs {
+        output(&format!("[LOG] {}", message));
+    }
+    for error in &hit.errors {
+        output(&format!("[WARN] Breakpoint condition failed: {}", error));
+    }
+    hit.pause
+}
@@ -14,6 +14,10 @@ pub enum StepMode {
     StepOut,
     /// Step to next basic block
     StepBlock,
+    /// Step to the next source line, entering calls
+    StepIntoLine,
+    /// Step to the next source line in this function or its caller
+    StepOverLine,
 }
 
 /// Instruction pointer state
@@ -33,6 +37,8 @@ pub struct InstructionPointer {
     step_mode: StepMode,
     /// Target depth for step out
     target_depth: Option<u32>,
+    /// Position in the recorded execution trace
+    trace_position: usize,
 }
 
 impl InstructionPointer {
@@ -46,6 +52,7 @@ impl InstructionPointer {
             stepping: false,
             step_mode: StepMode::StepInto,
             target_depth: None,
+            trace_position: 0,
         }
     }
 
@@ -59,6 +66,28 @@ impl InstructionPointer {
         self.call_stack_depth
     }
 
+    /// Get the position in the recorded execution trace
+    pub fn trace_position(&self) -> usize {
+        self.trace_position
+    }
+
+    /// Move to a step of the recorded execution trace
+    ///
+    /// The call depth is taken from the trace rather than inferred from the
+    /// instruction.
+    pub fn move_to_step(&mut self, position: usize, index: usize, depth: u32) {
+        self.advance_to(index);
+        self.set_trace_step(position, index, depth);
+    }
+
+    /// Return to a step of the recorded execution trace without recording
+    /// history, e.g. when stepping back or starting over
+    pub fn set_trace_step(&mut self, position: usize, index: usize, depth: u32) {
+        self.current_index = index;
+        self.trace_position = position;
+        self.call_stack_depth = depth;
+    }
+
     /// Check if currently stepping
     pub fn is_stepping(&self) -> bool {
         self.stepping
@@ -75,7 +104,7 @@ impl InstructionPointer {
         self.step_mode = mode;
 
         match mode {
-            StepMode::StepOver => {
+            StepMode::StepOver | StepMode::StepOverLine => {
                 self.target_depth = Some(self.call_stack_depth);
             }
             StepMode::StepOut => {
@@ -136,8 +165,8 @@ impl InstructionPointer {
         }
 
         match self.step_mode {
-            StepMode::StepInto => true,
-            StepMode::StepOver => {
+            StepMode::StepInto | StepMode::StepIntoLine => true,
+            StepMode::StepOver | StepMode::StepOverLine => {
                 // Pause if we're at the same depth or returned from a call
                 self.target_depth
                     .map(|target| self.call_stack_depth <= target)
@@ -162,6 +191,7 @@ impl InstructionPointer {
     pub fn reset(&mut self) {
         self.current_index = 0;
         self.call_stack_depth = 0;
+        self.trace_position = 0;
         self.history.clear();
         self.stepping = false;
         self.target_depth = None;
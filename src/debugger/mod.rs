@@ -1,11 +1,19 @@
 pub mod breakpoint;
+pub mod condition;
 pub mod engine;
 pub mod instruction_pointer;
+pub mod recording;
 pub mod state;
 pub mod stepper;
+pub mod trace;
+pub mod watchpoint;
 
-pub use breakpoint::BreakpointManager;
-pub use engine::DebuggerEngine;
+pub use breakpoint::{Breakpoint, BreakpointHit, BreakpointManager};
+pub use condition::{Condition, ConditionScope, HitCondition};
+pub use engine::{CallReport, DebuggerEngine, ExecutionBackend, HostCallRecord};
 pub use instruction_pointer::{InstructionPointer, StepMode};
+pub use recording::{Recording, Replay};
 pub use state::DebugState;
 pub use stepper::Stepper;
+pub use trace::{InstructionTrace, TraceStep};
+pub use watchpoint::{WatchAccess, Watchpoint, WatchpointManager};
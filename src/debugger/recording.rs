@@ -0,0 +1,549 @@
+//! Recorded execution sessions, saved to a file and replayed later
+//!
+//! A [`Recording`] is a timeline of the host calls an interpreted contract
+//! call made, each with the call stack at that point, the storage access it
+//! made, the events emitted so far and a budget checkpoint. A [`Replay`]
+//! moves through it in either direction and rebuilds the storage at each
+//! step from the recorded writes.
+
+use crate::debugger::breakpoint::{BreakpointManager, HOST_PREFIX};
+use crate::debugger::watchpoint::WatchpointManager;
+use crate::inspector::budget::BudgetInspector;
+use crate::inspector::events::{ContractEvent, EventInspector};
+use crate::inspector::storage::{StorageDurability, StorageEntry, StorageInspector};
+use crate::runtime::executor::{ContractExecutor, ExecutionResult};
+use crate::runtime::interpreter::{AccessKind, FrameInfo, HostCall, StorageAccess};
+use crate::utils::scval::{scval_to_json, scval_to_string};
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use soroban_env_host::Host;
+use std::fs;
+use std::path::Path;
+
+/// Format version written to recording files
+pub const RECORDING_VERSION: u32 = 1;
+
+/// CPU and memory consumed at a point of the recording
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedBudget {
+    pub cpu_instructions: u64,
+    pub memory_bytes: u64,
+}
+
+impl RecordedBudget {
+    fn capture(host: &Host) -> Self {
+        let info = BudgetInspector::get_cpu_usage(host);
+        Self {
+            cpu_instructions: info.cpu_instructions,
+            memory_bytes: info.memory_bytes,
+        }
+    }
+}
+
+/// An interpreted frame, as in [`FrameInfo`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub contract_id: String,
+    #[serde(default)]
+    pub function: Option<String>,
+    pub offset: usize,
+}
+
+impl From<&FrameInfo> for RecordedFrame {
+    fn from(frame: &FrameInfo) -> Self {
+        Self {
+            contract_id: frame.contract_id.clone(),
+            function: frame.function_name.clone(),
+            offset: frame.offset,
+        }
+    }
+}
+
+/// A storage access, with keys and values rendered as in storage listings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedAccess {
+    pub kind: AccessKind,
+    pub durability: StorageDurability,
+    pub key: String,
+    /// Value before the access; `None` if the entry did not exist
+    #[serde(default)]
+    pub old_value: Option<String>,
+    /// Value after the access; `None` if the entry does not exist
+    #[serde(default)]
+    pub new_value: Option<String>,
+}
+
+impl From<&StorageAccess> for RecordedAccess {
+    fn from(access: &StorageAccess) -> Self {
+        Self {
+            kind: access.kind,
+            durability: access.durability,
+            key: scval_to_string(&access.key),
+            old_value: access.old_value.as_ref().map(scval_to_string),
+            new_value: access.new_value.as_ref().map(scval_to_string),
+        }
+    }
+}
+
+/// A host call made by the recorded contract call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedStep {
+    /// Contract making the call
+    pub contract_id: String,
+    /// Interpreted frames, innermost first
+    pub frames: Vec<RecordedFrame>,
+    /// Host function name, e.g. `put_contract_data`
+    pub function: String,
+    /// The call with its decoded arguments, as printed at a pause
+    pub call: String,
+    /// Arguments by parameter name
+    #[serde(default)]
+    pub args: Vec<(String, serde_json::Value)>,
+    /// Storage access the call made
+    #[serde(default)]
+    pub access: Option<RecordedAccess>,
+    /// Contract events emitted before the call
+    pub events: usize,
+    /// Budget consumed before the call
+    pub budget: RecordedBudget,
+}
+
+/// A recorded contract call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    pub version: u32,
+    /// Contract the call was made to
+    pub contract_id: String,
+    pub function: String,
+    #[serde(default)]
+    pub args: Option<String>,
+    /// Storage of every contract before the call
+    pub initial_storage: Vec<StorageEntry>,
+    pub cpu_limit: u64,
+    pub memory_limit: u64,
+    /// Budget consumed before the call
+    pub initial_budget: RecordedBudget,
+    pub steps: Vec<RecordedStep>,
+    /// Contract events in the order they were emitted
+    pub events: Vec<ContractEvent>,
+    /// Budget consumed once the call returned or failed
+    pub final_budget: RecordedBudget,
+    /// Return value, or the error the call failed with
+    pub outcome: String,
+    pub failed: bool,
+}
+
+impl Recording {
+    /// Start recording a call of `function` on the executor's contract
+    pub fn start(executor: &ContractExecutor, function: &str, args: Option<&str>) -> Result<Self> {
+        let host = executor.host();
+        let budget = BudgetInspector::get_cpu_usage(host);
+        Ok(Self {
+            version: RECORDING_VERSION,
+            contract_id: executor.contract_id(),
+            function: function.to_string(),
+            args: args.map(str::to_string),
+            initial_storage: StorageInspector::capture_snapshot(host)?,
+            cpu_limit: budget.cpu_limit,
+            memory_limit: budget.memory_limit,
+            initial_budget: RecordedBudget::capture(host),
+            steps: Vec::new(),
+            events: Vec::new(),
+            final_budget: RecordedBudget::default(),
+            outcome: String::new(),
+            failed: false,
+        })
+    }
+
+    /// Record a host call about to be made from `frames`
+    pub fn record_host_call(&mut self, host: &Host, frames: &[FrameInfo], call: &HostCall) {
+        self.capture_events(host);
+        self.steps.push(RecordedStep {
+            contract_id: frames
+                .first()
+                .map(|frame| frame.contract_id.clone())
+                .unwrap_or_default(),
+            frames: frames.iter().map(RecordedFrame::from).collect(),
+            function: call.function.to_string(),
+            call: call.to_string(),
+            args: call
+                .args
+                .iter()
+                .map(|(name, value)| (name.to_string(), scval_to_json(value)))
+                .collect(),
+            access: None,
+            events: self.events.len(),
+            budget: RecordedBudget::capture(host),
+        });
+    }
+
+    /// Attach the storage access made by the last recorded host call
+    pub fn record_storage_access(&mut self, access: &StorageAccess) {
+        if let Some(step) = self.steps.last_mut() {
+            step.access = Some(access.into());
+        }
+    }
+
+    /// Close the recording with the call's result
+    pub fn finish(&mut self, host: &Host, result: &Result<ExecutionResult>) {
+        self.capture_events(host);
+        self.final_budget = RecordedBudget::capture(host);
+        match result {
+            Ok(result) => {
+                self.outcome = result.result.clone();
+                self.failed = result.value.is_none();
+            }
+            Err(e) => {
+                self.outcome = e.to_string();
+                self.failed = true;
+            }
+        }
+    }
+
+    /// Keep events emitted since the last capture. Events of a failed call
+    /// are kept even once the host drops them.
+    fn capture_events(&mut self, host: &Host) {
+        if let Ok(events) = EventInspector::get_events(host) {
+            if events.len() > self.events.len() {
+                self.events.extend_from_slice(&events[self.events.len()..]);
+            }
+        }
+    }
+
+    /// Write the recording as JSON
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| anyhow::anyhow!("Failed to serialize recording: {}", e))?;
+        fs::write(path, json)
+            .map_err(|e| anyhow::anyhow!("Failed to write recording {:?}: {}", path, e))?;
+        Ok(())
+    }
+
+    /// Read a recording written by [`save`](Self::save)
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read recording {:?}: {}", path, e))?;
+        let recording: Recording = serde_json::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("Failed to parse recording {:?}: {}", path, e))?;
+        if recording.version != RECORDING_VERSION {
+            anyhow::bail!(
+                "Recording {:?} has format version {}, expected {}",
+                path,
+                recording.version,
+                RECORDING_VERSION
+            );
+        }
+        Ok(recording)
+    }
+}
+
+/// Moves through a [`Recording`] forwards and backwards.
+///
+/// Positions `0..steps.len()` are the recorded host calls, each seen just
+/// before it is made; position `steps.len()` is the end of the call.
+pub struct Replay {
+    recording: Recording,
+    position: usize,
+}
+
+impl Replay {
+    /// Replay `recording`, starting at its end
+    pub fn new(recording: Recording) -> Self {
+        let position = recording.steps.len();
+        Self {
+            recording,
+            position,
+        }
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Host call at the current position; `None` at the end
+    pub fn current(&self) -> Option<&RecordedStep> {
+        self.recording.steps.get(self.position)
+    }
+
+    pub fn at_end(&self) -> bool {
+        self.position == self.recording.steps.len()
+    }
+
+    /// Jump to a position, returning `false` if it is past the end
+    pub fn seek(&mut self, position: usize) -> bool {
+        if position > self.recording.steps.len() {
+            return false;
+        }
+        self.position = position;
+        true
+    }
+
+    pub fn step_forward(&mut self) -> bool {
+        self.seek(self.position + 1)
+    }
+
+    pub fn step_back(&mut self) -> bool {
+        match self.position.checked_sub(1) {
+            Some(position) => self.seek(position),
+            None => false,
+        }
+    }
+
+    /// Run forward to the next step a breakpoint or watchpoint stops at,
+    /// or to the end. Returns whether one stopped it.
+    pub fn continue_forward(
+        &mut self,
+        breakpoints: &BreakpointManager,
+        watchpoints: &WatchpointManager,
+    ) -> bool {
+        while self.step_forward() {
+            if self.stops_here(breakpoints, watchpoints) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Run backward to the previous step a breakpoint or watchpoint stops
+    /// at, or to the start. Returns whether one stopped it.
+    pub fn reverse_continue(
+        &mut self,
+        breakpoints: &BreakpointManager,
+        watchpoints: &WatchpointManager,
+    ) -> bool {
+        while self.step_back() {
+            if self.stops_here(breakpoints, watchpoints) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Whether the current step is a host call with a breakpoint, the first
+    /// call made after entering a function with one, or a watched access.
+    /// Conditions, hit counts and log points are not evaluated.
+    fn stops_here(&self, breakpoints: &BreakpointManager, watchpoints: &WatchpointManager) -> bool {
+        let Some(step) = self.current() else {
+            return false;
+        };
+        let host_location = format!("{}{}", HOST_PREFIX, step.function);
+        if breakpoints.should_break_at(&step.contract_id, &host_location) {
+            return true;
+        }
+        if let Some(access) = &step.access {
+            if watchpoints.matching(access.kind, &access.key).is_some() {
+                return true;
+            }
+        }
+        self.entered_frames().any(|frame| {
+            frame
+                .function
+                .as_deref()
+                .is_some_and(|name| breakpoints.should_break_at(&frame.contract_id, name))
+        })
+    }
+
+    /// Frames of the current step that were not on the stack at the
+    /// previous one
+    fn entered_frames(&self) -> impl Iterator<Item = &RecordedFrame> {
+        let frames = self
+            .current()
+            .map(|step| step.frames.as_slice())
+            .unwrap_or(&[]);
+        let previous = self
+            .position
+            .checked_sub(1)
+            .and_then(|position| self.recording.steps.get(position))
+            .map(|step| step.frames.as_slice())
+            .unwrap_or(&[]);
+        let same = frames
+            .iter()
+            .rev()
+            .zip(previous.iter().rev())
+            .take_while(|(frame, before)| {
+                frame.contract_id == before.contract_id && frame.function == before.function
+            })
+            .count();
+        frames.iter().rev().skip(same)
+    }
+
+    /// Storage of every contract at the current position, with the writes
+    /// of the steps before it applied
+    pub fn storage(&self) -> Vec<StorageEntry> {
+        let mut entries = self.recording.initial_storage.clone();
+        for step in &self.recording.steps[..self.position] {
+            let Some(access) = step
+                .access
+                .as_ref()
+                .filter(|access| access.kind == AccessKind::Write)
+            else {
+                continue;
+            };
+            let existing = entries.iter().position(|entry| {
+                entry.contract == step.contract_id
+                    && entry.durability == access.durability
+                    && entry.key == access.key
+            });
+            match (existing, &access.new_value) {
+                (Some(i), Some(value)) => entries[i].value = value.clone(),
+                (Some(i), None) => {
+                    entries.remove(i);
+                }
+                (None, Some(value)) => entries.push(StorageEntry {
+                    contract: step.contract_id.clone(),
+                    durability: access.durability,
+                    key: access.key.clone(),
+                    value: value.clone(),
+                    live_until_ledger: None,
+                }),
+                (None, None) => {}
+            }
+        }
+        entries.sort_by(|a, b| {
+            (&a.contract, a.durability.as_str(), &a.key).cmp(&(
+                &b.contract,
+                b.durability.as_str(),
+                &b.key,
+            ))
+        });
+        entries
+    }
+
+    /// Contract events emitted before the current position
+    pub fn events(&self) -> &[ContractEvent] {
+        let emitted = self
+            .current()
+            .map(|step| step.events)
+            .unwrap_or(self.recording.events.len());
+        &self.recording.events[..emitted.min(self.recording.events.len())]
+    }
+
+    /// Budget consumed at the current position
+    pub fn budget(&self) -> RecordedBudget {
+        self.current()
+            .map(|step| step.budget)
+            .unwrap_or(self.recording.final_budget)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTRACT: &str = "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABSC4";
+
+    fn frame(function: &str) -> RecordedFrame {
+        RecordedFrame {
+            contract_id: CONTRACT.to_string(),
+            function: Some(function.to_string()),
+            offset: 0,
+        }
+    }
+
+    fn step(function: &str, frames: &[&str], access: Option<RecordedAccess>) -> RecordedStep {
+        RecordedStep {
+            contract_id: CONTRACT.to_string(),
+            frames: frames.iter().rev().map(|name| frame(name)).collect(),
+            function: function.to_string(),
+            call: format!("{}()", function),
+            args: Vec::new(),
+            access,
+            events: 0,
+            budget: RecordedBudget::default(),
+        }
+    }
+
+    fn write(key: &str, old: Option<&str>, new: Option<&str>) -> Option<RecordedAccess> {
+        Some(RecordedAccess {
+            kind: AccessKind::Write,
+            durability: StorageDurability::Instance,
+            key: key.to_string(),
+            old_value: old.map(str::to_string),
+            new_value: new.map(str::to_string),
+        })
+    }
+
+    fn recording() -> Recording {
+        Recording {
+            version: RECORDING_VERSION,
+            contract_id: CONTRACT.to_string(),
+            function: "increment".to_string(),
+            args: None,
+            initial_storage: Vec::new(),
+            cpu_limit: 0,
+            memory_limit: 0,
+            initial_budget: RecordedBudget::default(),
+            steps: vec![
+                step("has_contract_data", &["increment"], None),
+                step(
+                    "put_contract_data",
+                    &["increment"],
+                    write("count", None, Some("1")),
+                ),
+                step("call", &["increment", "bump"], None),
+                step(
+                    "put_contract_data",
+                    &["increment"],
+                    write("count", Some("1"), Some("2")),
+                ),
+            ],
+            events: Vec::new(),
+            final_budget: RecordedBudget::default(),
+            outcome: "2".to_string(),
+            failed: false,
+        }
+    }
+
+    #[test]
+    fn test_storage_follows_position() {
+        let mut replay = Replay::new(recording());
+        assert!(replay.at_end());
+        assert_eq!(replay.storage()[0].value, "2");
+
+        assert!(replay.step_back());
+        assert_eq!(replay.current().unwrap().function, "put_contract_data");
+        assert_eq!(replay.storage()[0].value, "1");
+
+        assert!(replay.seek(1));
+        assert!(replay.storage().is_empty());
+        assert!(!replay.seek(5));
+        assert!(replay.seek(0));
+        assert!(!replay.step_back());
+    }
+
+    #[test]
+    fn test_continue_stops_at_breakpoints_and_watchpoints() {
+        let mut breakpoints = BreakpointManager::new();
+        let mut watchpoints = WatchpointManager::new();
+        let mut replay = Replay::new(recording());
+
+        // Backward from the end to the last write of the watched key
+        watchpoints.add("count write").unwrap();
+        assert!(replay.reverse_continue(&breakpoints, &watchpoints));
+        assert_eq!(replay.position(), 3);
+        assert!(replay.reverse_continue(&breakpoints, &watchpoints));
+        assert_eq!(replay.position(), 1);
+        assert!(!replay.reverse_continue(&breakpoints, &watchpoints));
+        assert_eq!(replay.position(), 0);
+
+        // Function breakpoints stop at the first call after entering
+        watchpoints.clear();
+        breakpoints.add("bump");
+        assert!(replay.continue_forward(&breakpoints, &watchpoints));
+        assert_eq!(replay.position(), 2);
+        assert!(!replay.continue_forward(&breakpoints, &watchpoints));
+        assert!(replay.at_end());
+
+        breakpoints.add("host:has_contract_data");
+        assert!(replay.reverse_continue(&breakpoints, &watchpoints));
+        assert_eq!(replay.position(), 2);
+        assert!(replay.reverse_continue(&breakpoints, &watchpoints));
+        assert_eq!(replay.position(), 0);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::debugger::instruction_pointer::{InstructionPointer, StepMode};
+use crate::debugger::trace::InstructionTrace;
 use crate::inspector::stack::CallStackInspector;
 use crate::runtime::instruction::Instruction;
 
@@ -10,6 +11,9 @@ pub struct DebugState {
     instruction_pointer: InstructionPointer,
     current_instruction: Option<Instruction>,
     instructions: Vec<Instruction>,
+    /// Path taken by the last instrumented execution; stepping follows it
+    /// when it is not empty
+    trace: InstructionTrace,
     instruction_debug_enabled: bool,
     call_stack: CallStackInspector,
 }
@@ -23,6 +27,7 @@ impl DebugState {
             instruction_pointer: InstructionPointer::new(),
             current_instruction: None,
             instructions: Vec::new(),
+            trace: InstructionTrace::default(),
             instruction_debug_enabled: false,
             call_stack: CallStackInspector::new(),
         }
@@ -46,6 +51,7 @@ impl DebugState {
 
     pub fn set_instructions(&mut self, instructions: Vec<Instruction>) {
         self.instructions = instructions;
+        self.trace = InstructionTrace::default();
         self.current_instruction = self.instructions.first().cloned();
         self.instruction_pointer.reset();
     }
@@ -54,6 +60,27 @@ impl DebugState {
         &self.instructions
     }
 
+    /// Follow a recorded execution trace, starting at its first step.
+    pub fn set_trace(&mut self, trace: InstructionTrace) {
+        self.trace = trace;
+        self.instruction_pointer.clear_history();
+        match self.trace.get(0) {
+            Some(step) => {
+                self.instruction_pointer
+                    .set_trace_step(0, step.instruction_index, step.depth);
+                self.current_instruction = self.instructions.get(step.instruction_index).cloned();
+            }
+            None => {
+                self.instruction_pointer.set_trace_step(0, 0, 0);
+                self.current_instruction = self.instructions.first().cloned();
+            }
+        }
+    }
+
+    pub fn trace(&self) -> &InstructionTrace {
+        &self.trace
+    }
+
     pub fn current_instruction(&self) -> Option<&Instruction> {
         self.current_instruction.as_ref()
     }
@@ -104,12 +131,33 @@ impl DebugState {
         self.current_instruction.as_ref()
     }
 
+    /// Move to the next instruction: the next executed step when a trace
+    /// has been recorded, otherwise the next instruction in the binary.
     pub fn next_instruction(&mut self) -> Option<&Instruction> {
+        if !self.trace.is_empty() {
+            let position = self.instruction_pointer.trace_position() + 1;
+            let step = self.trace.get(position)?;
+            self.instruction_pointer
+                .move_to_step(position, step.instruction_index, step.depth);
+            self.current_instruction = self.instructions.get(step.instruction_index).cloned();
+            return self.current_instruction.as_ref();
+        }
+
         let next_index = self.instruction_pointer.current_index().saturating_add(1);
         self.advance_to_instruction(next_index)
     }
 
     pub fn previous_instruction(&mut self) -> Option<&Instruction> {
+        if !self.trace.is_empty() {
+            let position = self.instruction_pointer.trace_position().checked_sub(1)?;
+            let step = self.trace.get(position)?;
+            self.instruction_pointer.step_back();
+            self.instruction_pointer
+                .set_trace_step(position, step.instruction_index, step.depth);
+            self.current_instruction = self.instructions.get(step.instruction_index).cloned();
+            return self.current_instruction.as_ref();
+        }
+
         let prev_index = self.instruction_pointer.step_back()?;
         self.current_instruction = self.instructions.get(prev_index).cloned();
         self.current_instruction.as_ref()
@@ -138,7 +186,8 @@ impl DebugState {
         self.current_function = None;
         self.step_count = 0;
         self.instruction_pointer.reset();
-        self.current_instruction = self.instructions.first().cloned();
+        let trace = std::mem::take(&mut self.trace);
+        self.set_trace(trace);
         self.call_stack.clear();
     }
 
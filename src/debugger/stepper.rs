@@ -1,6 +1,7 @@
 use crate::debugger::instruction_pointer::StepMode;
 use crate::debugger::state::DebugState;
 use crate::runtime::instruction::Instruction;
+use crate::utils::SourceMap;
 
 /// Handles step-through execution of contracts at instruction level
 pub struct Stepper {
@@ -97,6 +98,44 @@ impl Stepper {
         self.find_next_control_flow_instruction(debug_state)
     }
 
+    /// Step to the next source line, stopping at the first line of a called
+    /// function. Without a source map, steps one instruction.
+    pub fn step_into_line(
+        &mut self,
+        debug_state: &mut DebugState,
+        source_map: Option<&SourceMap>,
+    ) -> bool {
+        let Some(source_map) = source_map else {
+            return self.step_into(debug_state);
+        };
+        if !self.active {
+            return false;
+        }
+
+        self.step_mode = StepMode::StepIntoLine;
+        debug_state.start_instruction_stepping(StepMode::StepIntoLine);
+        self.find_next_line(debug_state, source_map, false)
+    }
+
+    /// Step to the next source line without entering calls. Without a source
+    /// map, steps over one instruction.
+    pub fn step_over_line(
+        &mut self,
+        debug_state: &mut DebugState,
+        source_map: Option<&SourceMap>,
+    ) -> bool {
+        let Some(source_map) = source_map else {
+            return self.step_over(debug_state);
+        };
+        if !self.active {
+            return false;
+        }
+
+        self.step_mode = StepMode::StepOverLine;
+        debug_state.start_instruction_stepping(StepMode::StepOverLine);
+        self.find_next_line(debug_state, source_map, true)
+    }
+
     /// Step backwards to previous instruction
     pub fn step_back(&mut self, debug_state: &mut DebugState) -> bool {
         if !self.active {
@@ -125,8 +164,10 @@ impl Stepper {
 
         // Check step mode specific conditions
         match self.step_mode {
-            StepMode::StepInto => true,
-            StepMode::StepOver => {
+            // Line steps are resolved against the source map when stepping;
+            // here they pause as their instruction-level counterparts
+            StepMode::StepInto | StepMode::StepIntoLine => true,
+            StepMode::StepOver | StepMode::StepOverLine => {
                 // Pause if we're at same or lower call depth
                 let target_depth = debug_state.instruction_pointer().call_stack_depth();
                 debug_state.instruction_pointer().call_stack_depth() <= target_depth
@@ -164,15 +205,13 @@ impl Stepper {
     }
 
     /// Find next instruction at same or lower call depth
+    ///
+    /// Walks the recorded trace when there is one, so calls are followed
+    /// along the path they actually took.
     fn find_next_instruction_at_depth(&self, debug_state: &mut DebugState) -> bool {
         let target_depth = debug_state.instruction_pointer().call_stack_depth();
 
-        // Simulate stepping through instructions to find the right depth
-        for _ in 0..1000 {
-            // Prevent infinite loop
-            if debug_state.next_instruction().is_none() {
-                break;
-            }
+        while debug_state.next_instruction().is_some() {
             if debug_state.instruction_pointer().call_stack_depth() <= target_depth {
                 return true;
             }
@@ -185,11 +224,7 @@ impl Stepper {
     fn find_next_instruction_at_lower_depth(&self, debug_state: &mut DebugState) -> bool {
         let target_depth = debug_state.instruction_pointer().call_stack_depth();
 
-        for _ in 0..1000 {
-            // Prevent infinite loop
-            if debug_state.next_instruction().is_none() {
-                break;
-            }
+        while debug_state.next_instruction().is_some() {
             if debug_state.instruction_pointer().call_stack_depth() < target_depth {
                 return true;
             }
@@ -198,16 +233,43 @@ impl Stepper {
         false
     }
 
-    /// Find next control flow instruction
-    fn find_next_control_flow_instruction(&self, debug_state: &mut DebugState) -> bool {
-        for _ in 0..1000 {
-            // Prevent infinite loop
-            if let Some(inst) = debug_state.next_instruction() {
-                if inst.is_control_flow() {
+    /// Find the next instruction on another source line. Instructions
+    /// without a line are passed over, as are deeper calls when
+    /// `same_depth` is set.
+    fn find_next_line(
+        &self,
+        debug_state: &mut DebugState,
+        source_map: &SourceMap,
+        same_depth: bool,
+    ) -> bool {
+        let line_of = |debug_state: &DebugState| {
+            debug_state
+                .current_instruction()
+                .and_then(|instruction| source_map.get_line(instruction.offset))
+        };
+        let start_depth = debug_state.instruction_pointer().call_stack_depth();
+        let start = line_of(debug_state);
+
+        while debug_state.next_instruction().is_some() {
+            let depth = debug_state.instruction_pointer().call_stack_depth();
+            if same_depth && depth > start_depth {
+                continue;
+            }
+            if let Some(line) = line_of(debug_state) {
+                if depth != start_depth || Some(line) != start {
                     return true;
                 }
-            } else {
-                break;
+            }
+        }
+
+        false
+    }
+
+    /// Find next control flow instruction
+    fn find_next_control_flow_instruction(&self, debug_state: &mut DebugState) -> bool {
+        while let Some(inst) = debug_state.next_instruction() {
+            if inst.is_control_flow() {
+                return true;
             }
         }
 
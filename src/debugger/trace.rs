@@ -0,0 +1,123 @@
+//! Dynamic instruction trace recorded from an instrumented execution
+
+use crate::runtime::instruction::Instruction;
+use crate::runtime::instrumentation::{Probe, ProbeKind};
+
+/// One executed instruction (or basic block) in a recorded trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceStep {
+    /// Index into the parsed instruction list
+    pub instruction_index: usize,
+    /// Number of WASM frames below the one executing the instruction
+    pub depth: u32,
+}
+
+/// The path a contract call actually took, in execution order
+#[derive(Debug, Clone, Default)]
+pub struct InstructionTrace {
+    steps: Vec<TraceStep>,
+}
+
+impl InstructionTrace {
+    /// Create a trace from recorded steps
+    pub fn new(steps: Vec<TraceStep>) -> Self {
+        Self { steps }
+    }
+
+    /// Build a trace from the probes fired by an instrumented run.
+    ///
+    /// Entry and exit probes track the call depth; every other probe becomes a
+    /// step at the instruction it reports. Probes whose offset is not in
+    /// `instructions` are skipped.
+    pub fn from_probes(probes: &[Probe], instructions: &[Instruction]) -> Self {
+        let mut frames: u32 = 0;
+        let mut steps = Vec::new();
+
+        for probe in probes {
+            match probe.kind {
+                ProbeKind::Enter => frames += 1,
+                ProbeKind::Exit => frames = frames.saturating_sub(1),
+                ProbeKind::Instruction | ProbeKind::Block => {
+                    if let Ok(instruction_index) = instructions
+                        .binary_search_by_key(&probe.offset, |instruction| instruction.offset)
+                    {
+                        steps.push(TraceStep {
+                            instruction_index,
+                            depth: frames.saturating_sub(1),
+                        });
+                    }
+                }
+            }
+        }
+
+        Self { steps }
+    }
+
+    /// Recorded steps in execution order
+    pub fn steps(&self) -> &[TraceStep] {
+        &self.steps
+    }
+
+    /// Step at a position in the trace
+    pub fn get(&self, position: usize) -> Option<TraceStep> {
+        self.steps.get(position).copied()
+    }
+
+    /// Number of recorded steps
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Check if nothing was recorded
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasmparser::Operator;
+
+    fn probe(kind: ProbeKind, offset: usize) -> Probe {
+        Probe {
+            kind,
+            function_index: 0,
+            offset,
+        }
+    }
+
+    #[test]
+    fn test_from_probes_tracks_depth() {
+        let instructions = vec![
+            Instruction::new(0x10, Operator::Call { function_index: 1 }, 0, 0),
+            Instruction::new(0x12, Operator::End, 0, 1),
+            Instruction::new(0x20, Operator::Nop, 1, 0),
+            Instruction::new(0x21, Operator::End, 1, 1),
+        ];
+        let probes = vec![
+            probe(ProbeKind::Enter, 0x10),
+            probe(ProbeKind::Instruction, 0x10),
+            probe(ProbeKind::Enter, 0x20),
+            probe(ProbeKind::Instruction, 0x20),
+            probe(ProbeKind::Exit, 0x21),
+            probe(ProbeKind::Instruction, 0x99),
+            probe(ProbeKind::Exit, 0x12),
+        ];
+
+        let trace = InstructionTrace::from_probes(&probes, &instructions);
+        assert_eq!(
+            trace.steps(),
+            &[
+                TraceStep {
+                    instruction_index: 0,
+                    depth: 0
+                },
+                TraceStep {
+                    instruction_index: 2,
+                    depth: 1
+                },
+            ]
+        );
+    }
+}
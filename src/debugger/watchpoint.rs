@@ -0,0 +1,170 @@
+use crate::inspector::storage::FilterPattern;
+use crate::runtime::interpreter::AccessKind;
+use crate::{DebuggerError, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// Which storage accesses a watchpoint stops on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchAccess {
+    Read,
+    Write,
+    /// Reads and writes
+    Any,
+}
+
+impl WatchAccess {
+    fn covers(&self, kind: AccessKind) -> bool {
+        matches!(
+            (self, kind),
+            (WatchAccess::Any, _)
+                | (WatchAccess::Read, AccessKind::Read)
+                | (WatchAccess::Write, AccessKind::Write)
+        )
+    }
+}
+
+/// A storage watchpoint.
+///
+/// Written as `<key-pattern> [read|write]`, where the pattern uses the
+/// `--storage-filter` syntax: `prefix*`, `re:<regex>` or an exact key.
+/// Without `read` or `write`, both kinds of access stop.
+#[derive(Debug, Clone)]
+pub struct Watchpoint {
+    /// Key pattern as written
+    pub pattern: String,
+    pub access: WatchAccess,
+    filter: FilterPattern,
+}
+
+impl Watchpoint {
+    /// Whether an access of `kind` to the storage key `key`, rendered as in
+    /// storage listings, stops at this watchpoint
+    pub fn matches(&self, kind: AccessKind, key: &str) -> bool {
+        self.access.covers(kind) && self.filter.matches(key)
+    }
+}
+
+impl FromStr for Watchpoint {
+    type Err = anyhow::Error;
+
+    fn from_str(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        let (pattern, access) = match spec.rsplit_once(char::is_whitespace) {
+            Some((pattern, "read")) => (pattern.trim_end(), WatchAccess::Read),
+            Some((pattern, "write")) => (pattern.trim_end(), WatchAccess::Write),
+            _ => (spec, WatchAccess::Any),
+        };
+        if pattern.is_empty() {
+            return Err(DebuggerError::BreakpointError(format!(
+                "Invalid watchpoint '{}' (expected '<key-pattern> [read|write]')",
+                spec
+            ))
+            .into());
+        }
+        let filter = FilterPattern::parse(pattern).map_err(DebuggerError::BreakpointError)?;
+        Ok(Self {
+            pattern: pattern.to_string(),
+            access,
+            filter,
+        })
+    }
+}
+
+impl fmt::Display for Watchpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.access {
+            WatchAccess::Read => write!(f, "{} read", self.pattern),
+            WatchAccess::Write => write!(f, "{} write", self.pattern),
+            WatchAccess::Any => write!(f, "{}", self.pattern),
+        }
+    }
+}
+
+/// Manages storage watchpoints, keyed by pattern
+#[derive(Default)]
+pub struct WatchpointManager {
+    watchpoints: Vec<Watchpoint>,
+}
+
+impl WatchpointManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a watchpoint written as `<key-pattern> [read|write]`, replacing
+    /// any other on the same pattern
+    pub fn add(&mut self, spec: &str) -> Result<()> {
+        let watchpoint: Watchpoint = spec.parse()?;
+        self.remove(&watchpoint.pattern);
+        self.watchpoints.push(watchpoint);
+        Ok(())
+    }
+
+    /// Remove the watchpoint on a pattern
+    pub fn remove(&mut self, pattern: &str) -> bool {
+        let before = self.watchpoints.len();
+        self.watchpoints
+            .retain(|watchpoint| watchpoint.pattern != pattern);
+        self.watchpoints.len() != before
+    }
+
+    /// First watchpoint an access of `kind` to `key` stops at
+    pub fn matching(&self, kind: AccessKind, key: &str) -> Option<&Watchpoint> {
+        self.watchpoints
+            .iter()
+            .find(|watchpoint| watchpoint.matches(kind, key))
+    }
+
+    /// List all watchpoints in spec form
+    pub fn list(&self) -> Vec<String> {
+        self.watchpoints.iter().map(ToString::to_string).collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.watchpoints.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.watchpoints.is_empty()
+    }
+
+    pub fn count(&self) -> usize {
+        self.watchpoints.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_access_kind() {
+        let watchpoint: Watchpoint = "balance:* write".parse().unwrap();
+        assert_eq!(watchpoint.pattern, "balance:*");
+        assert_eq!(watchpoint.access, WatchAccess::Write);
+        assert!(watchpoint.matches(AccessKind::Write, "balance:alice"));
+        assert!(!watchpoint.matches(AccessKind::Read, "balance:alice"));
+        assert!(!watchpoint.matches(AccessKind::Write, "admin"));
+
+        let any: Watchpoint = "COUNTER".parse().unwrap();
+        assert_eq!(any.access, WatchAccess::Any);
+        assert!(any.matches(AccessKind::Read, "COUNTER"));
+        assert_eq!(any.to_string(), "COUNTER");
+
+        assert!("re:[ read".parse::<Watchpoint>().is_err());
+        assert!("read".parse::<Watchpoint>().unwrap().pattern == "read");
+    }
+
+    #[test]
+    fn test_manager_replaces_same_pattern() {
+        let mut manager = WatchpointManager::new();
+        manager.add("re:^bal read").unwrap();
+        manager.add("re:^bal write").unwrap();
+        assert_eq!(manager.count(), 1);
+        assert!(manager.matching(AccessKind::Write, "balance").is_some());
+        assert!(manager.matching(AccessKind::Read, "balance").is_none());
+        assert!(manager.remove("re:^bal"));
+        assert!(manager.is_empty());
+    }
+}
@@ -50,7 +50,7 @@ impl BudgetInspector {
 }
 
 /// Budget information snapshot
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct BudgetInfo {
     pub cpu_instructions: u64,
     pub cpu_limit: u64,
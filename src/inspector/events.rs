@@ -1,40 +1,90 @@
+use crate::runtime::instrumentation::Probe;
+use crate::utils::scval::scval_to_json;
 use crate::Result;
-use soroban_env_host::{xdr::ContractEventBody, Host};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use soroban_env_host::xdr::{self, ContractEventBody, ContractEventType, Hash, ScAddress, ScVal};
+use soroban_env_host::Host;
 
 /// Represents a captured contract event
-#[derive(Debug, Clone)]
+///
+/// Topics and data are rendered with [`scval_to_json`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ContractEvent {
     pub contract_id: Option<String>,
-    pub topics: Vec<String>,
-    pub data: String,
+    pub topics: Vec<Value>,
+    pub data: Value,
+}
+
+impl ContractEvent {
+    /// Topics as comma-separated JSON, for display
+    pub fn topics_text(&self) -> String {
+        self.topics
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// A contract call or return recorded in the host's diagnostic events
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallEvent {
+    Call {
+        contract_id: String,
+        function: String,
+    },
+    Return {
+        contract_id: Option<String>,
+        function: String,
+    },
 }
 
 pub struct EventInspector;
 
+/// Render a contract hash as a `C...` strkey
+fn contract_strkey(hash: &Hash) -> String {
+    ScAddress::Contract(hash.clone()).to_string()
+}
+
 impl EventInspector {
-    /// Extract events from the host and convert them to a friendly format
+    /// Extract the events contracts and the host published, without the
+    /// diagnostic trace, and convert them to a friendly format
     pub fn get_events(host: &Host) -> Result<Vec<ContractEvent>> {
-        let events = host.get_events()?.0;
+        Ok(Self::convert(
+            host.get_events()?
+                .0
+                .iter()
+                .map(|e| &e.event)
+                .filter(|e| e.type_ != ContractEventType::Diagnostic),
+        ))
+    }
+
+    /// Extract the diagnostic event log (calls, returns, contract logs and
+    /// errors) from the host in the same format
+    pub fn get_diagnostic_events(host: &Host) -> Result<Vec<ContractEvent>> {
+        Ok(Self::convert(
+            host.get_diagnostic_events()?.0.iter().map(|e| &e.event),
+        ))
+    }
+
+    fn convert<'a>(events: impl Iterator<Item = &'a xdr::ContractEvent>) -> Vec<ContractEvent> {
         let mut contract_events = Vec::new();
 
-        for host_event in events.iter() {
-            let event = &host_event.event;
+        for event in events {
+            if Probe::is_probe_event(event) {
+                continue;
+            }
 
             // Extract topics and data from event body
             let (topics, data) = match &event.body {
-                ContractEventBody::V0(v0) => {
-                    let mut topics = Vec::new();
-                    for topic in v0.topics.iter() {
-                        topics.push(format!("{:?}", topic));
-                    }
-                    let data = format!("{:?}", v0.data);
-                    (topics, data)
-                }
+                ContractEventBody::V0(v0) => (
+                    v0.topics.iter().map(scval_to_json).collect(),
+                    scval_to_json(&v0.data),
+                ),
             };
 
-            // Parse contract ID
-            // contract_id is Option<Hash>
-            let contract_id = event.contract_id.as_ref().map(|h| format!("{:?}", h));
+            let contract_id = event.contract_id.as_ref().map(contract_strkey);
 
             contract_events.push(ContractEvent {
                 contract_id,
@@ -43,14 +93,51 @@ impl EventInspector {
             });
         }
 
-        Ok(contract_events)
+        contract_events
+    }
+
+    /// Decode the `fn_call` / `fn_return` diagnostic events into a call trace
+    pub fn call_events(events: &[xdr::ContractEvent]) -> Vec<CallEvent> {
+        events
+            .iter()
+            .filter_map(|event| {
+                let ContractEventBody::V0(body) = &event.body;
+                let symbol = |val: &ScVal| match val {
+                    ScVal::Symbol(s) => Some(s.to_utf8_string_lossy()),
+                    _ => None,
+                };
+                match body.topics.as_slice() {
+                    [kind, ScVal::Bytes(callee), function]
+                        if symbol(kind).as_deref() == Some("fn_call") =>
+                    {
+                        let callee: [u8; 32] = callee.as_slice().try_into().ok()?;
+                        Some(CallEvent::Call {
+                            contract_id: contract_strkey(&Hash(callee)),
+                            function: symbol(function)?,
+                        })
+                    }
+                    [kind, function] if symbol(kind).as_deref() == Some("fn_return") => {
+                        Some(CallEvent::Return {
+                            contract_id: event.contract_id.as_ref().map(contract_strkey),
+                            function: symbol(function)?,
+                        })
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
     }
 
     /// Filter events by a topic string
     pub fn filter_events(events: &[ContractEvent], topic_filter: &str) -> Vec<ContractEvent> {
         events
             .iter()
-            .filter(|e| e.topics.iter().any(|t| t.contains(topic_filter)))
+            .filter(|e| {
+                e.topics.iter().any(|t| match t {
+                    Value::String(s) => s.contains(topic_filter),
+                    other => other.to_string().contains(topic_filter),
+                })
+            })
             .cloned()
             .collect()
     }
@@ -59,24 +146,74 @@ impl EventInspector {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use soroban_env_host::xdr::{ContractEventType, ContractEventV0, ExtensionPoint, ScSymbol};
+
+    fn diagnostic(contract: Option<[u8; 32]>, topics: Vec<ScVal>) -> xdr::ContractEvent {
+        xdr::ContractEvent {
+            ext: ExtensionPoint::V0,
+            contract_id: contract.map(Hash),
+            type_: ContractEventType::Diagnostic,
+            body: ContractEventBody::V0(ContractEventV0 {
+                topics: topics.try_into().unwrap(),
+                data: ScVal::Void,
+            }),
+        }
+    }
+
+    fn symbol(s: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(s.try_into().unwrap()))
+    }
+
+    #[test]
+    fn test_call_events_decode_calls_and_returns() {
+        let callee = [1u8; 32];
+        let events = vec![
+            diagnostic(
+                None,
+                vec![
+                    symbol("fn_call"),
+                    ScVal::Bytes(callee.to_vec().try_into().unwrap()),
+                    symbol("echo"),
+                ],
+            ),
+            diagnostic(None, vec![symbol("log")]),
+            diagnostic(Some(callee), vec![symbol("fn_return"), symbol("echo")]),
+        ];
+
+        let calls = EventInspector::call_events(&events);
+        let contract_id = contract_strkey(&Hash(callee));
+        assert_eq!(
+            calls,
+            vec![
+                CallEvent::Call {
+                    contract_id: contract_id.clone(),
+                    function: "echo".to_string(),
+                },
+                CallEvent::Return {
+                    contract_id: Some(contract_id),
+                    function: "echo".to_string(),
+                },
+            ]
+        );
+    }
 
     #[test]
     fn test_filter_events() {
         let events = vec![
             ContractEvent {
                 contract_id: None,
-                topics: vec!["topic1".to_string(), "common".to_string()],
-                data: "data1".to_string(),
+                topics: vec!["topic1".into(), "common".into()],
+                data: "data1".into(),
             },
             ContractEvent {
                 contract_id: None,
-                topics: vec!["topic2".to_string(), "common".to_string()],
-                data: "data2".to_string(),
+                topics: vec!["topic2".into(), "common".into()],
+                data: "data2".into(),
             },
             ContractEvent {
                 contract_id: None,
-                topics: vec!["topic3".to_string()],
-                data: "data3".to_string(),
+                topics: vec!["topic3".into()],
+                data: "data3".into(),
             },
         ];
 
@@ -1,9 +1,12 @@
+use crate::utils::scval::scval_to_string;
 use anyhow::{Context, Result};
 use crossterm::style::{Color, Stylize};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use soroban_env_host::budget::Budget;
+use soroban_env_host::xdr::{ContractDataDurability, LedgerEntryData, ScVal};
 use soroban_env_host::Host;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
@@ -18,13 +21,120 @@ pub enum FilterPattern {
     Exact(String),
 }
 
+/// Durability class of a contract storage entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageDurability {
+    Persistent,
+    Temporary,
+    Instance,
+}
+
+impl StorageDurability {
+    /// Parse a durability name (`persistent`, `temporary` or `instance`)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "persistent" => Some(StorageDurability::Persistent),
+            "temporary" => Some(StorageDurability::Temporary),
+            "instance" => Some(StorageDurability::Instance),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StorageDurability::Persistent => "persistent",
+            StorageDurability::Temporary => "temporary",
+            StorageDurability::Instance => "instance",
+        }
+    }
+}
+
+impl std::fmt::Display for StorageDurability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single contract storage entry captured from the host
+///
+/// Keys and values are rendered with [`crate::utils::scval::scval_to_string`],
+/// i.e. in the same format `--storage` accepts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageEntry {
+    pub contract: String,
+    pub durability: StorageDurability,
+    pub key: String,
+    pub value: String,
+    pub live_until_ledger: Option<u32>,
+}
+
 /// Storage state snapshot for import/export
+///
+/// `entries` holds persistent storage (the only section older files have);
+/// temporary and instance entries are kept in their own sections.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct StorageState {
     pub entries: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub temporary: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub instance: HashMap<String, String>,
 }
 
 impl StorageState {
+    /// Build a storage state from captured entries
+    pub fn from_entries(entries: &[StorageEntry]) -> Self {
+        let mut state = StorageState::default();
+        for entry in entries {
+            let section = match entry.durability {
+                StorageDurability::Persistent => &mut state.entries,
+                StorageDurability::Temporary => &mut state.temporary,
+                StorageDurability::Instance => &mut state.instance,
+            };
+            section.insert(entry.key.clone(), entry.value.clone());
+        }
+        state
+    }
+
+    /// Total number of entries across all sections
+    pub fn len(&self) -> usize {
+        self.entries.len() + self.temporary.len() + self.instance.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Render as the sectioned JSON accepted by `ContractExecutor::set_initial_storage`
+    pub fn to_initial_storage_json(&self) -> Result<String> {
+        let mut sections = serde_json::Map::new();
+        for (name, section) in [
+            ("persistent", &self.entries),
+            ("temporary", &self.temporary),
+            ("instance", &self.instance),
+        ] {
+            if !section.is_empty() {
+                sections.insert(name.to_string(), serde_json::to_value(section)?);
+            }
+        }
+        Ok(serde_json::Value::Object(sections).to_string())
+    }
+
+    /// Write this storage state to a JSON file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let json =
+            serde_json::to_string_pretty(self).context("Failed to serialize storage state")?;
+        fs::write(path.as_ref(), json).context("Failed to write storage file")?;
+        Ok(())
+    }
+
+    /// Read a storage state from a JSON file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let contents = fs::read_to_string(path.as_ref()).context("Failed to read storage file")?;
+        serde_json::from_str(&contents).context("Failed to parse storage JSON")
+    }
+
     /// Export storage state to JSON file
     pub fn export_to_file<P: AsRef<Path>>(
         entries: &HashMap<String, String>,
@@ -32,19 +142,14 @@ impl StorageState {
     ) -> Result<()> {
         let state = StorageState {
             entries: entries.clone(),
+            ..Default::default()
         };
-        let json =
-            serde_json::to_string_pretty(&state).context("Failed to serialize storage state")?;
-        fs::write(path.as_ref(), json).context("Failed to write storage file")?;
-        Ok(())
+        state.save(path)
     }
 
     /// Import storage state from JSON file
     pub fn import_from_file<P: AsRef<Path>>(path: P) -> Result<HashMap<String, String>> {
-        let contents = fs::read_to_string(path.as_ref()).context("Failed to read storage file")?;
-        let state: StorageState =
-            serde_json::from_str(&contents).context("Failed to parse storage JSON")?;
-        Ok(state.entries)
+        Ok(Self::load(path)?.entries)
     }
 }
 
@@ -309,19 +414,186 @@ impl StorageInspector {
         println!();
     }
 
-    /// Capture a snapshot of all storage entries from the host
-    pub fn capture_snapshot(_host: &Host) -> HashMap<String, String> {
-        // In a real implementation, we would iterate through host.get_ledger_entries()
-        // or track changes via a custom Storage instance.
-        // For this debugger, we'll try to extract what's available.
-        // Since Host doesn't easily expose all entries without XDR iteration,
-        // we'll use a placeholder logic that would be backed by actual storage tracking
-        // in a production environment.
+    /// Create an inspector showing the given captured entries
+    pub fn from_entries(entries: &[StorageEntry]) -> Self {
+        Self {
+            storage: Self::to_flat_map(entries),
+            ..Self::new()
+        }
+    }
+
+    /// Capture every contract storage entry held by the host
+    ///
+    /// Walks the host's ledger storage map, expanding each contract's instance
+    /// storage into individual entries. Entries are sorted by contract,
+    /// durability and key.
+    pub fn capture_snapshot(host: &Host) -> Result<Vec<StorageEntry>> {
+        let map = host.with_mut_storage(|storage| Ok(storage.map.clone()))?;
+        // Scan with a throwaway budget so inspection doesn't consume the
+        // contract's own budget.
+        let scan_budget = Budget::default();
+
+        let mut entries = Vec::new();
+        for (_, slot) in map.iter(&scan_budget)? {
+            let Some((entry, live_until)) = slot else {
+                continue;
+            };
+            let LedgerEntryData::ContractData(data) = &entry.data else {
+                continue;
+            };
+            let contract = data.contract.to_string();
+
+            if let (ScVal::LedgerKeyContractInstance, ScVal::ContractInstance(instance)) =
+                (&data.key, &data.val)
+            {
+                for item in instance.storage.iter().flat_map(|m| m.iter()) {
+                    entries.push(StorageEntry {
+                        contract: contract.clone(),
+                        durability: StorageDurability::Instance,
+                        key: scval_to_string(&item.key),
+                        value: scval_to_string(&item.val),
+                        live_until_ledger: *live_until,
+                    });
+                }
+                continue;
+            }
+
+            let durability = match data.durability {
+                ContractDataDurability::Persistent => StorageDurability::Persistent,
+                ContractDataDurability::Temporary => StorageDurability::Temporary,
+            };
+            entries.push(StorageEntry {
+                contract,
+                durability,
+                key: scval_to_string(&data.key),
+                value: scval_to_string(&data.val),
+                live_until_ledger: *live_until,
+            });
+        }
 
-        // NOTE: In Soroban host, entries are typically accessed by key.
-        // To show "everything", we'd need to have tracked access during execution.
+        entries.sort_by(|a, b| {
+            (&a.contract, a.durability.as_str(), &a.key).cmp(&(
+                &b.contract,
+                b.durability.as_str(),
+                &b.key,
+            ))
+        });
+        Ok(entries)
+    }
 
-        HashMap::new()
+    /// Flatten captured entries into a `key -> value` map
+    ///
+    /// A key that appears under more than one durability is qualified as
+    /// `<durability>:<key>` so no entry is lost. When the entries belong to
+    /// more than one contract, every key is qualified as
+    /// `<contract>:<durability>:<key>`.
+    pub fn to_flat_map(entries: &[StorageEntry]) -> HashMap<String, String> {
+        let key = Self::flat_key(entries);
+        entries
+            .iter()
+            .map(|entry| (key(entry), entry.value.clone()))
+            .collect()
+    }
+
+    /// Compute the difference between two captures of the same storage
+    ///
+    /// Keys are flattened as by [`to_flat_map`](Self::to_flat_map), over both
+    /// captures, so an entry keeps its key when a call adds entries that
+    /// would qualify it.
+    pub fn diff_entries(before: &[StorageEntry], after: &[StorageEntry]) -> StorageDiff {
+        let key = Self::flat_key(before.iter().chain(after));
+        let flatten = |entries: &[StorageEntry]| -> HashMap<String, String> {
+            entries
+                .iter()
+                .map(|entry| (key(entry), entry.value.clone()))
+                .collect()
+        };
+        Self::compute_diff(&flatten(before), &flatten(after))
+    }
+
+    /// How [`to_flat_map`](Self::to_flat_map) keys each of `entries`
+    fn flat_key<'a>(
+        entries: impl IntoIterator<Item = &'a StorageEntry>,
+    ) -> impl Fn(&StorageEntry) -> String {
+        let mut contracts = HashSet::new();
+        let mut durabilities: HashMap<String, HashSet<StorageDurability>> = HashMap::new();
+        for entry in entries {
+            contracts.insert(entry.contract.clone());
+            durabilities
+                .entry(entry.key.clone())
+                .or_default()
+                .insert(entry.durability);
+        }
+
+        let by_contract = contracts.len() > 1;
+        move |entry| {
+            if by_contract {
+                format!("{}:{}:{}", entry.contract, entry.durability, entry.key)
+            } else if durabilities
+                .get(&entry.key)
+                .is_some_and(|durabilities| durabilities.len() > 1)
+            {
+                format!("{}:{}", entry.durability, entry.key)
+            } else {
+                entry.key.clone()
+            }
+        }
+    }
+
+    /// Print captured entries with their durability and TTL
+    pub fn display_entries(entries: &[StorageEntry]) {
+        if entries.is_empty() {
+            println!("Storage: (empty)");
+            return;
+        }
+
+        println!("Storage ({} entries):", entries.len());
+        for entry in entries {
+            let ttl = entry
+                .live_until_ledger
+                .map(|ledger| format!("live until {}", ledger))
+                .unwrap_or_else(|| "no ttl".to_string());
+            println!(
+                "  [{}] {} = {} ({})",
+                entry.durability,
+                entry.key.clone().with(Color::Cyan),
+                entry.value,
+                ttl.with(Color::DarkGrey)
+            );
+        }
+    }
+
+    /// Print captured entries grouped per contract, labelling each group with `describe`
+    pub fn display_entries_by_contract(
+        entries: &[StorageEntry],
+        describe: impl Fn(&str) -> String,
+    ) {
+        let groups = Self::group_by_contract(entries);
+        if groups.len() <= 1 {
+            Self::display_entries(entries);
+            return;
+        }
+
+        for (contract, group) in groups {
+            println!("{}", describe(contract).with(Color::Yellow));
+            Self::display_entries(&group);
+        }
+    }
+
+    /// Captured entries split per contract, in the order the contracts
+    /// first appear
+    pub fn group_by_contract(entries: &[StorageEntry]) -> Vec<(&str, Vec<StorageEntry>)> {
+        let mut groups: Vec<(&str, Vec<StorageEntry>)> = Vec::new();
+        for entry in entries {
+            match groups
+                .iter_mut()
+                .find(|(contract, _)| *contract == entry.contract)
+            {
+                Some((_, group)) => group.push(entry.clone()),
+                None => groups.push((&entry.contract, vec![entry.clone()])),
+            }
+        }
+        groups
     }
 
     /// Compute the difference between two storage snapshots
@@ -402,9 +674,10 @@ impl StorageInspector {
 }
 
 /// Represents the differences between two storage states
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct StorageDiff {
     pub added: HashMap<String, String>,
+    /// Old and new values by key
     pub modified: HashMap<String, (String, String)>,
     pub deleted: Vec<String>,
 }
@@ -675,6 +948,118 @@ mod tests {
         assert_eq!(imported.len(), 0);
     }
 
+    #[test]
+    fn test_storage_state_sections_round_trip() {
+        use tempfile::NamedTempFile;
+
+        let entries = vec![
+            StorageEntry {
+                contract: "C1".to_string(),
+                durability: StorageDurability::Persistent,
+                key: "admin".to_string(),
+                value: "alice".to_string(),
+                live_until_ledger: Some(100),
+            },
+            StorageEntry {
+                contract: "C1".to_string(),
+                durability: StorageDurability::Instance,
+                key: "count".to_string(),
+                value: r#"{"type":"i64","value":7}"#.to_string(),
+                live_until_ledger: Some(100),
+            },
+        ];
+
+        let state = StorageState::from_entries(&entries);
+        assert_eq!(state.len(), 2);
+        assert_eq!(
+            state.instance.get("count").unwrap(),
+            r#"{"type":"i64","value":7}"#
+        );
+
+        let temp_file = NamedTempFile::new().unwrap();
+        state.save(temp_file.path()).unwrap();
+        let loaded = StorageState::load(temp_file.path()).unwrap();
+        assert_eq!(loaded.entries.get("admin"), Some(&"alice".to_string()));
+        assert!(loaded.temporary.is_empty());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&loaded.to_initial_storage_json().unwrap()).unwrap();
+        assert_eq!(json["persistent"]["admin"], "alice");
+        assert!(json.get("temporary").is_none());
+    }
+
+    #[test]
+    fn test_flat_map_qualifies_duplicate_keys() {
+        let entry = |durability, value: &str| StorageEntry {
+            contract: "C1".to_string(),
+            durability,
+            key: "key".to_string(),
+            value: value.to_string(),
+            live_until_ledger: None,
+        };
+        let entries = vec![
+            entry(StorageDurability::Persistent, "p"),
+            entry(StorageDurability::Temporary, "t"),
+        ];
+
+        let flat = StorageInspector::to_flat_map(&entries);
+        assert_eq!(flat.get("persistent:key"), Some(&"p".to_string()));
+        assert_eq!(flat.get("temporary:key"), Some(&"t".to_string()));
+    }
+
+    fn instance_entry(contract: &str, value: &str) -> StorageEntry {
+        StorageEntry {
+            contract: contract.to_string(),
+            durability: StorageDurability::Instance,
+            key: "count".to_string(),
+            value: value.to_string(),
+            live_until_ledger: None,
+        }
+    }
+
+    #[test]
+    fn test_flat_map_qualifies_keys_by_contract() {
+        let entries = vec![instance_entry("C1", "1"), instance_entry("C2", "2")];
+
+        let flat = StorageInspector::to_flat_map(&entries);
+        assert_eq!(flat.len(), 2);
+        assert_eq!(flat.get("C1:instance:count"), Some(&"1".to_string()));
+        assert_eq!(flat.get("C2:instance:count"), Some(&"2".to_string()));
+    }
+
+    #[test]
+    fn test_group_by_contract_keeps_same_keys_of_two_contracts_apart() {
+        let entries = vec![
+            instance_entry("C1", "1"),
+            instance_entry("C2", "2"),
+            instance_entry("C1", "3"),
+        ];
+
+        let groups = StorageInspector::group_by_contract(&entries);
+        assert_eq!(
+            groups,
+            vec![
+                ("C1", vec![entries[0].clone(), entries[2].clone()]),
+                ("C2", vec![entries[1].clone()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_entries_keeps_same_keys_of_two_contracts_apart() {
+        let before = vec![instance_entry("C1", "1")];
+        let after = vec![instance_entry("C1", "2"), instance_entry("C2", "1")];
+
+        let diff = StorageInspector::diff_entries(&before, &after);
+        assert_eq!(
+            diff.modified.get("C1:instance:count"),
+            Some(&("1".to_string(), "2".to_string()))
+        );
+        assert_eq!(diff.added.get("C2:instance:count"), Some(&"1".to_string()));
+        assert_eq!(diff.added.len() + diff.modified.len(), 2);
+        assert!(diff.deleted.is_empty());
+    }
+
     #[test]
     fn test_storage_import_invalid_json() {
         use std::io::Write;
@@ -685,6 +1070,8 @@ mod tests {
 
         let result = StorageState::import_from_file(temp_file.path());
         assert!(result.is_err());
+    }
+
     // ── Storage Access Pattern Analyzer tests ────────────────────────
 
     #[test]
@@ -4,11 +4,13 @@ pub mod cli;
 pub mod codegen;
 pub mod compare;
 pub mod config;
+pub mod dap;
 pub mod debugger;
 pub mod inspector;
 pub mod logging;
 pub mod profiler;
 pub mod repeat;
+pub mod rpc;
 pub mod runtime;
 pub mod simulator;
 pub mod ui;
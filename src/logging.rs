@@ -4,6 +4,34 @@
 //! structured logging across the application using the `tracing` crate.
 
 use std::fmt;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing_subscriber::fmt::writer::{EitherWriter, MakeWriter};
+
+/// Set while log output is dropped, e.g. while the full-screen interface
+/// owns the terminal
+static MUTED: AtomicBool = AtomicBool::new(false);
+
+/// Log output destination: stderr, unless muted with [`set_muted`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogWriter;
+
+impl<'a> MakeWriter<'a> for LogWriter {
+    type Writer = EitherWriter<io::Stderr, io::Sink>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        if MUTED.load(Ordering::Relaxed) {
+            EitherWriter::B(io::sink())
+        } else {
+            EitherWriter::A(io::stderr())
+        }
+    }
+}
+
+/// Drop log output until unmuted, so it does not draw over a full-screen UI.
+pub fn set_muted(muted: bool) {
+    MUTED.store(muted, Ordering::Relaxed);
+}
 
 /// Helper function to format and log multi-line output without structured fields.
 /// Used for formatted displays like tables and summaries.
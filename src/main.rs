@@ -15,7 +15,7 @@ fn initialize_tracing(verbosity: Verbosity) {
     if use_json {
         let json_layer = tracing_subscriber::fmt::layer()
             .json()
-            .with_writer(std::io::stderr)
+            .with_writer(soroban_debugger::logging::LogWriter)
             .with_target(true)
             .with_level(true);
 
@@ -28,7 +28,7 @@ fn initialize_tracing(verbosity: Verbosity) {
             .init();
     } else {
         let fmt_layer = tracing_subscriber::fmt::layer()
-            .with_writer(std::io::stderr)
+            .with_writer(soroban_debugger::logging::LogWriter)
             .with_target(true)
             .with_level(true);
 
@@ -69,6 +69,9 @@ fn main() -> Result<()> {
             soroban_debugger::cli::commands::upgrade_check(args, verbosity)
         }
         Some(Commands::Compare(args)) => soroban_debugger::cli::commands::compare(args),
+        Some(Commands::Replay(args)) => soroban_debugger::cli::commands::replay(args),
+        Some(Commands::Dap(args)) => soroban_debugger::cli::commands::dap(args),
+        Some(Commands::Rpc(args)) => soroban_debugger::cli::commands::rpc(args),
         Some(Commands::Completions(args)) => {
             let mut cmd = Cli::command();
             generate(args.shell, &mut cmd, "soroban-debug", &mut io::stdout());
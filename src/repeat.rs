@@ -2,6 +2,8 @@ use crate::debugger::engine::DebuggerEngine;
 use crate::inspector::budget::{BudgetInfo, BudgetInspector};
 use crate::logging;
 use crate::runtime::executor::ContractExecutor;
+use crate::runtime::manifest::ContractManifest;
+use crate::simulator::SnapshotLoader;
 use crate::Result;
 use std::time::{Duration, Instant};
 
@@ -155,6 +157,8 @@ pub struct RepeatRunner {
     wasm_bytes: Vec<u8>,
     breakpoints: Vec<String>,
     initial_storage: Option<String>,
+    network_snapshot: Option<SnapshotLoader>,
+    manifest: Option<ContractManifest>,
 }
 
 impl RepeatRunner {
@@ -167,9 +171,23 @@ impl RepeatRunner {
             wasm_bytes,
             breakpoints,
             initial_storage,
+            network_snapshot: None,
+            manifest: None,
         }
     }
 
+    /// Start every run from a network snapshot.
+    pub fn with_network_snapshot(mut self, snapshot: SnapshotLoader) -> Self {
+        self.network_snapshot = Some(snapshot);
+        self
+    }
+
+    /// Register the manifest's contracts for every run.
+    pub fn with_manifest(mut self, manifest: ContractManifest) -> Self {
+        self.manifest = Some(manifest);
+        self
+    }
+
     /// Run the contract function `n` times and return aggregate stats.
     pub fn run(&self, function: &str, args: Option<&str>, n: u32) -> Result<AggregateStats> {
         logging::log_repeat_execution(function, n as usize);
@@ -184,7 +202,15 @@ impl RepeatRunner {
             );
 
             // Fresh executor and engine per run for isolation
-            let mut executor = ContractExecutor::new(self.wasm_bytes.clone())?;
+            let mut executor = match &self.network_snapshot {
+                Some(snapshot) => {
+                    ContractExecutor::with_network_snapshot(self.wasm_bytes.clone(), snapshot)?.0
+                }
+                None => ContractExecutor::new(self.wasm_bytes.clone())?,
+            };
+            if let Some(ref manifest) = self.manifest {
+                executor.register_manifest(manifest)?;
+            }
 
             if let Some(ref storage) = self.initial_storage {
                 executor.set_initial_storage(storage.clone())?;
@@ -210,7 +236,7 @@ impl RepeatRunner {
                 iteration: i,
                 duration,
                 budget,
-                result,
+                result: result.result,
             });
         }
 
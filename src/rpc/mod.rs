@@ -0,0 +1,36 @@
+//! Line-delimited JSON-RPC interface for scripting the debugger
+//!
+//! `soroban-debug rpc` lets test harnesses load a contract, call its
+//! functions and inspect storage, events and budget as typed JSON, over
+//! stdio or a Unix socket. See [`Server`] for the methods.
+
+pub mod protocol;
+pub mod server;
+
+pub use protocol::{Connection, Request, RpcError};
+pub use server::Server;
+
+use crate::Result;
+use std::io::{self, BufReader};
+
+/// Serve one client over stdin and stdout
+pub fn serve_stdio() -> Result<()> {
+    Server::new(Connection::new(io::stdin().lock(), io::stdout())).run()
+}
+
+/// Wait for one client on a Unix socket at `path` and serve it. The socket
+/// file is removed afterwards.
+#[cfg(unix)]
+pub fn serve_unix(path: &std::path::Path) -> Result<()> {
+    use anyhow::Context;
+    use std::os::unix::net::UnixListener;
+
+    let listener = UnixListener::bind(path)
+        .with_context(|| format!("Failed to listen on socket {:?}", path))?;
+    eprintln!("Listening for a JSON-RPC client on {}", path.display());
+    let accepted = listener.accept();
+    let _ = std::fs::remove_file(path);
+    let (stream, _) = accepted?;
+    let reader = BufReader::new(stream.try_clone()?);
+    Server::new(Connection::new(reader, stream)).run()
+}
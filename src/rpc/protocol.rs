@@ -0,0 +1,245 @@
+//! Line-delimited JSON-RPC 2.0 framing
+//!
+//! Each message is one line of JSON. Requests carry an `id` that their
+//! response repeats; notifications from the server, such as `paused`, have
+//! none.
+
+use serde_json::{json, Value};
+use std::fmt;
+use std::io::{BufRead, Write};
+
+/// Error codes defined by JSON-RPC 2.0
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// Error code for requests the debugger could not carry out
+pub const DEBUGGER_ERROR: i64 = -32000;
+
+/// A request read from the client
+#[derive(Debug, Clone)]
+pub struct Request {
+    /// `null` for notifications, which get no response
+    pub id: Value,
+    pub method: String,
+    /// The request's `params`, or `null` if it has none
+    pub params: Value,
+}
+
+impl Request {
+    /// A parameter, failing with [`INVALID_PARAMS`] if it is missing
+    pub fn param(&self, name: &str) -> Result<&Value, RpcError> {
+        self.params
+            .get(name)
+            .filter(|value| !value.is_null())
+            .ok_or_else(|| RpcError::invalid_params(format!("missing parameter '{}'", name)))
+    }
+
+    /// A string parameter, failing with [`INVALID_PARAMS`] if it is missing
+    pub fn str_param(&self, name: &str) -> Result<&str, RpcError> {
+        self.param(name)?.as_str().ok_or_else(|| {
+            RpcError::invalid_params(format!("parameter '{}' must be a string", name))
+        })
+    }
+
+    /// A string parameter, if present
+    pub fn optional_str(&self, name: &str) -> Option<&str> {
+        self.params.get(name).and_then(Value::as_str)
+    }
+}
+
+/// An error response
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(INVALID_PARAMS, message)
+    }
+
+    pub fn debugger(message: impl Into<String>) -> Self {
+        Self::new(DEBUGGER_ERROR, message)
+    }
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::debugger(format!("{:#}", e))
+    }
+}
+
+/// Both directions of a JSON-RPC connection
+pub struct Connection {
+    reader: Box<dyn BufRead>,
+    writer: Box<dyn Write>,
+}
+
+impl Connection {
+    pub fn new(reader: impl BufRead + 'static, writer: impl Write + 'static) -> Self {
+        Self {
+            reader: Box::new(reader),
+            writer: Box::new(writer),
+        }
+    }
+
+    /// Read the next request, or `None` once the client has closed the
+    /// connection. Malformed lines are answered with an error and skipped.
+    pub fn read_request(&mut self) -> crate::Result<Option<Request>> {
+        loop {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            match parse_request(&line) {
+                Ok(request) => return Ok(Some(request)),
+                // Answered even without an id, as JSON-RPC asks
+                Err((id, error)) => self.send(&error_response(&id, error))?,
+            }
+        }
+    }
+
+    /// Answer a request, unless it was a notification
+    pub fn respond(&mut self, id: &Value, result: Result<Value, RpcError>) -> crate::Result<()> {
+        if id.is_null() {
+            return Ok(());
+        }
+        let message = match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => error_response(id, error),
+        };
+        self.send(&message)
+    }
+
+    /// Send a notification
+    pub fn notify(&mut self, method: &str, params: Value) -> crate::Result<()> {
+        self.send(&json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+    }
+
+    fn send(&mut self, message: &Value) -> crate::Result<()> {
+        writeln!(self.writer, "{}", serde_json::to_string(message)?)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+fn error_response(id: &Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+/// Parse one line into a request, or the id to report an error against
+fn parse_request(line: &str) -> Result<Request, (Value, RpcError)> {
+    let message: Value = serde_json::from_str(line).map_err(|e| {
+        (
+            Value::Null,
+            RpcError::new(PARSE_ERROR, format!("not JSON: {}", e)),
+        )
+    })?;
+    let id = message.get("id").cloned().unwrap_or(Value::Null);
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return Err((
+            id,
+            RpcError::new(INVALID_REQUEST, "request without a method"),
+        ));
+    };
+    let method = method.to_string();
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    Ok(Request { id, method, params })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::io::Cursor;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct Sent(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Sent {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn sent_lines(sent: &Sent) -> Vec<Value> {
+        String::from_utf8(sent.0.borrow().clone())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_reads_requests_and_skips_blank_lines() {
+        let input = "\n{\"jsonrpc\":\"2.0\",\"id\":7,\"method\":\"getBudget\"}\n";
+        let mut connection = Connection::new(Cursor::new(input), Vec::new());
+        let request = connection.read_request().unwrap().unwrap();
+        assert_eq!(request.id, json!(7));
+        assert_eq!(request.method, "getBudget");
+        assert!(request.params.is_null());
+        assert!(connection.read_request().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_answers_malformed_lines() {
+        let input = "not json\n{\"id\":1}\n{\"id\":2,\"method\":\"call\"}\n";
+        let sent = Sent::default();
+        let mut connection = Connection::new(Cursor::new(input), sent.clone());
+        let request = connection.read_request().unwrap().unwrap();
+        assert_eq!(request.id, json!(2));
+
+        let errors = sent_lines(&sent);
+        assert_eq!(errors[0]["id"], Value::Null);
+        assert_eq!(errors[0]["error"]["code"], PARSE_ERROR);
+        assert_eq!(errors[1]["id"], 1);
+        assert_eq!(errors[1]["error"]["code"], INVALID_REQUEST);
+    }
+
+    #[test]
+    fn test_notifications_get_no_response() {
+        let sent = Sent::default();
+        let mut connection = Connection::new(Cursor::new(""), sent.clone());
+        connection.respond(&Value::Null, Ok(json!(1))).unwrap();
+        connection.respond(&json!("a"), Ok(json!(1))).unwrap();
+        connection
+            .respond(&json!(3), Err(RpcError::debugger("failed")))
+            .unwrap();
+        connection
+            .notify("paused", json!({"reason": "step"}))
+            .unwrap();
+
+        let lines = sent_lines(&sent);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], json!({"jsonrpc": "2.0", "id": "a", "result": 1}));
+        assert_eq!(lines[1]["error"]["message"], "failed");
+        assert_eq!(lines[2]["method"], "paused");
+    }
+}
@@ -0,0 +1,390 @@
+//! Methods of the JSON-RPC interface
+//!
+//! [`Server`] answers requests between calls. A `call` made on the
+//! interpreter backend can pause at breakpoints; the server then sends a
+//! `paused` notification and [`RpcPause`] answers requests until `step`,
+//! `continue` or `abort` resumes the call. The `call` response follows once
+//! the call returns.
+
+use crate::debugger::{DebuggerEngine, ExecutionBackend};
+use crate::inspector::events::EventInspector;
+use crate::inspector::{BudgetInspector, StorageInspector};
+use crate::rpc::protocol::{Connection, Request, RpcError, METHOD_NOT_FOUND};
+use crate::runtime::executor::{ContractExecutor, StorageSnapshot};
+use crate::runtime::interpreter::{PauseContext, PauseHandler, PauseReason, ResumeAction};
+use crate::runtime::manifest::ContractManifest;
+use crate::simulator::SnapshotLoader;
+use crate::ui::pause_prompt::{describe_access, describe_trap, frame_name};
+use crate::utils::wasm;
+use anyhow::Context;
+use serde_json::{json, Value};
+use soroban_env_host::Host;
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::rc::Rc;
+
+type RpcResult = std::result::Result<Value, RpcError>;
+
+/// State the server shares with the pause handler of its calls
+struct Shared {
+    connection: RefCell<Connection>,
+    /// Set once the client has gone, possibly while a call is paused
+    closed: Cell<bool>,
+    /// Strkeys of the loaded contract and those registered next to it
+    workspace: RefCell<Vec<String>>,
+}
+
+/// Serves one client until it closes the connection
+pub struct Server {
+    shared: Rc<Shared>,
+    engine: Option<DebuggerEngine>,
+    /// Checkpoints taken with `snapshot`, by id - 1
+    snapshots: Vec<StorageSnapshot>,
+}
+
+impl Server {
+    pub fn new(connection: Connection) -> Self {
+        Self {
+            shared: Rc::new(Shared {
+                connection: RefCell::new(connection),
+                closed: Cell::new(false),
+                workspace: RefCell::new(Vec::new()),
+            }),
+            engine: None,
+            snapshots: Vec::new(),
+        }
+    }
+
+    /// Answer requests until the client closes the connection
+    pub fn run(mut self) -> crate::Result<()> {
+        loop {
+            let request = self.shared.connection.borrow_mut().read_request()?;
+            let Some(request) = request else {
+                return Ok(());
+            };
+            let result = self.handle(&request);
+            if self.shared.closed.get() {
+                return Ok(());
+            }
+            self.shared
+                .connection
+                .borrow_mut()
+                .respond(&request.id, result)?;
+        }
+    }
+
+    fn handle(&mut self, request: &Request) -> RpcResult {
+        match request.method.as_str() {
+            "load" => self.load(request),
+            "call" => self.call(request),
+            "getStorage" => {
+                let engine = self.engine()?;
+                storage(&self.shared, engine.executor().host(), request)
+            }
+            "setStorage" => {
+                let storage = match request.param("storage")? {
+                    Value::String(json) => json.clone(),
+                    storage => storage.to_string(),
+                };
+                self.engine_mut()?
+                    .executor_mut()
+                    .set_initial_storage(storage)?;
+                Ok(Value::Null)
+            }
+            "getEvents" => events(self.engine()?.executor().host()),
+            "getBudget" => budget(self.engine()?.executor().host()),
+            "setBreakpoint" => {
+                let spec = request.str_param("spec")?;
+                let breakpoints = self.engine_mut()?.breakpoints_mut();
+                breakpoints.add_spec(spec)?;
+                Ok(json!({ "breakpoints": breakpoints.list() }))
+            }
+            "removeBreakpoint" => {
+                let location = request.str_param("location")?;
+                let breakpoints = self.engine_mut()?.breakpoints_mut();
+                let removed = breakpoints.remove(location);
+                Ok(json!({ "removed": removed, "breakpoints": breakpoints.list() }))
+            }
+            "clearBreakpoints" => {
+                self.engine_mut()?.breakpoints_mut().clear();
+                Ok(json!({ "breakpoints": [] }))
+            }
+            "snapshot" => {
+                let snapshot = self.engine()?.executor().snapshot_storage()?;
+                let ledger_entries = snapshot.ledger_entry_count();
+                let budget =
+                    serde_json::to_value(snapshot.budget()).map_err(anyhow::Error::from)?;
+                self.snapshots.push(snapshot);
+                Ok(json!({
+                    "snapshot": self.snapshots.len(),
+                    "ledger_entries": ledger_entries,
+                    "budget": budget,
+                }))
+            }
+            "restore" => {
+                let id = request.param("snapshot")?.as_u64().unwrap_or(0);
+                let snapshot = usize::try_from(id)
+                    .ok()
+                    .and_then(|id| id.checked_sub(1))
+                    .and_then(|index| self.snapshots.get(index))
+                    .cloned()
+                    .ok_or_else(|| RpcError::invalid_params(format!("no snapshot {}", id)))?;
+                self.engine_mut()?
+                    .executor_mut()
+                    .restore_storage(&snapshot)?;
+                Ok(Value::Null)
+            }
+            "step" | "continue" | "abort" | "getStack" => {
+                Err(RpcError::debugger("No call is paused"))
+            }
+            other => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method '{}'", other),
+            )),
+        }
+    }
+
+    /// Load a contract, replacing any loaded before
+    fn load(&mut self, request: &Request) -> RpcResult {
+        let contract = request.str_param("contract")?;
+        let backend_name = request.optional_str("backend").unwrap_or("interpreter");
+        let backend: ExecutionBackend = backend_name
+            .parse()
+            .map_err(|e: crate::DebuggerError| RpcError::invalid_params(e.to_string()))?;
+
+        let wasm_bytes = fs::read(contract)
+            .with_context(|| format!("Failed to read WASM file: {:?}", contract))?;
+        let mut executor = match request.optional_str("network_snapshot") {
+            Some(path) => {
+                let loader = SnapshotLoader::from_file(path)?;
+                ContractExecutor::with_network_snapshot(wasm_bytes.clone(), &loader)?.0
+            }
+            None => ContractExecutor::new(wasm_bytes.clone())?,
+        };
+        if let Some(path) = request.optional_str("manifest") {
+            executor.register_manifest(&ContractManifest::from_file(path)?)?;
+        }
+        let contract_id = executor.contract_id();
+        *self.shared.workspace.borrow_mut() = std::iter::once(contract_id.clone())
+            .chain(executor.contracts().iter().map(|c| c.contract_id.clone()))
+            .collect();
+
+        let mut engine = DebuggerEngine::new(executor, vec![]);
+        engine.set_quiet(true);
+        if backend == ExecutionBackend::Interpreter {
+            engine.enable_interpreter(&wasm_bytes)?;
+        }
+        engine.set_pause_handler(RpcPause {
+            shared: Rc::clone(&self.shared),
+        });
+        self.engine = Some(engine);
+        self.snapshots.clear();
+
+        Ok(json!({
+            "contract_id": contract_id,
+            "backend": backend_name.to_lowercase(),
+            "functions": wasm::parse_functions(&wasm_bytes)?,
+        }))
+    }
+
+    /// Call a function of the loaded contract, reporting its result, events,
+    /// storage changes and budget
+    fn call(&mut self, request: &Request) -> RpcResult {
+        let function = request.str_param("function")?.to_string();
+        let args = match request.params.get("args") {
+            None | Some(Value::Null) => None,
+            Some(Value::String(json)) => {
+                serde_json::from_str::<Value>(json)
+                    .map_err(|e| RpcError::invalid_params(format!("args are not JSON: {}", e)))?;
+                Some(json.clone())
+            }
+            Some(args) => Some(args.to_string()),
+        };
+        let step_on_entry = request
+            .params
+            .get("step_on_entry")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+
+        let engine = self.engine_mut()?;
+        match engine.interpreter_mut() {
+            Some(interpreter) => interpreter.set_step_on_entry(step_on_entry),
+            None if step_on_entry => {
+                return Err(RpcError::invalid_params(
+                    "step_on_entry needs the interpreter backend",
+                ))
+            }
+            None => {}
+        }
+        let report = engine.execute_with_report(&function, args.as_deref())?;
+        Ok(serde_json::to_value(report).map_err(anyhow::Error::from)?)
+    }
+
+    fn engine(&self) -> std::result::Result<&DebuggerEngine, RpcError> {
+        self.engine.as_ref().ok_or_else(not_loaded)
+    }
+
+    fn engine_mut(&mut self) -> std::result::Result<&mut DebuggerEngine, RpcError> {
+        self.engine.as_mut().ok_or_else(not_loaded)
+    }
+}
+
+fn not_loaded() -> RpcError {
+    RpcError::debugger("No contract is loaded; call 'load' first")
+}
+
+/// Answers requests while a call is paused
+struct RpcPause {
+    shared: Rc<Shared>,
+}
+
+impl RpcPause {
+    fn serve(&self, context: &PauseContext, request: &Request) -> Option<ResumeAction> {
+        let (result, action) = match request.method.as_str() {
+            "step" => match request.optional_str("mode").unwrap_or("into") {
+                "into" => (Ok(Value::Null), Some(ResumeAction::StepInto)),
+                "over" => (Ok(Value::Null), Some(ResumeAction::StepOver)),
+                "out" => (Ok(Value::Null), Some(ResumeAction::StepOut)),
+                "line" => (Ok(Value::Null), Some(ResumeAction::StepOverLine)),
+                "line-into" => (Ok(Value::Null), Some(ResumeAction::StepIntoLine)),
+                other => (
+                    Err(RpcError::invalid_params(format!(
+                        "unknown step mode '{}'; use into, over, out, line or line-into",
+                        other
+                    ))),
+                    None,
+                ),
+            },
+            "continue" => (Ok(Value::Null), Some(ResumeAction::Continue)),
+            "abort" => (Ok(Value::Null), Some(ResumeAction::Abort)),
+            "getStack" => (Ok(stack(context)), None),
+            "getStorage" => (storage(&self.shared, context.host(), request), None),
+            "getEvents" => (events(context.host()), None),
+            "getBudget" => (budget(context.host()), None),
+            "load" | "call" | "setStorage" | "setBreakpoint" | "removeBreakpoint"
+            | "clearBreakpoints" | "snapshot" | "restore" => (
+                Err(RpcError::debugger(
+                    "A call is paused; step, continue or abort it first",
+                )),
+                None,
+            ),
+            other => (
+                Err(RpcError::new(
+                    METHOD_NOT_FOUND,
+                    format!("unknown method '{}'", other),
+                )),
+                None,
+            ),
+        };
+        let sent = self
+            .shared
+            .connection
+            .borrow_mut()
+            .respond(&request.id, result);
+        match sent {
+            Ok(()) => action,
+            Err(e) => {
+                tracing::warn!("Lost the JSON-RPC client: {}", e);
+                self.shared.closed.set(true);
+                Some(ResumeAction::Abort)
+            }
+        }
+    }
+}
+
+impl PauseHandler for RpcPause {
+    fn on_pause(&mut self, context: &PauseContext) -> ResumeAction {
+        let mut paused = stack(context);
+        paused["reason"] = json!(match context.reason() {
+            PauseReason::Breakpoint | PauseReason::LineBreakpoint => "breakpoint",
+            PauseReason::Step => "step",
+            PauseReason::Watchpoint => "watchpoint",
+            PauseReason::HostCall => "host_call",
+            PauseReason::Error => "error",
+        });
+        if let Some(access) = context.storage_access() {
+            paused["detail"] = json!(describe_access(access));
+        } else if let Some(call) = context.host_call() {
+            paused["detail"] = json!(format!("Calling {}", call));
+        } else if let Some(trap) = context.trap() {
+            paused["detail"] = json!(describe_trap(context, trap));
+        }
+        let notified = self.shared.connection.borrow_mut().notify("paused", paused);
+
+        if notified.is_ok() {
+            loop {
+                let request = self.shared.connection.borrow_mut().read_request();
+                match request {
+                    Ok(Some(request)) => {
+                        if let Some(action) = self.serve(context, &request) {
+                            return action;
+                        }
+                    }
+                    // Nobody is left to resume the call
+                    Ok(None) | Err(_) => break,
+                }
+            }
+        }
+        self.shared.closed.set(true);
+        ResumeAction::Abort
+    }
+
+    fn on_output(&mut self, message: &str) {
+        let _ = self
+            .shared
+            .connection
+            .borrow_mut()
+            .notify("output", json!({ "message": message }));
+    }
+}
+
+/// Interpreted frames of the paused call, innermost first, with the paused
+/// frame's locals and operand stack
+fn stack(context: &PauseContext) -> Value {
+    let frames: Vec<Value> = context
+        .frames()
+        .iter()
+        .map(|frame| {
+            let mut entry = json!({
+                "function": frame_name(frame),
+                "contract_id": frame.contract_id,
+                "offset": frame.offset,
+            });
+            if let Some(location) = &frame.location {
+                entry["location"] = json!(location);
+            }
+            entry
+        })
+        .collect();
+    let values = |values: &[_]| -> Vec<String> { values.iter().map(ToString::to_string).collect() };
+    json!({
+        "frames": frames,
+        "locals": values(context.locals()),
+        "stack": values(context.stack()),
+    })
+}
+
+/// Storage entries of one contract, or of the loaded contract and those
+/// registered next to it
+fn storage(shared: &Shared, host: &Host, request: &Request) -> RpcResult {
+    let workspace = shared.workspace.borrow();
+    let entries: Vec<_> = StorageInspector::capture_snapshot(host)?
+        .into_iter()
+        .filter(|entry| match request.optional_str("contract") {
+            Some(contract) => entry.contract == contract,
+            None => workspace.contains(&entry.contract),
+        })
+        .collect();
+    Ok(serde_json::to_value(entries).map_err(anyhow::Error::from)?)
+}
+
+/// Contract events emitted so far in the session, in order
+fn events(host: &Host) -> RpcResult {
+    let events = EventInspector::get_events(host)?;
+    Ok(serde_json::to_value(events).map_err(anyhow::Error::from)?)
+}
+
+fn budget(host: &Host) -> RpcResult {
+    let budget = BudgetInspector::get_cpu_usage(host);
+    Ok(serde_json::to_value(budget).map_err(anyhow::Error::from)?)
+}
@@ -1,53 +1,231 @@
-use crate::utils::ArgumentParser;
+use crate::inspector::budget::{BudgetInfo, BudgetInspector};
+use crate::inspector::storage::{StorageDurability, StorageEntry, StorageInspector};
+use crate::runtime::instrumentation::{Probe, MAX_PROBES};
+use crate::runtime::interpreter::{Interpreter, PauseHandler, Trap};
+use crate::runtime::manifest::ContractManifest;
+use crate::simulator::loader::parse_contract_address;
+use crate::simulator::{LoadedSnapshot, SnapshotLoader};
+use crate::utils::scval::scval_to_json;
+use crate::utils::{ArgumentParser, ContractErrorInfo, ContractSpec};
 use crate::{DebuggerError, Result};
 
-use soroban_env_host::{DiagnosticLevel, Host};
-use soroban_sdk::{Address, Env, InvokeError, Symbol, Val, Vec as SorobanVec};
+use soroban_env_host::storage::Storage;
+use soroban_env_host::xdr::{ScAddress, ScErrorType, ScVal};
+use soroban_env_host::{DiagnosticLevel, Env as _, Host, HostError, LedgerInfo};
+use soroban_sdk::{
+    testutils::EnvTestConfig, Address, Bytes, Env, Error, IntoVal, String as SorobanString, Symbol,
+    TryFromVal, Val, Vec as SorobanVec,
+};
 use std::time::Instant;
 use tracing::{info, warn};
 
+/// CPU instructions and memory bytes a traced call may spend per probe on
+/// top of the contract's own limits. A probe costs about 6,300 instructions
+/// and 450 bytes.
+const PROBE_CPU_ALLOWANCE: u64 = 10_000;
+const PROBE_MEMORY_ALLOWANCE: u64 = 1_000;
+
 /// Result of a contract execution including timing information
 #[derive(Debug, serde::Serialize)]
 pub struct ExecutionResult {
+    /// Return value as compact JSON text, or an error description
     pub result: String,
+    /// Return value as JSON, rendered against the function's spec return type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+    /// Contract error raised by the call, named from the spec when possible
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ContractErrorInfo>,
     pub execution_time_ms: f64,
 }
 
+/// A contract registered next to the primary contract
+#[derive(Debug, Clone)]
+pub struct RegisteredContract {
+    /// Name used to attribute activity to the contract
+    pub name: String,
+    /// Contract address as a strkey (`C...`)
+    pub contract_id: String,
+}
+
 /// Executes Soroban contracts in a test environment
 pub struct ContractExecutor {
     env: Env,
     contract_address: Address,
+    contracts: Vec<RegisteredContract>,
+    spec: ContractSpec,
 }
 
 impl ContractExecutor {
     /// Create a new contract executor.
     pub fn new(wasm: Vec<u8>) -> Result<Self> {
-        info!("Initializing contract executor");
+        Self::with_env(Self::debug_env(), wasm)
+    }
+
+    /// Create a contract executor on top of a network snapshot.
+    ///
+    /// The snapshot is applied before the contract is registered, so the
+    /// contract's entries are live at the snapshot's ledger sequence.
+    pub fn with_network_snapshot(
+        wasm: Vec<u8>,
+        snapshot: &SnapshotLoader,
+    ) -> Result<(Self, LoadedSnapshot)> {
+        let env = Self::debug_env();
+        let loaded = snapshot.apply_to_environment(&env)?;
+        Ok((Self::with_env(env, wasm)?, loaded))
+    }
 
-        let env = Env::default();
+    fn debug_env() -> Env {
+        // The SDK writes a test snapshot when an Env is dropped, which fails
+        // once a call has exhausted the budget
+        let env = Env::new_with_config(EnvTestConfig {
+            capture_snapshot_at_drop: false,
+        });
         env.host()
             .set_diagnostic_level(DiagnosticLevel::Debug)
             .expect("Failed to set diagnostic level");
+        env
+    }
 
+    fn with_env(env: Env, wasm: Vec<u8>) -> Result<Self> {
+        info!("Initializing contract executor");
+
+        let spec = load_spec(&wasm);
         let contract_address = env.register(wasm.as_slice(), ());
 
         Ok(Self {
             env,
             contract_address,
+            contracts: Vec::new(),
+            spec,
         })
     }
 
+    /// Register an additional contract in the same environment.
+    ///
+    /// The contract is deployed at `contract_id` (a `C...` strkey or 32-byte
+    /// hex) when given, otherwise at a generated address. `constructor_args`
+    /// is a JSON array in the `ArgumentParser` format, coerced against the
+    /// contract's `__constructor` declaration when it has one. Returns the contract's
+    /// strkey, which the primary contract can then call.
+    pub fn register_contract(
+        &mut self,
+        name: &str,
+        wasm: &[u8],
+        contract_id: Option<&str>,
+        constructor_args: Option<&str>,
+    ) -> Result<String> {
+        let args = match constructor_args {
+            Some(args_json) => {
+                let spec = load_spec(wasm);
+                let args = self.parse_args(&spec, "__constructor", args_json)?;
+                SorobanVec::from_slice(&self.env, &args)
+            }
+            None => SorobanVec::<Val>::new(&self.env),
+        };
+
+        let address = match contract_id {
+            Some(id) => {
+                let address = parse_contract_address(&self.env, id)?;
+                let strkey = ScAddress::from(&address).to_string();
+                if strkey == self.contract_id() || self.contract_label(&strkey).is_some() {
+                    return Err(DebuggerError::ExecutionError(format!(
+                        "Contract {} is already registered",
+                        strkey
+                    ))
+                    .into());
+                }
+                self.env.register_at(&address, wasm, args)
+            }
+            None => self.env.register(wasm, args),
+        };
+        let contract_id = ScAddress::from(&address).to_string();
+
+        info!("Registered contract {} at {}", name, contract_id);
+        self.contracts.push(RegisteredContract {
+            name: name.to_string(),
+            contract_id: contract_id.clone(),
+        });
+        Ok(contract_id)
+    }
+
+    /// Register every contract listed in a manifest.
+    pub fn register_manifest(&mut self, manifest: &ContractManifest) -> Result<()> {
+        for contract in &manifest.contracts {
+            let wasm = std::fs::read(&contract.wasm).map_err(|e| {
+                DebuggerError::WasmLoadError(format!(
+                    "Failed to read WASM {:?} for contract {}: {}",
+                    contract.wasm,
+                    contract.name(),
+                    e
+                ))
+            })?;
+            let constructor_args = contract.constructor_args.as_ref().map(|a| a.to_string());
+            self.register_contract(
+                &contract.name(),
+                &wasm,
+                contract.contract_id.as_deref(),
+                constructor_args.as_deref(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Replace the primary contract's code, keeping its address and storage.
+    ///
+    /// Used to swap in an instrumented build of the same contract.
+    pub fn replace_contract_wasm(&mut self, wasm: &[u8]) -> Result<()> {
+        let hash = self
+            .env
+            .deployer()
+            .upload_contract_wasm(Bytes::from_slice(&self.env, wasm));
+        self.env.as_contract(&self.contract_address, || {
+            self.env.deployer().update_current_contract_wasm(hash)
+        });
+        info!("Replaced code of contract {}", self.contract_id());
+        Ok(())
+    }
+
+    /// Strkey of the primary contract.
+    pub fn contract_id(&self) -> String {
+        ScAddress::from(&self.contract_address).to_string()
+    }
+
+    /// Interface of the primary contract from its `contractspecv0` section.
+    pub fn spec(&self) -> &ContractSpec {
+        &self.spec
+    }
+
+    /// Contracts registered next to the primary contract.
+    pub fn contracts(&self) -> &[RegisteredContract] {
+        &self.contracts
+    }
+
+    /// Name of a contract registered from a manifest, if any.
+    pub fn contract_label(&self, contract_id: &str) -> Option<&str> {
+        self.contracts
+            .iter()
+            .find(|c| c.contract_id == contract_id)
+            .map(|c| c.name.as_str())
+    }
+
+    /// Describe a contract for display, e.g. `echo (CAAA...)` or `primary`.
+    pub fn describe_contract(&self, contract_id: &str) -> String {
+        if contract_id == self.contract_id() {
+            return "primary".to_string();
+        }
+        match self.contract_label(contract_id) {
+            Some(name) => format!("{} ({})", name, contract_id),
+            None => contract_id.to_string(),
+        }
+    }
+
     /// Execute a contract function
     pub fn execute(&self, function: &str, args: Option<&str>) -> Result<ExecutionResult> {
         info!("Executing function: {}", function);
 
         let func_symbol = Symbol::new(&self.env, function);
-
-        let parsed_args = if let Some(args_json) = args {
-            self.parse_args(args_json)?
-        } else {
-            vec![]
-        };
+        let parsed_args = self.call_args(function, args)?;
 
         let args_vec = if parsed_args.is_empty() {
             SorobanVec::<Val>::new(&self.env)
@@ -58,12 +236,13 @@ impl ContractExecutor {
         // Start timing
         let start = Instant::now();
 
-        // Call the contract
-        // try_invoke_contract returns Result<Result<Val, ConversionError>, Result<InvokeError, InvokeError>>
-        let invoke_result = self.env.try_invoke_contract::<Val, InvokeError>(
-            &self.contract_address,
-            &func_symbol,
-            args_vec,
+        // The host is called directly rather than through the SDK's
+        // `try_invoke_contract`, which panics on errors a contract cannot
+        // recover from, such as an exhausted budget
+        let invoke_result = self.env.host().try_call(
+            self.contract_address.to_object(),
+            func_symbol.to_symbol_val(),
+            args_vec.to_object(),
         );
 
         // End timing
@@ -71,44 +250,166 @@ impl ContractExecutor {
         let execution_time_ms = duration.as_secs_f64() * 1000.0;
 
         match invoke_result {
-            Ok(Ok(val)) => {
-                info!("Function executed successfully in {:.2}ms", execution_time_ms);
-                Ok(ExecutionResult {
-                    result: format!("{:?}", val),
+            // Errors the caller can recover from come back as error values
+            Ok(val) => match Error::try_from_val(&self.env, &val) {
+                Ok(error) if error.is_type(ScErrorType::Contract) => {
+                    let error = self.spec.describe_error(Some(function), error.get_code());
+                    let err_msg = error.to_string();
+                    Ok(failed(Some(error), err_msg, execution_time_ms))
+                }
+                Ok(_) => Ok(failed(
+                    None,
+                    "Contract execution aborted".to_string(),
                     execution_time_ms,
-                })
-            }
-            Ok(Err(conv_err)) => {
-                warn!("Return value conversion failed: {:?}", conv_err);
-                Ok(ExecutionResult {
-                    result: format!("Error (Conversion): {:?}", conv_err),
+                )),
+                Err(_) => Ok(self.returned(function, val, execution_time_ms)),
+            },
+            Err(e) => {
+                if e.error.is_type(ScErrorType::Budget) {
+                    // Every host operation fails while the budget is
+                    // exhausted; start it over so the host can be inspected
+                    self.env.host().budget_cloned().reset()?;
+                }
+                Ok(failed(
+                    None,
+                    format!("Contract execution failed: {:?}", e.error),
                     execution_time_ms,
-                })
+                ))
             }
-            Err(Ok(inv_err)) => {
-                let err_msg = match inv_err {
-                    InvokeError::Contract(code) => format!("Contract error code: {}", code),
-                    InvokeError::Abort => "Contract execution aborted".to_string(),
-                };
-                warn!("{}", err_msg);
-                Ok(ExecutionResult {
-                    result: format!("Error: {}", err_msg),
-                    execution_time_ms,
+        }
+    }
+
+    /// Execute a contract function in the built-in interpreter.
+    ///
+    /// The call runs instruction by instruction in a frame of the primary
+    /// contract, handing control to `handler` whenever it pauses. Host imports
+    /// go to this executor's host, so storage, events and budget are shared
+    /// with [`execute`](Self::execute); storage changes are rolled back if the
+    /// call fails or is aborted.
+    pub fn execute_interpreted(
+        &self,
+        interpreter: &Interpreter,
+        function: &str,
+        args: Option<&str>,
+        handler: &mut dyn PauseHandler,
+    ) -> Result<ExecutionResult> {
+        info!("Interpreting function: {}", function);
+
+        let parsed_args = self.call_args(function, args)?;
+        let ScAddress::Contract(contract_hash) = ScAddress::from(&self.contract_address) else {
+            return Err(DebuggerError::ExecutionError(
+                "Primary contract address is not a contract".to_string(),
+            )
+            .into());
+        };
+        let func_symbol = Symbol::new(&self.env, function).to_symbol_val();
+        let host = self.env.host();
+
+        let start = Instant::now();
+        let mut trap = None;
+        let outcome = host.with_test_contract_frame(contract_hash.clone(), func_symbol, || {
+            interpreter
+                .invoke(host, &contract_hash, function, &parsed_args, handler)
+                .map_err(|t| {
+                    let error = t.to_error();
+                    trap = Some(t);
+                    HostError::from(error)
                 })
+        });
+        let execution_time_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        match outcome {
+            Ok(val) => Ok(self.returned(function, val, execution_time_ms)),
+            Err(e) => {
+                // Contract errors, raised or returned, are named from the spec
+                let error = e
+                    .error
+                    .is_type(ScErrorType::Contract)
+                    .then(|| self.spec.describe_error(Some(function), e.error.get_code()));
+                let err_msg = match (&error, trap) {
+                    (Some(error), _) => error.to_string(),
+                    (None, Some(Trap::Aborted)) => "Execution aborted by the debugger".to_string(),
+                    (None, Some(trap)) => format!("Contract execution trapped: {}", trap),
+                    (None, None) => format!("Contract execution failed: {:?}", e.error),
+                };
+                Ok(failed(error, err_msg, execution_time_ms))
             }
-            Err(Err(inv_err)) => {
-                warn!("Invocation error conversion failed: {:?}", inv_err);
-                Ok(ExecutionResult {
-                    result: format!("Error (Invocation Conversion): {:?}", inv_err),
-                    execution_time_ms,
-                })
+        }
+    }
+
+    /// Result of a call that returned `val`
+    fn returned(&self, function: &str, val: Val, execution_time_ms: f64) -> ExecutionResult {
+        info!(
+            "Function executed successfully in {:.2}ms",
+            execution_time_ms
+        );
+        let value = self.render_return_value(function, val);
+        ExecutionResult {
+            result: value.to_string(),
+            value: Some(value),
+            error: None,
+            execution_time_ms,
+        }
+    }
+
+    /// Render a returned value as JSON, using the function's declared return
+    /// type when the spec has one.
+    fn render_return_value(&self, function: &str, val: Val) -> serde_json::Value {
+        match ScVal::try_from_val(&self.env, &val) {
+            Ok(scval) => match self.spec.return_type(function) {
+                Some(ty) => self.spec.value_to_json(&scval, ty),
+                None => scval_to_json(&scval),
+            },
+            Err(e) => {
+                warn!("Failed to convert return value to ScVal: {:?}", e);
+                serde_json::Value::String(format!("{:?}", val))
             }
         }
     }
 
     /// Set initial storage state.
-    pub fn set_initial_storage(&mut self, _storage_json: String) -> Result<()> {
-        info!("Setting initial storage (not yet implemented)");
+    ///
+    /// The JSON is either a flat object of `key -> value` pairs, written to
+    /// persistent storage, or an object whose top-level keys are `persistent`,
+    /// `temporary` and/or `instance`, each holding such an object. Keys and
+    /// values use the `ArgumentParser` format; a string that itself holds JSON
+    /// text (e.g. `"{\"type\":\"u32\",\"value\":1}"`) is decoded first, so
+    /// object keys can carry typed values too.
+    pub fn set_initial_storage(&mut self, storage_json: String) -> Result<()> {
+        let value: serde_json::Value = serde_json::from_str(&storage_json)
+            .map_err(|e| DebuggerError::StorageError(format!("Invalid storage JSON: {}", e)))?;
+        let obj = value.as_object().ok_or_else(|| {
+            DebuggerError::StorageError("Storage must be a JSON object".to_string())
+        })?;
+
+        let sectioned = !obj.is_empty()
+            && obj
+                .iter()
+                .all(|(k, v)| StorageDurability::parse(k).is_some() && v.is_object());
+
+        let sections: Vec<_> = if sectioned {
+            obj.iter()
+                .filter_map(|(name, entries)| {
+                    Some((StorageDurability::parse(name)?, entries.as_object()?))
+                })
+                .collect()
+        } else {
+            vec![(StorageDurability::Persistent, obj)]
+        };
+
+        let mut count = 0;
+        for (durability, entries) in sections {
+            for (raw_key, raw_value) in entries {
+                let key =
+                    decode_storage_json(&self.env, &serde_json::Value::String(raw_key.clone()))?;
+                let val = decode_storage_json(&self.env, raw_value)?;
+                put_storage_entry(&self.env, &self.contract_address, durability, key, val);
+                info!("Initial {} storage entry set: {}", durability, raw_key);
+                count += 1;
+            }
+        }
+
+        info!("Initial storage set ({} entries)", count);
         Ok(())
     }
 
@@ -128,23 +429,72 @@ impl ContractExecutor {
     }
 
     /// Capture a snapshot of current contract storage.
-    pub fn get_storage_snapshot(&self) -> Result<HashMap<String, String>> {
-        Ok(HashMap::new())
+    pub fn get_storage_snapshot(&self) -> Result<Vec<StorageEntry>> {
+        let contract = self.contract_id();
+        Ok(StorageInspector::capture_snapshot(self.env.host())?
+            .into_iter()
+            .filter(|entry| entry.contract == contract)
+            .collect())
+    }
+
+    /// Capture storage of the primary contract and every registered contract.
+    pub fn get_workspace_storage_snapshot(&self) -> Result<Vec<StorageEntry>> {
+        let primary = self.contract_id();
+        Ok(StorageInspector::capture_snapshot(self.env.host())?
+            .into_iter()
+            .filter(|entry| {
+                entry.contract == primary || self.contract_label(&entry.contract).is_some()
+            })
+            .collect())
     }
 
-    /// Snapshot current storage state for dry-run rollback.
+    /// Checkpoint the host's ledger storage, ledger info and budget state.
+    ///
+    /// Used for dry-run rollback; any number of checkpoints can be taken and
+    /// restored with [`restore_storage`](Self::restore_storage).
     pub fn snapshot_storage(&self) -> Result<StorageSnapshot> {
+        let host = self.env.host();
+        let storage = host.with_mut_storage(|storage| Ok(storage.clone()))?;
+        let ledger_info = host.with_ledger_info(|info| Ok(info.clone()))?;
+        let budget = BudgetInspector::get_cpu_usage(host);
+
+        info!(
+            "Storage checkpoint taken ({} ledger entries)",
+            storage.map.len()
+        );
         Ok(StorageSnapshot {
-            _contract_address: self.contract_address.clone(),
+            storage,
+            ledger_info,
+            budget,
         })
     }
 
-    /// Restore storage state from snapshot (dry-run rollback).
-    pub fn restore_storage(&mut self, _snapshot: &StorageSnapshot) -> Result<()> {
+    /// Restore storage, ledger info and budget from a checkpoint (dry-run rollback).
+    ///
+    /// The budget gets the checkpoint's limits back. soroban-env-host has no
+    /// way to write the consumed counters, so they start over from zero, as
+    /// they do before every call; [`StorageSnapshot::budget`] keeps what had
+    /// been consumed when the checkpoint was taken.
+    pub fn restore_storage(&mut self, snapshot: &StorageSnapshot) -> Result<()> {
+        let host = self.env.host();
+        host.with_mut_storage(|storage| {
+            *storage = snapshot.storage.clone();
+            Ok(())
+        })?;
+        host.set_ledger_info(snapshot.ledger_info.clone())?;
+        host.budget_cloned()
+            .reset_limits(snapshot.budget.cpu_limit, snapshot.budget.memory_limit)?;
+
         info!("Storage state restored (dry-run rollback)");
         Ok(())
     }
 
+    /// Start the budget's counters over for a new call, keeping its limits.
+    pub fn reset_budget(&self) -> Result<()> {
+        self.env.host().budget_cloned().reset()?;
+        Ok(())
+    }
+
     /// Get diagnostic events from the host.
     pub fn get_diagnostic_events(&self) -> Result<Vec<soroban_env_host::xdr::ContractEvent>> {
         Ok(self
@@ -154,14 +504,187 @@ impl ContractExecutor {
             .0
             .into_iter()
             .map(|he| he.event)
+            .filter(|event| !Probe::is_probe_event(event))
+            .collect())
+    }
+
+    /// Run `function` on an instrumented build of the primary contract and
+    /// return the probes it fired.
+    ///
+    /// The run happens in a throwaway copy of this host's ledger, so probes
+    /// are never billed to the contract and nothing the run does (storage
+    /// writes, events, budget) shows here. Its budget is this host's limits
+    /// plus an allowance for [`MAX_PROBES`] probes.
+    pub fn trace_instrumented(
+        &self,
+        instrumented: &[u8],
+        function: &str,
+        args: Option<&str>,
+    ) -> Result<Vec<Probe>> {
+        let host = self.env.host();
+        let storage = host.with_mut_storage(|storage| Ok(storage.clone()))?;
+        let ledger_info = host.with_ledger_info(|info| Ok(info.clone()))?;
+
+        let env = Self::debug_env();
+        env.host().set_ledger_info(ledger_info)?;
+        env.host().with_mut_storage(|copy| {
+            *copy = storage;
+            Ok(())
+        })?;
+        let mut fork = Self {
+            contract_address: parse_contract_address(&env, &self.contract_id())?,
+            env,
+            contracts: self.contracts.clone(),
+            spec: self.spec.clone(),
+        };
+        fork.replace_contract_wasm(instrumented)?;
+        let budget = BudgetInspector::get_cpu_usage(host);
+        let probes = MAX_PROBES as u64;
+        fork.env.host().budget_cloned().reset_limits(
+            budget
+                .cpu_limit
+                .saturating_add(probes * PROBE_CPU_ALLOWANCE),
+            budget
+                .memory_limit
+                .saturating_add(probes * PROBE_MEMORY_ALLOWANCE),
+        )?;
+
+        fork.execute(function, args)?;
+        fork.get_probes()
+    }
+
+    /// Probes fired by an instrumented build of the contract, in execution
+    /// order. Probes from calls that failed are included.
+    pub fn get_probes(&self) -> Result<Vec<Probe>> {
+        Ok(self
+            .env
+            .host()
+            .get_events()?
+            .0
+            .iter()
+            .filter_map(|he| Probe::from_event(&he.event))
             .collect())
     }
 
-    fn parse_args(&self, args_json: &str) -> Result<Vec<Val>> {
+    /// Arguments for a call of `function` on the primary contract, by
+    /// parameter name and rendered as bare JSON
+    pub fn named_args(
+        &self,
+        function: &str,
+        args: Option<&str>,
+    ) -> Result<Vec<(String, serde_json::Value)>> {
+        let host = self.env.host();
+        let args = self
+            .call_args(function, args)?
+            .iter()
+            .map(|val| ScVal::try_from_val(host, val))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| DebuggerError::InvalidArguments(format!("{:?}", e)))?;
+        Ok(self.spec.named_args(function, &args))
+    }
+
+    /// Arguments for a call of `function` on the primary contract
+    fn call_args(&self, function: &str, args: Option<&str>) -> Result<Vec<Val>> {
+        match args {
+            Some(args_json) => self.parse_args(&self.spec, function, args_json),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Parse arguments for `function`, coercing them against its spec
+    /// declaration when the contract has one.
+    fn parse_args(&self, spec: &ContractSpec, function: &str, args_json: &str) -> Result<Vec<Val>> {
         let parser = ArgumentParser::new(self.env.clone());
-        parser.parse_args_string(args_json).map_err(|e| {
+        let parsed = match spec.function(function) {
+            Some(func) => spec.parse_args(&parser, func, args_json),
+            None => parser.parse_args_string(args_json),
+        };
+        parsed.map_err(|e| {
             warn!("Failed to parse arguments: {}", e);
             DebuggerError::InvalidArguments(e.to_string()).into()
         })
     }
 }
+
+/// Result of a call that failed
+fn failed(
+    error: Option<ContractErrorInfo>,
+    err_msg: String,
+    execution_time_ms: f64,
+) -> ExecutionResult {
+    warn!("{}", err_msg);
+    ExecutionResult {
+        result: format!("Error: {}", err_msg),
+        value: None,
+        error,
+        execution_time_ms,
+    }
+}
+
+/// Read a contract's spec, treating an unreadable spec as empty.
+fn load_spec(wasm: &[u8]) -> ContractSpec {
+    ContractSpec::from_wasm(wasm).unwrap_or_else(|e| {
+        warn!("Failed to read contract spec: {}", e);
+        ContractSpec::default()
+    })
+}
+
+/// Decode one storage key or value from its JSON representation.
+pub(crate) fn decode_storage_json(env: &Env, value: &serde_json::Value) -> Result<Val> {
+    if let serde_json::Value::String(text) = value {
+        if let Ok(inner) = serde_json::from_str::<serde_json::Value>(text) {
+            if !inner.is_string() {
+                return decode_storage_json(env, &inner);
+            }
+        }
+        // Bare strings become Symbols when they are valid symbols, and
+        // Strings otherwise (e.g. `balance:alice`).
+        return Ok(match Symbol::try_from_val(env, &text.as_str()) {
+            Ok(symbol) => symbol.into_val(env),
+            Err(_) => SorobanString::from_str(env, text).into_val(env),
+        });
+    }
+
+    let parser = ArgumentParser::new(env.clone());
+    parser.parse_single_value(value).map_err(|e| {
+        DebuggerError::StorageError(format!("Invalid storage entry {}: {}", value, e)).into()
+    })
+}
+
+/// Write a single entry into a contract's storage.
+pub(crate) fn put_storage_entry(
+    env: &Env,
+    contract: &Address,
+    durability: StorageDurability,
+    key: Val,
+    val: Val,
+) {
+    env.as_contract(contract, || {
+        let storage = env.storage();
+        match durability {
+            StorageDurability::Persistent => storage.persistent().set(&key, &val),
+            StorageDurability::Temporary => storage.temporary().set(&key, &val),
+            StorageDurability::Instance => storage.instance().set(&key, &val),
+        }
+    });
+}
+
+/// Checkpoint of the host's ledger and budget state taken by [`ContractExecutor::snapshot_storage`]
+#[derive(Clone)]
+pub struct StorageSnapshot {
+    storage: Storage,
+    ledger_info: LedgerInfo,
+    budget: BudgetInfo,
+}
+
+impl StorageSnapshot {
+    /// Number of ledger entries (including removed entries) in the checkpoint
+    pub fn ledger_entry_count(&self) -> usize {
+        self.storage.map.len()
+    }
+
+    /// Budget limits and consumption at the time of the checkpoint
+    pub fn budget(&self) -> &BudgetInfo {
+        &self.budget
+    }
+}
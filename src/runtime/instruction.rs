@@ -217,18 +217,31 @@ impl InstructionParser {
 
     /// Parse WASM bytecode and extract all instructions
     pub fn parse(&mut self, wasm_bytes: &[u8]) -> Result<&[Instruction], String> {
-        use wasmparser::{Parser, Payload};
+        use wasmparser::{Parser, Payload, TypeRef};
 
         self.instructions.clear();
 
         let parser = Parser::new(0);
+        // Imported functions come first in the function index space
+        let mut function_index = 0;
 
         for payload in parser.parse_all(wasm_bytes) {
             let payload = payload.map_err(|e| format!("WASM parsing error: {}", e))?;
 
-            if let Payload::CodeSectionEntry(body) = payload {
-                let function_index = self.instructions.len() as u32; // Simplified function indexing
-                self.parse_function_body(body, function_index)?;
+            match payload {
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        let import = import.map_err(|e| format!("WASM parsing error: {}", e))?;
+                        if matches!(import.ty, TypeRef::Func(_)) {
+                            function_index += 1;
+                        }
+                    }
+                }
+                Payload::CodeSectionEntry(body) => {
+                    self.parse_function_body(body, function_index)?;
+                    function_index += 1;
+                }
+                _ => {}
             }
         }
 
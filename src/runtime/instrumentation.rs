@@ -1,10 +1,206 @@
 use crate::runtime::instruction::{Instruction, InstructionParser};
+use soroban_env_common::{SymbolSmall, U64Small};
+use soroban_env_host::xdr::{ContractEvent, ContractEventBody, ContractEventType, ScVal};
 use std::sync::Arc;
-use walrus::{FunctionId, Module, ModuleConfig};
+use walrus::ir::{
+    BinaryOp, Block, Br, BrIf, BrTable, Call, Const, IfElse, Instr, InstrLocId, InstrSeqId,
+    InstrSeqType, Loop, UnaryOp, Value,
+};
+use walrus::{FunctionBuilder, FunctionId, FunctionKind, InitExpr, LocalFunction, Module, ValType};
 
 /// Callback function type for instruction hooks
 pub type InstructionHook = Arc<dyn Fn(usize, &Instruction) -> bool + Send + Sync>;
 
+/// Topic of the contract events published by instrumentation probes
+pub const PROBE_TOPIC: &str = "dbg_probe";
+
+/// Probes an instrumented call fires before it traps, which bounds the budget
+/// a traced call needs on top of the contract's own
+pub const MAX_PROBES: usize = 500_000;
+
+/// Largest function index a probe can carry
+const MAX_PROBE_FUNCTION: u32 = (1 << 22) - 1;
+
+/// Where probes are inserted by [`Instrumenter::instrument`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProbeMode {
+    /// Before every instruction
+    #[default]
+    Instruction,
+    /// At the start of every basic block
+    Block,
+}
+
+/// What a probe marks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeKind {
+    /// An instruction is about to execute
+    Instruction,
+    /// A basic block is about to execute
+    Block,
+    /// A function was entered
+    Enter,
+    /// A function is returning
+    Exit,
+}
+
+/// A probe fired during an instrumented run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Probe {
+    pub kind: ProbeKind,
+    /// Index of the function in the original module, imports included
+    pub function_index: u32,
+    /// Byte offset of the instruction in the original module
+    pub offset: usize,
+}
+
+impl Probe {
+    /// Pack the probe into the 56 bits of a small `u64` value.
+    fn encode(&self) -> u64 {
+        let kind = match self.kind {
+            ProbeKind::Instruction => 0u64,
+            ProbeKind::Block => 1,
+            ProbeKind::Enter => 2,
+            ProbeKind::Exit => 3,
+        };
+        (kind << 54) | ((self.function_index as u64) << 32) | self.offset as u64
+    }
+
+    fn decode(data: u64) -> Self {
+        let kind = match data >> 54 {
+            0 => ProbeKind::Instruction,
+            1 => ProbeKind::Block,
+            2 => ProbeKind::Enter,
+            _ => ProbeKind::Exit,
+        };
+        Self {
+            kind,
+            function_index: ((data >> 32) as u32) & MAX_PROBE_FUNCTION,
+            offset: (data & u32::MAX as u64) as usize,
+        }
+    }
+
+    /// Decode the probe published by an instrumented contract, if `event`
+    /// is one.
+    pub fn from_event(event: &ContractEvent) -> Option<Self> {
+        if event.type_ != ContractEventType::Contract {
+            return None;
+        }
+        let ContractEventBody::V0(body) = &event.body;
+        match (body.topics.as_slice(), &body.data) {
+            ([ScVal::Symbol(topic)], ScVal::U64(data))
+                if topic.0.as_slice() == PROBE_TOPIC.as_bytes() =>
+            {
+                Some(Self::decode(*data))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether `event` was published by an instrumentation probe
+    pub fn is_probe_event(event: &ContractEvent) -> bool {
+        Self::from_event(event).is_some()
+    }
+}
+
+/// Probe call site shared by the blocks of one function
+struct ProbeSite {
+    probe: FunctionId,
+    function_index: u32,
+    /// Offset reported when the function returns
+    exit_offset: u32,
+}
+
+impl ProbeSite {
+    /// Append `i64.const <probe>; call $probe`
+    fn emit(&self, instrs: &mut Vec<(Instr, InstrLocId)>, kind: ProbeKind, offset: u32) {
+        let data = Probe {
+            kind,
+            function_index: self.function_index,
+            offset: offset as usize,
+        }
+        .encode();
+        let payload = U64Small::try_from(data)
+            .expect("probe data fits in a small value")
+            .to_val()
+            .get_payload();
+        instrs.push((
+            Instr::Const(Const {
+                value: Value::I64(payload as i64),
+            }),
+            InstrLocId::default(),
+        ));
+        instrs.push((
+            Instr::Call(Call { func: self.probe }),
+            InstrLocId::default(),
+        ));
+    }
+}
+
+/// Add `$probe(data: i64)`, which publishes `data` as a contract event with
+/// the [`PROBE_TOPIC`] topic through the host's `vec_new`, `vec_push_back`
+/// and `contract_event` imports, and traps once [`MAX_PROBES`] have fired.
+fn add_probe_function(module: &mut Module) -> Result<FunctionId, String> {
+    let vec_new = host_import(module, "v", "_", 0)?;
+    let vec_push_back = host_import(module, "v", "6", 2)?;
+    let contract_event = host_import(module, "x", "1", 2)?;
+    let topic = SymbolSmall::try_from_str(PROBE_TOPIC)
+        .map_err(|e| format!("Invalid probe topic: {:?}", e))?
+        .to_val()
+        .get_payload();
+
+    let remaining = module.globals.add_local(
+        ValType::I64,
+        true,
+        InitExpr::Value(Value::I64(MAX_PROBES as i64)),
+    );
+    let data = module.locals.add(ValType::I64);
+    let mut builder = FunctionBuilder::new(&mut module.types, &[ValType::I64], &[]);
+    builder
+        .name("__debug_probe".to_string())
+        .func_body()
+        .global_get(remaining)
+        .unop(UnaryOp::I64Eqz)
+        .if_else(
+            None,
+            |then| {
+                then.unreachable();
+            },
+            |_| {},
+        )
+        .global_get(remaining)
+        .i64_const(1)
+        .binop(BinaryOp::I64Sub)
+        .global_set(remaining)
+        .call(vec_new)
+        .i64_const(topic as i64)
+        .call(vec_push_back)
+        .local_get(data)
+        .call(contract_event)
+        .drop();
+    Ok(builder.finish(vec![data], &mut module.funcs))
+}
+
+/// Find or add a Soroban host function import taking `params` `i64`s and
+/// returning an `i64`.
+fn host_import(
+    module: &mut Module,
+    module_name: &str,
+    name: &str,
+    params: usize,
+) -> Result<FunctionId, String> {
+    if let Some(import) = module.imports.find(module_name, name) {
+        return match module.imports.get(import).kind {
+            walrus::ImportKind::Function(func) => Ok(func),
+            _ => Err(format!("Import {}.{} is not a function", module_name, name)),
+        };
+    }
+    let ty = module
+        .types
+        .add(&vec![ValType::I64; params], &[ValType::I64]);
+    Ok(module.add_import_func(module_name, name, ty).0)
+}
+
 /// WASM instrumentation for adding debug hooks
 pub struct Instrumenter {
     /// Whether instrumentation is enabled
@@ -13,6 +209,10 @@ pub struct Instrumenter {
     hook: Option<InstructionHook>,
     /// Parsed instructions for reference
     instructions: Vec<Instruction>,
+    /// Probe granularity
+    mode: ProbeMode,
+    /// Probes fired by the last instrumented run
+    trace: Vec<Probe>,
 }
 
 impl Instrumenter {
@@ -22,6 +222,8 @@ impl Instrumenter {
             enabled: false,
             hook: None,
             instructions: Vec::new(),
+            mode: ProbeMode::default(),
+            trace: Vec::new(),
         }
     }
 
@@ -66,29 +268,47 @@ impl Instrumenter {
         &self.instructions
     }
 
+    /// Set whether probes are inserted per instruction or per basic block
+    pub fn set_mode(&mut self, mode: ProbeMode) {
+        self.mode = mode;
+    }
+
+    /// Get the probe granularity
+    pub fn mode(&self) -> ProbeMode {
+        self.mode
+    }
+
     /// Instrument WASM bytecode with debugging hooks
     ///
-    /// This adds calls to a debug callback function before each instruction
-    /// when debug mode is enabled.
+    /// Every instruction (or every basic block, depending on the
+    /// [`ProbeMode`]) is preceded by a call to a probe function, and each
+    /// function reports its entry and exit. The Soroban VM only links its own
+    /// host interface, so the probe is a small local function that publishes
+    /// the function index and offset through the host's `contract_event`
+    /// import; [`record_trace`](Self::record_trace) reads them back.
     pub fn instrument(&self, wasm_bytes: &[u8]) -> Result<Vec<u8>, String> {
-        if !self.enabled || self.hook.is_none() {
-            // If not enabled or no hook, return original WASM
+        if !self.enabled {
+            // If not enabled, return original WASM
             return Ok(wasm_bytes.to_vec());
         }
 
         // Parse the WASM module
-        let _config = ModuleConfig::new();
         let mut module = Module::from_buffer(wasm_bytes)
             .map_err(|e| format!("Failed to parse WASM module: {}", e))?;
 
-        // Add a debug callback import
-        let debug_callback_type = module.types.add(&[], &[]);
-        let (debug_callback, _) = module.add_import_func("debug", "callback", debug_callback_type);
+        // Function indices as they appear in the original binary, before any
+        // imports are added
+        let func_indices: Vec<(FunctionId, u32)> = module
+            .funcs
+            .iter()
+            .enumerate()
+            .map(|(index, func)| (func.id(), index as u32))
+            .collect();
+
+        let probe = add_probe_function(&mut module)?;
 
-        // Instrument each function (simplified for now)
-        let func_ids: Vec<FunctionId> = module.funcs.iter_local().map(|(id, _)| id).collect();
-        for func_id in func_ids {
-            self.instrument_function(&mut module, func_id, debug_callback)?;
+        for (func_id, function_index) in func_indices {
+            self.instrument_function(&mut module, func_id, probe, function_index)?;
         }
 
         // Emit the instrumented WASM
@@ -98,25 +318,161 @@ impl Instrumenter {
     /// Instrument a single function with debug hooks
     fn instrument_function(
         &self,
-        _module: &mut Module,
-        _func_id: FunctionId,
-        _debug_callback: FunctionId,
+        module: &mut Module,
+        func_id: FunctionId,
+        probe: FunctionId,
+        function_index: u32,
     ) -> Result<(), String> {
-        // Simplified implementation for now
-        // Full implementation would require deep integration with walrus IR
+        let results = module
+            .types
+            .get(module.funcs.get(func_id).ty())
+            .results()
+            .to_vec();
+        let body_ty = InstrSeqType::new(&mut module.types, &[], &results);
+        let func = match &mut module.funcs.get_mut(func_id).kind {
+            FunctionKind::Local(func) => func,
+            _ => return Ok(()),
+        };
+
+        // The first operator and the function's closing `end`
+        let (first, last) = match (
+            func.instruction_mapping.first(),
+            func.instruction_mapping.last(),
+        ) {
+            (Some((_, first)), Some((_, last))) => (first.data(), last.data()),
+            _ => return Ok(()),
+        };
+        if function_index > MAX_PROBE_FUNCTION {
+            return Err(format!(
+                "Function index {} is too large to instrument",
+                function_index
+            ));
+        }
+
+        let site = ProbeSite {
+            probe,
+            function_index,
+            exit_offset: last,
+        };
+
+        let entry = func.entry_block();
+        let mut seqs = Vec::new();
+        let mut pending = vec![entry];
+        while let Some(seq) = pending.pop() {
+            for (instr, _) in &func.block(seq).instrs {
+                match instr {
+                    Instr::Block(Block { seq }) | Instr::Loop(Loop { seq }) => pending.push(*seq),
+                    Instr::IfElse(IfElse {
+                        consequent,
+                        alternative,
+                    }) => {
+                        pending.push(*consequent);
+                        pending.push(*alternative);
+                    }
+                    _ => {}
+                }
+            }
+            seqs.push(seq);
+            self.instrument_block(func, seq, &site);
+        }
+
+        // Move the body into a block of its own, so a branch out of the
+        // function body still passes the exit probe
+        let body = func.builder_mut().dangling_instr_seq(body_ty).id();
+        func.block_mut(body).instrs = std::mem::take(&mut func.block_mut(entry).instrs);
+        for seq in seqs
+            .into_iter()
+            .map(|seq| if seq == entry { body } else { seq })
+        {
+            for (instr, _) in func.block_mut(seq).instrs.iter_mut() {
+                match instr {
+                    Instr::Br(Br { block }) | Instr::BrIf(BrIf { block }) if *block == entry => {
+                        *block = body
+                    }
+                    Instr::BrTable(BrTable { blocks, default }) => {
+                        for block in blocks.iter_mut().chain(std::iter::once(default)) {
+                            if *block == entry {
+                                *block = body;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut instrs = Vec::with_capacity(5);
+        site.emit(&mut instrs, ProbeKind::Enter, first);
+        instrs.push((Instr::Block(Block { seq: body }), InstrLocId::default()));
+        site.emit(&mut instrs, ProbeKind::Exit, last);
+        func.block_mut(entry).instrs = instrs;
+
         Ok(())
     }
 
     /// Instrument a basic block with debug hooks
-    #[allow(dead_code)]
-    fn instrument_block(
-        &self,
-        _module: &mut Module,
-        _debug_callback: FunctionId,
-    ) -> Result<(), String> {
-        // Simplified implementation for now
-        // This is complex and requires deep understanding of the walrus IR
-        Ok(())
+    fn instrument_block(&self, func: &mut LocalFunction, seq: InstrSeqId, site: &ProbeSite) {
+        let original = std::mem::take(&mut func.block_mut(seq).instrs);
+        let mut instrs = Vec::with_capacity(original.len() * 3 + 2);
+
+        if self.mode == ProbeMode::Block {
+            if let Some((_, loc)) = original.iter().find(|(_, loc)| !loc.is_default()) {
+                site.emit(&mut instrs, ProbeKind::Block, loc.data());
+            }
+        }
+
+        let mut iter = original.into_iter().peekable();
+        while let Some((instr, loc)) = iter.next() {
+            if self.mode == ProbeMode::Instruction && !loc.is_default() {
+                site.emit(&mut instrs, ProbeKind::Instruction, loc.data());
+            }
+            if matches!(instr, Instr::Return(_)) {
+                site.emit(&mut instrs, ProbeKind::Exit, site.exit_offset);
+            }
+
+            // Execution resumes after a nested block or a conditional branch,
+            // which starts a new basic block
+            let ends_block = matches!(
+                instr,
+                Instr::Block(_) | Instr::Loop(_) | Instr::IfElse(_) | Instr::BrIf(_)
+            );
+            instrs.push((instr, loc));
+
+            if self.mode == ProbeMode::Block && ends_block {
+                if let Some((_, next)) = iter.peek().filter(|(_, next)| !next.is_default()) {
+                    site.emit(&mut instrs, ProbeKind::Block, next.data());
+                }
+            }
+        }
+
+        func.block_mut(seq).instrs = instrs;
+    }
+
+    /// Record the probes fired by an instrumented run
+    ///
+    /// The hook is called for each probe that maps to a parsed instruction,
+    /// in execution order, until it returns `true`. Returns the position in
+    /// the trace where the hook asked to stop, if it did.
+    pub fn record_trace(&mut self, trace: Vec<Probe>) -> Option<usize> {
+        self.trace = trace;
+
+        let hook = self.hook.as_ref()?;
+        self.trace.iter().position(|probe| {
+            self.instruction_index(probe.offset)
+                .is_some_and(|index| hook(index, &self.instructions[index]))
+        })
+    }
+
+    /// Probes fired by the last instrumented run
+    pub fn trace(&self) -> &[Probe] {
+        &self.trace
+    }
+
+    /// Index of the parsed instruction at a byte offset
+    pub fn instruction_index(&self, offset: usize) -> Option<usize> {
+        self.instructions
+            .binary_search_by_key(&offset, |instruction| instruction.offset)
+            .ok()
     }
 
     /// Call the instruction hook if present
@@ -143,3 +499,101 @@ impl Default for Instrumenter {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{ContractEventV0, ExtensionPoint, ScSymbol};
+
+    fn probe_event(data: ScVal) -> ContractEvent {
+        ContractEvent {
+            ext: ExtensionPoint::V0,
+            contract_id: None,
+            type_: ContractEventType::Contract,
+            body: ContractEventBody::V0(ContractEventV0 {
+                topics: vec![ScVal::Symbol(ScSymbol(PROBE_TOPIC.try_into().unwrap()))]
+                    .try_into()
+                    .unwrap(),
+                data,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_probe_encoding_round_trips() {
+        for kind in [
+            ProbeKind::Instruction,
+            ProbeKind::Block,
+            ProbeKind::Enter,
+            ProbeKind::Exit,
+        ] {
+            let probe = Probe {
+                kind,
+                function_index: MAX_PROBE_FUNCTION,
+                offset: u32::MAX as usize,
+            };
+            assert!(U64Small::try_from(probe.encode()).is_ok());
+            assert_eq!(Probe::decode(probe.encode()), probe);
+        }
+    }
+
+    #[test]
+    fn test_probe_from_event() {
+        let probe = Probe {
+            kind: ProbeKind::Instruction,
+            function_index: 7,
+            offset: 0x1234,
+        };
+        let event = probe_event(ScVal::U64(probe.encode()));
+        assert_eq!(Probe::from_event(&event), Some(probe));
+
+        let other = probe_event(ScVal::Void);
+        assert!(!Probe::is_probe_event(&other));
+    }
+
+    #[test]
+    fn test_instrument_disabled_returns_original() {
+        let wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        let instrumenter = Instrumenter::new();
+        assert_eq!(instrumenter.instrument(&wasm).unwrap(), wasm);
+    }
+
+    #[test]
+    fn test_instrument_adds_probes() {
+        let mut module = Module::default();
+        let mut builder = FunctionBuilder::new(&mut module.types, &[], &[ValType::I32]);
+        builder
+            .func_body()
+            .i32_const(1)
+            .i32_const(2)
+            .binop(walrus::ir::BinaryOp::I32Add);
+        let func = builder.finish(vec![], &mut module.funcs);
+        module.exports.add("add", func);
+        let wasm = module.emit_wasm();
+
+        let mut instrumenter = Instrumenter::parse_only(&wasm).unwrap();
+        instrumenter.enable();
+        let instrumented = Module::from_buffer(&instrumenter.instrument(&wasm).unwrap()).unwrap();
+
+        assert!(instrumented.imports.find("x", "1").is_some());
+        let add = instrumented
+            .exports
+            .iter()
+            .find(|e| e.name == "add")
+            .unwrap();
+        let walrus::ExportItem::Function(add) = add.item else {
+            panic!("export is not a function");
+        };
+        let FunctionKind::Local(add) = &instrumented.funcs.get(add).kind else {
+            panic!("export is not a local function");
+        };
+        let calls = add
+            .block(add.entry_block())
+            .instrs
+            .iter()
+            .filter(|(instr, _)| matches!(instr, Instr::Call(_)))
+            .count();
+        // Entry and exit around the body
+        assert_eq!(calls, 2);
+    }
+}
@@ -0,0 +1,485 @@
+//! Bridge from interpreted host imports to the Soroban `Host`
+//!
+//! Every host function is forwarded to the matching [`Env`] method on the
+//! host. Functions that read or write the contract's linear memory cannot go
+//! through `Env` outside a VM, so they are implemented here on top of the
+//! slice-based [`EnvBase`] methods.
+
+use super::{AccessKind, HostCall, StorageAccess};
+use crate::inspector::storage::StorageDurability;
+use soroban_env_common::xdr::{
+    ContractDataDurability, ContractExecutable, Hash, LedgerEntryData, LedgerKey,
+    LedgerKeyContractCode, LedgerKeyContractData, ScAddress, ScErrorCode, ScErrorType, ScVal,
+};
+use soroban_env_common::{
+    call_macro_with_all_host_functions, AddressObject, Bool, BytesObject, DurationObject, Env,
+    EnvBase, Error, I128Object, I256Object, I256Val, I64Object, MapObject, StorageType,
+    StringObject, Symbol, SymbolObject, SymbolStr, TimepointObject, TryFromVal, U128Object,
+    U256Object, U256Val, U32Val, U64Object, U64Val, Val, VecObject, Void,
+};
+use soroban_env_host::{Host, HostError};
+use std::rc::Rc;
+
+/// Conversion between interpreter `i64` operands and host function arguments
+trait HostArg: Sized {
+    fn from_i64(raw: i64) -> Result<Self, HostError>;
+    fn into_i64(self) -> i64;
+    /// The argument as shown to the user
+    fn to_scval(&self, host: &Host) -> Result<ScVal, HostError>;
+}
+
+impl HostArg for i64 {
+    fn from_i64(raw: i64) -> Result<Self, HostError> {
+        Ok(raw)
+    }
+
+    fn into_i64(self) -> i64 {
+        self
+    }
+
+    fn to_scval(&self, _host: &Host) -> Result<ScVal, HostError> {
+        Ok(ScVal::I64(*self))
+    }
+}
+
+impl HostArg for u64 {
+    fn from_i64(raw: i64) -> Result<Self, HostError> {
+        Ok(raw as u64)
+    }
+
+    fn into_i64(self) -> i64 {
+        self as i64
+    }
+
+    fn to_scval(&self, _host: &Host) -> Result<ScVal, HostError> {
+        Ok(ScVal::U64(*self))
+    }
+}
+
+impl HostArg for StorageType {
+    fn from_i64(raw: i64) -> Result<Self, HostError> {
+        match raw {
+            0 => Ok(StorageType::Temporary),
+            1 => Ok(StorageType::Persistent),
+            2 => Ok(StorageType::Instance),
+            _ => Err(invalid_input()),
+        }
+    }
+
+    fn into_i64(self) -> i64 {
+        self as i64
+    }
+
+    fn to_scval(&self, _host: &Host) -> Result<ScVal, HostError> {
+        let name = match self {
+            StorageType::Temporary => "temporary",
+            StorageType::Persistent => "persistent",
+            StorageType::Instance => "instance",
+        };
+        Ok(ScVal::Symbol(name.try_into().map_err(|_| invalid_input())?))
+    }
+}
+
+macro_rules! impl_host_arg_for_val {
+    ($($type:ty),*) => {
+        $(
+            impl HostArg for $type {
+                fn from_i64(raw: i64) -> Result<Self, HostError> {
+                    let val = Val::from_payload(raw as u64);
+                    if !val.is_good() {
+                        return Err(invalid_input());
+                    }
+                    <$type>::try_from(val).map_err(|_| invalid_input())
+                }
+
+                fn into_i64(self) -> i64 {
+                    Val::from(self).get_payload() as i64
+                }
+
+                fn to_scval(&self, host: &Host) -> Result<ScVal, HostError> {
+                    Ok(ScVal::try_from_val(host, &Val::from(*self))?)
+                }
+            }
+        )*
+    };
+}
+
+impl_host_arg_for_val!(
+    Val,
+    Symbol,
+    AddressObject,
+    BytesObject,
+    DurationObject,
+    TimepointObject,
+    SymbolObject,
+    StringObject,
+    VecObject,
+    MapObject,
+    I64Object,
+    I128Object,
+    I256Object,
+    U64Object,
+    U128Object,
+    U256Object,
+    U64Val,
+    U256Val,
+    I256Val,
+    Void,
+    Bool,
+    Error,
+    U32Val
+);
+
+fn invalid_input() -> HostError {
+    Error::from_type_and_code(ScErrorType::Value, ScErrorCode::InvalidInput).into()
+}
+
+fn out_of_bounds() -> HostError {
+    Error::from_type_and_code(ScErrorType::WasmVm, ScErrorCode::IndexBounds).into()
+}
+
+fn next_arg<T: HostArg>(args: &mut impl Iterator<Item = i64>) -> Result<T, HostError> {
+    T::from_i64(args.next().ok_or_else(invalid_input)?)
+}
+
+macro_rules! generate_host_bridge {
+    {
+        $(
+            $(#[$mod_attr:meta])*
+            mod $mod_id:ident $mod_str:literal
+            {
+                $(
+                    $(#[$fn_attr:meta])*
+                    { $fn_str:literal, $($min_proto:literal)?, $($max_proto:literal)?, fn $fn_id:ident ($($arg:ident:$type:ty),*) -> $ret:ty }
+                )*
+            }
+        )*
+    } => {
+        /// Host function name for the import `module.name`, e.g.
+        /// `put_contract_data` for `l._`.
+        pub(crate) fn host_function_name(module: &str, name: &str) -> Option<&'static str> {
+            match (module, name) {
+                $($(
+                    ($mod_str, $fn_str) => Some(stringify!($fn_id)),
+                )*)*
+                _ => None,
+            }
+        }
+
+        /// Whether `function` names a host function
+        pub(crate) fn is_host_function(function: &str) -> bool {
+            matches!(function, $($(stringify!($fn_id))|*)|*)
+        }
+
+        /// Decode the arguments of a host function by parameter name. Returns
+        /// `None` for unknown names.
+        fn decode_env_args(
+            host: &Host,
+            function: &str,
+            args: &[i64],
+        ) -> Result<Option<Vec<(&'static str, ScVal)>>, HostError> {
+            let mut args = args.iter().copied();
+            Ok(match function {
+                $($(
+                    stringify!($fn_id) => Some(vec![$(
+                        (stringify!($arg), next_arg::<$type>(&mut args)?.to_scval(host)?),
+                    )*]),
+                )*)*
+                _ => None,
+            })
+        }
+
+        /// Call a host function through its `Env` method. Returns `None` for
+        /// unknown names.
+        fn call_env(host: &Host, function: &str, args: &[i64]) -> Option<Result<i64, HostError>> {
+            match function {
+                $($(
+                    stringify!($fn_id) => Some((|| {
+                        $( host.check_protocol_version_lower_bound($min_proto)?; )?
+                        $( host.check_protocol_version_upper_bound($max_proto)?; )?
+                        // Unused by host functions without arguments
+                        #[allow(unused_mut, unused_variables)]
+                        let mut args = args.iter().copied();
+                        $( let $arg: $type = next_arg(&mut args)?; )*
+                        let ret: $ret = <Host as Env>::$fn_id(host, $($arg),*)?;
+                        Ok(ret.into_i64())
+                    })()),
+                )*)*
+                _ => None,
+            }
+        }
+    };
+}
+
+call_macro_with_all_host_functions! { generate_host_bridge }
+
+/// Call the host function `function` with raw interpreter operands.
+///
+/// `memory` is the contract's linear memory, used by the functions that copy
+/// data in or out of it.
+pub(crate) fn call_host(
+    host: &Host,
+    memory: &mut [u8],
+    function: &str,
+    args: &[i64],
+) -> Result<i64, HostError> {
+    let arg = |index: usize| args.get(index).copied().ok_or_else(invalid_input);
+    let u32_arg = |index: usize| -> Result<usize, HostError> {
+        Ok(u32::from(U32Val::from_i64(arg(index)?)?) as usize)
+    };
+
+    match function {
+        "log_from_linear_memory" => {
+            let msg = read_str(memory, u32_arg(0)?, u32_arg(1)?)?;
+            let vals = read_vals(memory, u32_arg(2)?, u32_arg(3)?)?;
+            Ok(host.log_from_slice(msg, &vals)?.into_i64())
+        }
+        "map_new_from_linear_memory" => {
+            let len = u32_arg(2)?;
+            let keys = read_slices(memory, u32_arg(0)?, len)?;
+            let vals = read_vals(memory, u32_arg(1)?, len)?;
+            Ok(host.map_new_from_slices(&keys, &vals)?.into_i64())
+        }
+        "map_unpack_to_linear_memory" => {
+            let map = MapObject::from_i64(arg(0)?)?;
+            let (keys_pos, vals_pos, len) = (u32_arg(1)?, u32_arg(2)?, u32_arg(3)?);
+            let keys = read_slices(memory, keys_pos, len)?
+                .into_iter()
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            let keys = keys.iter().map(String::as_str).collect::<Vec<_>>();
+            let mut vals = vec![Val::VOID.to_val(); len];
+            let ret = host.map_unpack_to_slice(map, &keys, &mut vals)?;
+            write_vals(memory, vals_pos, &vals)?;
+            Ok(ret.into_i64())
+        }
+        "vec_new_from_linear_memory" => {
+            let vals = read_vals(memory, u32_arg(0)?, u32_arg(1)?)?;
+            Ok(host.vec_new_from_slice(&vals)?.into_i64())
+        }
+        "vec_unpack_to_linear_memory" => {
+            let vec = VecObject::from_i64(arg(0)?)?;
+            let mut vals = vec![Val::VOID.to_val(); u32_arg(2)?];
+            let ret = host.vec_unpack_to_slice(vec, &mut vals)?;
+            write_vals(memory, u32_arg(1)?, &vals)?;
+            Ok(ret.into_i64())
+        }
+        "bytes_copy_to_linear_memory" => {
+            let bytes = BytesObject::from_i64(arg(0)?)?;
+            let b_pos = U32Val::from_i64(arg(1)?)?;
+            let slice = slice_mut(memory, u32_arg(2)?, u32_arg(3)?)?;
+            host.bytes_copy_to_slice(bytes, b_pos, slice)?;
+            Ok(Val::VOID.to_val().get_payload() as i64)
+        }
+        "bytes_copy_from_linear_memory" => {
+            let bytes = BytesObject::from_i64(arg(0)?)?;
+            let b_pos = U32Val::from_i64(arg(1)?)?;
+            let slice = slice(memory, u32_arg(2)?, u32_arg(3)?)?;
+            Ok(host.bytes_copy_from_slice(bytes, b_pos, slice)?.into_i64())
+        }
+        "bytes_new_from_linear_memory" => {
+            let slice = slice(memory, u32_arg(0)?, u32_arg(1)?)?;
+            Ok(host.bytes_new_from_slice(slice)?.into_i64())
+        }
+        "string_copy_to_linear_memory" => {
+            let string = StringObject::from_i64(arg(0)?)?;
+            let s_pos = U32Val::from_i64(arg(1)?)?;
+            let slice = slice_mut(memory, u32_arg(2)?, u32_arg(3)?)?;
+            host.string_copy_to_slice(string, s_pos, slice)?;
+            Ok(Val::VOID.to_val().get_payload() as i64)
+        }
+        "symbol_copy_to_linear_memory" => {
+            let symbol = SymbolObject::from_i64(arg(0)?)?;
+            let s_pos = U32Val::from_i64(arg(1)?)?;
+            let slice = slice_mut(memory, u32_arg(2)?, u32_arg(3)?)?;
+            host.symbol_copy_to_slice(symbol, s_pos, slice)?;
+            Ok(Val::VOID.to_val().get_payload() as i64)
+        }
+        "string_new_from_linear_memory" => {
+            let slice = slice(memory, u32_arg(0)?, u32_arg(1)?)?;
+            Ok(host.string_new_from_slice(slice)?.into_i64())
+        }
+        "symbol_new_from_linear_memory" => {
+            let slice = slice(memory, u32_arg(0)?, u32_arg(1)?)?;
+            Ok(host.symbol_new_from_slice(slice)?.into_i64())
+        }
+        "symbol_index_in_linear_memory" => {
+            let symbol = Symbol::from_i64(arg(0)?)?;
+            let strs = read_slices(memory, u32_arg(1)?, u32_arg(2)?)?;
+            Ok(host.symbol_index_in_strs(symbol, &strs)?.into_i64())
+        }
+        _ => call_env(host, function, args).unwrap_or_else(|| {
+            Err(Error::from_type_and_code(ScErrorType::WasmVm, ScErrorCode::MissingValue).into())
+        }),
+    }
+}
+
+/// Decode a call of a storage host function, reading the entry's current
+/// value. Returns `None` for other host functions.
+pub(crate) fn storage_access(
+    host: &Host,
+    function: &str,
+    args: &[i64],
+) -> Result<Option<StorageAccess>, HostError> {
+    let mut args = args.iter().copied();
+    let key: Val = next_arg(&mut args)?;
+    let (kind, new_value) = match function {
+        "put_contract_data" => (AccessKind::Write, Some(next_arg::<Val>(&mut args)?)),
+        "del_contract_data" => (AccessKind::Write, None),
+        "get_contract_data" | "has_contract_data" => (AccessKind::Read, None),
+        _ => return Ok(None),
+    };
+    let storage_type: StorageType = next_arg(&mut args)?;
+
+    let to_scval = |val: Val| ScVal::try_from_val(host, &val).map_err(HostError::from);
+    let old_value = if host.has_contract_data(key, storage_type)?.into() {
+        Some(to_scval(host.get_contract_data(key, storage_type)?)?)
+    } else {
+        None
+    };
+    let new_value = match kind {
+        AccessKind::Read => old_value.clone(),
+        AccessKind::Write => new_value.map(to_scval).transpose()?,
+    };
+
+    Ok(Some(StorageAccess {
+        kind,
+        durability: match storage_type {
+            StorageType::Temporary => StorageDurability::Temporary,
+            StorageType::Persistent => StorageDurability::Persistent,
+            StorageType::Instance => StorageDurability::Instance,
+        },
+        key: to_scval(key)?,
+        old_value,
+        new_value,
+    }))
+}
+
+/// Decode a host function call for display and breakpoint conditions
+pub(crate) fn host_call(
+    host: &Host,
+    function: &'static str,
+    args: &[i64],
+) -> Result<HostCall, HostError> {
+    let args = decode_env_args(host, function, args)?.ok_or_else(|| {
+        HostError::from(Error::from_type_and_code(
+            ScErrorType::WasmVm,
+            ScErrorCode::MissingValue,
+        ))
+    })?;
+    Ok(HostCall { function, args })
+}
+
+/// Arguments of a `call` or `try_call` import
+pub(crate) struct ContractCall {
+    pub contract: Hash,
+    pub function: Symbol,
+    pub function_name: String,
+    pub args: Vec<Val>,
+}
+
+/// Decode the `(contract, func, args)` operands of `call` and `try_call`.
+pub(crate) fn decode_contract_call(host: &Host, args: &[i64]) -> Result<ContractCall, HostError> {
+    let mut args = args.iter().copied();
+    let address: AddressObject = next_arg(&mut args)?;
+    let function: Symbol = next_arg(&mut args)?;
+    let call_args: VecObject = next_arg(&mut args)?;
+
+    let ScAddress::Contract(contract) = host.scaddress_from_address(address)? else {
+        return Err(invalid_input());
+    };
+    let function_name = SymbolStr::try_from_val(host, &function)?.to_string();
+    let len = u32::from(host.vec_len(call_args)?);
+    let args = (0..len)
+        .map(|i| host.vec_get(call_args, U32Val::from(i)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ContractCall {
+        contract,
+        function,
+        function_name,
+        args,
+    })
+}
+
+/// Wasm code of a deployed contract, or `None` for contracts without Wasm,
+/// such as Stellar Asset contracts.
+pub(crate) fn contract_wasm(host: &Host, contract: &Hash) -> Result<Option<Vec<u8>>, HostError> {
+    let budget = host.budget_cloned();
+    let instance_key = Rc::new(LedgerKey::ContractData(LedgerKeyContractData {
+        contract: ScAddress::Contract(contract.clone()),
+        key: ScVal::LedgerKeyContractInstance,
+        durability: ContractDataDurability::Persistent,
+    }));
+    let instance = host.with_mut_storage(|storage| storage.get(&instance_key, &budget))?;
+    let executable = match &instance.data {
+        LedgerEntryData::ContractData(entry) => match &entry.val {
+            ScVal::ContractInstance(instance) => instance.executable.clone(),
+            _ => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    let ContractExecutable::Wasm(hash) = executable else {
+        return Ok(None);
+    };
+
+    let code_key = Rc::new(LedgerKey::ContractCode(LedgerKeyContractCode { hash }));
+    let code = host.with_mut_storage(|storage| storage.get(&code_key, &budget))?;
+    match &code.data {
+        LedgerEntryData::ContractCode(entry) => Ok(Some(entry.code.to_vec())),
+        _ => Ok(None),
+    }
+}
+
+fn slice(memory: &[u8], pos: usize, len: usize) -> Result<&[u8], HostError> {
+    pos.checked_add(len)
+        .and_then(|end| memory.get(pos..end))
+        .ok_or_else(out_of_bounds)
+}
+
+fn slice_mut(memory: &mut [u8], pos: usize, len: usize) -> Result<&mut [u8], HostError> {
+    pos.checked_add(len)
+        .and_then(|end| memory.get_mut(pos..end))
+        .ok_or_else(out_of_bounds)
+}
+
+fn read_str(memory: &[u8], pos: usize, len: usize) -> Result<&str, HostError> {
+    std::str::from_utf8(slice(memory, pos, len)?).map_err(|_| invalid_input())
+}
+
+/// Read `len` Vals stored as consecutive little-endian `u64` payloads
+fn read_vals(memory: &[u8], pos: usize, len: usize) -> Result<Vec<Val>, HostError> {
+    let bytes = slice(memory, pos, len.checked_mul(8).ok_or_else(out_of_bounds)?)?;
+    bytes
+        .chunks_exact(8)
+        .map(|chunk| {
+            let payload = u64::from_le_bytes(chunk.try_into().expect("8-byte chunk"));
+            Val::from_i64(payload as i64)
+        })
+        .collect()
+}
+
+fn write_vals(memory: &mut [u8], pos: usize, vals: &[Val]) -> Result<(), HostError> {
+    let bytes = slice_mut(
+        memory,
+        pos,
+        vals.len().checked_mul(8).ok_or_else(out_of_bounds)?,
+    )?;
+    for (chunk, val) in bytes.chunks_exact_mut(8).zip(vals) {
+        chunk.copy_from_slice(&val.get_payload().to_le_bytes());
+    }
+    Ok(())
+}
+
+/// Read `len` strings described by consecutive `(ptr: u32, len: u32)` pairs
+fn read_slices(memory: &[u8], pos: usize, len: usize) -> Result<Vec<&str>, HostError> {
+    let table = slice(memory, pos, len.checked_mul(8).ok_or_else(out_of_bounds)?)?;
+    table
+        .chunks_exact(8)
+        .map(|entry| {
+            let ptr = u32::from_le_bytes(entry[..4].try_into().expect("4-byte pointer"));
+            let len = u32::from_le_bytes(entry[4..].try_into().expect("4-byte length"));
+            read_str(memory, ptr as usize, len as usize)
+        })
+        .collect()
+}
@@ -0,0 +1,1168 @@
+//! Stack machine executing a decoded module
+
+use super::host;
+use super::module::{Load, Module, NumOp, Op, Store, PAGE_SIZE};
+use super::{
+    AuthCheck, HostCall, Interpreter, PauseHandler, PauseReason, ResumeAction, StorageAccess, Trap,
+    WasmValue,
+};
+use crate::utils::{SourceLocation, SourceMap};
+use soroban_env_host::xdr::{Hash, ScErrorCode, ScErrorType, ScVal};
+use soroban_env_host::{Error, Host, HostError, TryFromVal, Val};
+use std::collections::HashSet;
+
+/// Maximum number of nested interpreted frames
+const MAX_FRAMES: usize = 1024;
+
+/// Largest memory the 32-bit address space allows, in pages
+const MAX_PAGES: usize = 65536;
+
+/// Branch target of an enclosing block, loop or function body
+#[derive(Debug, Clone, Copy)]
+struct Label {
+    /// Operand stack height below the block's parameters
+    height: usize,
+    /// Number of values a branch to this label carries
+    arity: usize,
+    /// Instruction to continue at after branching
+    target: usize,
+    is_loop: bool,
+}
+
+#[derive(Debug)]
+struct Frame {
+    function: u32,
+    pc: usize,
+    locals: Vec<WasmValue>,
+    labels: Vec<Label>,
+    stack_base: usize,
+}
+
+/// When the machine should next hand control to the pause handler.
+///
+/// Depths count frames across every interpreted contract in the call, so a
+/// step over a cross-contract call skips the callee's frames too.
+#[derive(Debug, Clone)]
+pub(crate) enum RunMode {
+    Step,
+    /// Pause at the next instruction at or above this depth
+    StepOver(usize),
+    /// Pause at the next instruction above this depth
+    StepOut(usize),
+    /// Pause at the next instruction on a source line other than the given
+    /// file and line at this depth, or on any line at another depth
+    StepIntoLine(usize, Option<(String, usize)>),
+    /// As `StepIntoLine`, but only at or above this depth
+    StepOverLine(usize, Option<(String, usize)>),
+    Continue,
+}
+
+/// One interpreted contract invocation, from instantiation to return
+pub(crate) struct Machine<'a> {
+    interpreter: &'a Interpreter,
+    module: &'a Module,
+    host: &'a Host,
+    /// Strkey of the contract being run
+    contract_id: String,
+    /// Frames of the interpreted contracts that called this one, innermost
+    /// first
+    outer: Vec<FrameInfo>,
+    breakpoints: HashSet<u32>,
+    /// Offsets of instructions with a line breakpoint
+    line_breakpoints: HashSet<usize>,
+    /// Interpreter breakpoints version the two sets above were taken at
+    breakpoints_version: u64,
+    memory: Vec<u8>,
+    max_pages: usize,
+    globals: Vec<WasmValue>,
+    stack: Vec<WasmValue>,
+    frames: Vec<Frame>,
+    mode: RunMode,
+    /// Set when a frame was pushed and its first instruction has not run yet
+    entering: bool,
+}
+
+impl<'a> Machine<'a> {
+    /// Instantiate the module: fresh memory with data segments and globals.
+    pub fn new(
+        interpreter: &'a Interpreter,
+        module: &'a Module,
+        host: &'a Host,
+        contract: &Hash,
+        outer: Vec<FrameInfo>,
+        mode: RunMode,
+    ) -> Result<Self, Trap> {
+        let (initial_pages, max_pages) = module.memory.unwrap_or((0, Some(0)));
+        let max_pages = max_pages.unwrap_or(MAX_PAGES).min(MAX_PAGES);
+        let mut memory = vec![0; initial_pages * PAGE_SIZE];
+        for (address, bytes) in &module.data {
+            memory
+                .get_mut(*address..*address + bytes.len())
+                .ok_or(Trap::MemoryOutOfBounds)?
+                .copy_from_slice(bytes);
+        }
+
+        let contract_id = contract_strkey(contract);
+        Ok(Self {
+            interpreter,
+            module,
+            host,
+            breakpoints: interpreter.breakpoints_in(module, &contract_id),
+            line_breakpoints: interpreter.line_breakpoints_in(module),
+            breakpoints_version: interpreter.breakpoints_version(),
+            contract_id,
+            outer,
+            memory,
+            max_pages,
+            globals: module.globals.clone(),
+            stack: Vec::new(),
+            frames: Vec::new(),
+            mode,
+            entering: false,
+        })
+    }
+
+    /// Run mode left by the last pause, for the calling contract to continue
+    /// with
+    pub fn mode(&self) -> RunMode {
+        self.mode.clone()
+    }
+
+    /// Depth of the current frame across all interpreted contracts
+    fn depth(&self) -> usize {
+        self.outer.len() + self.frames.len() - 1
+    }
+
+    /// Byte offset of the next instruction in the binary
+    fn offset(&self) -> Option<usize> {
+        let frame = self.frames.last()?;
+        Some(
+            self.module
+                .function(frame.function)?
+                .code
+                .get(frame.pc)?
+                .offset,
+        )
+    }
+
+    /// File and line of the next instruction, if the contract has debug info
+    /// covering it
+    fn line(&self) -> Option<(&str, usize)> {
+        self.module.source_map.as_ref()?.get_line(self.offset()?)
+    }
+
+    /// Whether a line step started on `start` at depth `from` ends here
+    fn reached_line(&self, depth: usize, from: usize, start: &Option<(String, usize)>) -> bool {
+        let Some(line) = self.line() else {
+            return false;
+        };
+        depth != from || start.as_ref().map(|(file, line)| (file.as_str(), *line)) != Some(line)
+    }
+
+    /// Call a function and run until it returns.
+    pub fn run(
+        &mut self,
+        function: u32,
+        args: &[WasmValue],
+        handler: &mut dyn PauseHandler,
+    ) -> Result<Option<WasmValue>, Trap> {
+        let module = self.module;
+        self.stack.extend_from_slice(args);
+        self.call(function, handler)?;
+
+        while let Some(frame) = self.frames.last() {
+            let code = &module
+                .function(frame.function)
+                .ok_or_else(|| Trap::Invalid("frame without code".to_string()))?
+                .code;
+            let instr = code
+                .get(frame.pc)
+                .ok_or_else(|| Trap::Invalid("fell off the end of a function".to_string()))?;
+
+            self.check_pause(handler)?;
+            if let Err(trap) = self.execute(&instr.op, handler) {
+                return Err(self.fail(trap, handler));
+            }
+        }
+
+        let returns = module
+            .signature(function)
+            .map_or(0, |signature| signature.results.len());
+        Ok(if returns > 0 { self.stack.pop() } else { None })
+    }
+
+    /// Hand control to the pause handler if a breakpoint or step ends here.
+    fn check_pause(&mut self, handler: &mut dyn PauseHandler) -> Result<(), Trap> {
+        // Breakpoints may have changed while a callee was paused
+        if self.breakpoints_version != self.interpreter.breakpoints_version() {
+            self.breakpoints = self
+                .interpreter
+                .breakpoints_in(self.module, &self.contract_id);
+            self.line_breakpoints = self.interpreter.line_breakpoints_in(self.module);
+            self.breakpoints_version = self.interpreter.breakpoints_version();
+        }
+
+        let depth = self.depth();
+        let entered = std::mem::take(&mut self.entering);
+        let function = self.frames.last().expect("paused without a frame").function;
+
+        let reason = if entered
+            && self.breakpoints.contains(&function)
+            && handler.on_breakpoint(&PauseContext {
+                machine: self,
+                reason: PauseReason::Breakpoint,
+                detail: PauseDetail::None,
+            }) {
+            Some(PauseReason::Breakpoint)
+        } else if !self.line_breakpoints.is_empty()
+            && self
+                .offset()
+                .is_some_and(|offset| self.line_breakpoints.contains(&offset))
+        {
+            Some(PauseReason::LineBreakpoint)
+        } else {
+            match &self.mode {
+                RunMode::Step => Some(PauseReason::Step),
+                RunMode::StepOver(d) if depth <= *d => Some(PauseReason::Step),
+                RunMode::StepOut(d) if depth < *d => Some(PauseReason::Step),
+                RunMode::StepIntoLine(d, start) if self.reached_line(depth, *d, start) => {
+                    Some(PauseReason::Step)
+                }
+                RunMode::StepOverLine(d, start)
+                    if depth <= *d && self.reached_line(depth, *d, start) =>
+                {
+                    Some(PauseReason::Step)
+                }
+                _ => None,
+            }
+        };
+
+        match reason {
+            Some(reason) => self.pause(reason, PauseDetail::None, handler),
+            None => Ok(()),
+        }
+    }
+
+    /// With break on error, pause where `trap` was raised, once per failure.
+    /// Returns the trap to unwind with.
+    fn fail(&mut self, trap: Trap, handler: &mut dyn PauseHandler) -> Trap {
+        if !self.interpreter.breaks_on_error()
+            || matches!(
+                trap,
+                Trap::Aborted | Trap::Unsupported(_) | Trap::Invalid(_)
+            )
+            || self.interpreter.report_error()
+        {
+            return trap;
+        }
+        match self.pause(PauseReason::Error, PauseDetail::Trap(&trap), handler) {
+            Err(aborted) => aborted,
+            Ok(()) => trap,
+        }
+    }
+
+    /// Hand control to the pause handler and take up the run mode it asks for.
+    fn pause(
+        &mut self,
+        reason: PauseReason,
+        detail: PauseDetail,
+        handler: &mut dyn PauseHandler,
+    ) -> Result<(), Trap> {
+        let depth = self.depth();
+        let action = handler.on_pause(&PauseContext {
+            machine: self,
+            reason,
+            detail,
+        });
+        if let Some(changes) = handler.take_breakpoint_changes() {
+            self.interpreter.apply_breakpoint_changes(changes);
+        }
+        let line = self.line().map(|(file, line)| (file.to_string(), line));
+        let has_lines = self.module.source_map.is_some();
+        self.mode = match action {
+            ResumeAction::StepInto => RunMode::Step,
+            ResumeAction::StepOver => RunMode::StepOver(depth),
+            ResumeAction::StepOut => RunMode::StepOut(depth),
+            // Without line tables no instruction has a line to stop on
+            ResumeAction::StepIntoLine if !has_lines => RunMode::Step,
+            ResumeAction::StepOverLine if !has_lines => RunMode::StepOver(depth),
+            ResumeAction::StepIntoLine => RunMode::StepIntoLine(depth, line),
+            ResumeAction::StepOverLine => RunMode::StepOverLine(depth, line),
+            ResumeAction::Continue => RunMode::Continue,
+            ResumeAction::Abort => return Err(Trap::Aborted),
+        };
+        Ok(())
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("executing without a frame")
+    }
+
+    fn pop(&mut self) -> Result<WasmValue, Trap> {
+        self.stack
+            .pop()
+            .ok_or_else(|| Trap::Invalid("operand stack underflow".to_string()))
+    }
+
+    fn pop_i32(&mut self) -> Result<i32, Trap> {
+        match self.pop()? {
+            WasmValue::I32(v) => Ok(v),
+            other => Err(Trap::Invalid(format!("expected i32, found {}", other))),
+        }
+    }
+
+    fn pop_i64(&mut self) -> Result<i64, Trap> {
+        match self.pop()? {
+            WasmValue::I64(v) => Ok(v),
+            other => Err(Trap::Invalid(format!("expected i64, found {}", other))),
+        }
+    }
+
+    /// Pop `count` values, in push order
+    fn pop_n(&mut self, count: usize) -> Result<Vec<WasmValue>, Trap> {
+        let from = self
+            .stack
+            .len()
+            .checked_sub(count)
+            .ok_or_else(|| Trap::Invalid("operand stack underflow".to_string()))?;
+        Ok(self.stack.split_off(from))
+    }
+
+    /// Call a function from the current frame's `call` instruction. Host
+    /// imports run with the frame still on that instruction, so pauses and
+    /// failures inside them point at the call; a defined function returns to
+    /// the instruction after it.
+    fn call_from_frame(
+        &mut self,
+        function: u32,
+        handler: &mut dyn PauseHandler,
+    ) -> Result<(), Trap> {
+        if (function as usize) < self.module.imports.len() {
+            self.call(function, handler)?;
+            self.frame().pc += 1;
+            Ok(())
+        } else {
+            self.frame().pc += 1;
+            self.call(function, handler)
+        }
+    }
+
+    /// Call a host import or push a frame for a defined function.
+    fn call(&mut self, function: u32, handler: &mut dyn PauseHandler) -> Result<(), Trap> {
+        let module = self.module;
+        let signature = module
+            .signature(function)
+            .ok_or_else(|| Trap::Invalid(format!("call to unknown function {}", function)))?;
+        let args = self.pop_n(signature.params.len())?;
+
+        if let Some(import) = module.imports.get(function as usize) {
+            let raw: Vec<i64> = args
+                .iter()
+                .map(|arg| match *arg {
+                    WasmValue::I32(v) => v as i64,
+                    WasmValue::I64(v) => v,
+                })
+                .collect();
+            if self.interpreter.traces_host_calls() {
+                let call = host::host_call(self.host, import.function, &raw)?;
+                let detail = PauseDetail::HostCall(&call);
+                let stop = handler.on_host_call(&PauseContext {
+                    machine: self,
+                    reason: PauseReason::HostCall,
+                    detail,
+                });
+                if stop {
+                    self.pause(PauseReason::HostCall, detail, handler)?;
+                }
+            }
+            if matches!(import.function, "require_auth" | "require_auth_for_args") {
+                self.interpreter.record_auth_check(AuthCheck {
+                    contract_id: self.contract_id.clone(),
+                    function: import.function,
+                    args: raw.clone(),
+                });
+            }
+            let access = if self.interpreter.watches_storage() {
+                host::storage_access(self.host, import.function, &raw)?
+            } else {
+                None
+            };
+            let ret = match import.function {
+                "call" | "try_call" => self.call_contract(import.function, &raw, handler)?,
+                _ => host::call_host(self.host, &mut self.memory, import.function, &raw)?,
+            };
+            if !signature.results.is_empty() {
+                self.stack.push(WasmValue::I64(ret));
+            }
+            if let Some(access) = access {
+                let detail = PauseDetail::StorageAccess(&access);
+                let watched = handler.on_storage_access(&PauseContext {
+                    machine: self,
+                    reason: PauseReason::Watchpoint,
+                    detail,
+                });
+                if watched {
+                    self.pause(PauseReason::Watchpoint, detail, handler)?;
+                }
+            }
+            return Ok(());
+        }
+
+        if self.frames.len() >= MAX_FRAMES {
+            return Err(Trap::StackOverflow);
+        }
+        let defined = module
+            .function(function)
+            .ok_or_else(|| Trap::Invalid(format!("call to unknown function {}", function)))?;
+        let mut locals = args;
+        for ty in &defined.locals {
+            locals.push(WasmValue::zero(*ty)?);
+        }
+
+        let stack_base = self.stack.len();
+        self.frames.push(Frame {
+            function,
+            pc: 0,
+            locals,
+            labels: vec![Label {
+                height: stack_base,
+                arity: signature.results.len(),
+                target: defined.code.len(),
+                is_loop: false,
+            }],
+            stack_base,
+        });
+        self.entering = true;
+        Ok(())
+    }
+
+    /// Invoke another contract through `call` or `try_call`.
+    ///
+    /// Wasm callees are interpreted in a frame of their own, so breakpoints
+    /// and steps reach into them; other contracts run on the host.
+    fn call_contract(
+        &mut self,
+        import: &str,
+        raw: &[i64],
+        handler: &mut dyn PauseHandler,
+    ) -> Result<i64, Trap> {
+        let call = host::decode_contract_call(self.host, raw)?;
+        let Some(module) = self.interpreter.callee_module(self.host, &call.contract)? else {
+            return Ok(host::call_host(self.host, &mut self.memory, import, raw)?);
+        };
+
+        let callee_id = contract_strkey(&call.contract);
+        let outer = self.frames_info();
+        if outer.iter().any(|frame| frame.contract_id == callee_id) {
+            return Err(Trap::Host(
+                Error::from_type_and_code(ScErrorType::Context, ScErrorCode::InvalidAction).into(),
+            ));
+        }
+
+        let mut mode = self.mode.clone();
+        let mut trap = None;
+        let result =
+            self.host
+                .with_test_contract_frame(call.contract.clone(), call.function, || {
+                    let (result, final_mode) = self.interpreter.call_export(
+                        self.host,
+                        &module,
+                        &call.contract,
+                        &call.function_name,
+                        &call.args,
+                        outer,
+                        mode.clone(),
+                        handler,
+                    );
+                    mode = final_mode;
+                    result.map_err(|t| {
+                        let error = t.to_error();
+                        trap = Some(t);
+                        HostError::from(error)
+                    })
+                });
+        self.mode = mode;
+
+        match (result, trap) {
+            (Ok(val), _) => Ok(val.get_payload() as i64),
+            // Debugger-side failures are not the contract's to recover from
+            (Err(_), Some(t @ (Trap::Aborted | Trap::Unsupported(_) | Trap::Invalid(_)))) => Err(t),
+            (Err(e), _) if import == "try_call" && e.is_recoverable() => {
+                self.interpreter.clear_reported_error();
+                // As the host does: contract errors pass through, the rest
+                // are narrowed to one code
+                let error = if e.error.is_type(ScErrorType::Contract) {
+                    e.error
+                } else {
+                    Error::from_type_and_code(ScErrorType::Context, ScErrorCode::InvalidAction)
+                };
+                Ok(Val::from(error).get_payload() as i64)
+            }
+            (Err(_), Some(t)) => Err(t),
+            (Err(e), None) => Err(Trap::Host(e)),
+        }
+    }
+
+    /// Frames of this and the calling contracts, innermost first
+    fn frames_info(&self) -> Vec<FrameInfo> {
+        self.frames
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(index, frame)| self.frame_info(frame, index > 0))
+            .chain(self.outer.iter().cloned())
+            .collect()
+    }
+
+    /// Where `frame` is. A `calling` frame has moved past the `call` it is
+    /// waiting on, so it is placed on that instruction.
+    fn frame_info(&self, frame: &Frame, calling: bool) -> FrameInfo {
+        let pc = if calling {
+            frame.pc.saturating_sub(1)
+        } else {
+            frame.pc
+        };
+        let offset = self
+            .module
+            .function(frame.function)
+            .and_then(|function| function.code.get(pc))
+            .map_or(0, |instr| instr.offset);
+        FrameInfo {
+            contract_id: self.contract_id.clone(),
+            function_index: frame.function,
+            function_name: self.module.names.get(&frame.function).cloned(),
+            offset,
+            location: self
+                .module
+                .source_map
+                .as_ref()
+                .and_then(|map| map.get_location(offset)),
+        }
+    }
+
+    /// Pop the current frame, leaving its results on the caller's stack.
+    fn return_from_frame(&mut self) -> Result<(), Trap> {
+        let frame = self.frames.pop().expect("returning without a frame");
+        let results = self
+            .module
+            .signature(frame.function)
+            .map_or(0, |signature| signature.results.len());
+        let keep_from = self
+            .stack
+            .len()
+            .checked_sub(results)
+            .filter(|from| *from >= frame.stack_base)
+            .ok_or_else(|| Trap::Invalid("operand stack underflow".to_string()))?;
+        self.stack.drain(frame.stack_base..keep_from);
+        Ok(())
+    }
+
+    /// Branch to the label `depth` levels out.
+    fn branch(&mut self, depth: u32) -> Result<(), Trap> {
+        let stack_len = self.stack.len();
+        let frame = self.frames.last_mut().expect("branching without a frame");
+        let index = frame
+            .labels
+            .len()
+            .checked_sub(depth as usize + 1)
+            .ok_or_else(|| Trap::Invalid(format!("branch depth {} out of range", depth)))?;
+        let label = frame.labels[index];
+
+        let keep_from = stack_len
+            .checked_sub(label.arity)
+            .filter(|from| *from >= label.height)
+            .ok_or_else(|| Trap::Invalid("operand stack underflow".to_string()))?;
+
+        if label.is_loop {
+            frame.labels.truncate(index + 1);
+        } else {
+            frame.labels.truncate(index);
+        }
+        frame.pc = label.target;
+        let finished = frame.labels.is_empty();
+        self.stack.drain(label.height..keep_from);
+
+        if finished {
+            self.return_from_frame()?;
+        }
+        Ok(())
+    }
+
+    fn push_label(&mut self, params: usize, arity: usize, target: usize, is_loop: bool) {
+        let height = self.stack.len().saturating_sub(params);
+        self.frame().labels.push(Label {
+            height,
+            arity,
+            target,
+            is_loop,
+        });
+    }
+
+    /// Effective address of a memory access, checked against memory size.
+    fn address(&self, base: i32, offset: u64, size: usize) -> Result<usize, Trap> {
+        let address = (base as u32 as u64)
+            .checked_add(offset)
+            .ok_or(Trap::MemoryOutOfBounds)?;
+        let end = address
+            .checked_add(size as u64)
+            .ok_or(Trap::MemoryOutOfBounds)?;
+        if end > self.memory.len() as u64 {
+            return Err(Trap::MemoryOutOfBounds);
+        }
+        Ok(address as usize)
+    }
+
+    fn load<const N: usize>(&mut self, offset: u64) -> Result<[u8; N], Trap> {
+        let base = self.pop_i32()?;
+        let address = self.address(base, offset, N)?;
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(&self.memory[address..address + N]);
+        Ok(bytes)
+    }
+
+    fn store(&mut self, offset: u64, bytes: &[u8]) -> Result<(), Trap> {
+        let base = self.pop_i32()?;
+        let address = self.address(base, offset, bytes.len())?;
+        self.memory[address..address + bytes.len()].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Execute one instruction and advance the program counter.
+    fn execute(&mut self, op: &Op, handler: &mut dyn PauseHandler) -> Result<(), Trap> {
+        // Control instructions set the program counter themselves
+        match op {
+            Op::Block {
+                results,
+                params,
+                end,
+            } => {
+                self.push_label(*params, *results, end + 1, false);
+                self.frame().pc += 1;
+                return Ok(());
+            }
+            Op::Loop { params } => {
+                let pc = self.frame().pc;
+                self.push_label(*params, *params, pc + 1, true);
+                self.frame().pc += 1;
+                return Ok(());
+            }
+            Op::If {
+                results,
+                params,
+                else_at,
+                end,
+            } => {
+                let condition = self.pop_i32()?;
+                if condition != 0 {
+                    self.push_label(*params, *results, end + 1, false);
+                    self.frame().pc += 1;
+                } else if let Some(else_at) = else_at {
+                    self.push_label(*params, *results, end + 1, false);
+                    self.frame().pc = else_at + 1;
+                } else {
+                    self.frame().pc = end + 1;
+                }
+                return Ok(());
+            }
+            Op::Else { end } => {
+                let frame = self.frame();
+                frame.labels.pop();
+                frame.pc = end + 1;
+                return Ok(());
+            }
+            Op::End => {
+                let frame = self.frame();
+                frame.labels.pop();
+                if frame.labels.is_empty() {
+                    return self.return_from_frame();
+                }
+                frame.pc += 1;
+                return Ok(());
+            }
+            Op::Br(depth) => return self.branch(*depth),
+            Op::BrIf(depth) => {
+                if self.pop_i32()? != 0 {
+                    return self.branch(*depth);
+                }
+                self.frame().pc += 1;
+                return Ok(());
+            }
+            Op::BrTable(targets, default) => {
+                let index = self.pop_i32()? as u32 as usize;
+                let depth = targets.get(index).copied().unwrap_or(*default);
+                return self.branch(depth);
+            }
+            Op::Return => return self.return_from_frame(),
+            Op::Call(function) => return self.call_from_frame(*function, handler),
+            Op::CallIndirect(type_index) => {
+                let element = self.pop_i32()? as u32 as usize;
+                let function = self
+                    .module
+                    .table
+                    .get(element)
+                    .copied()
+                    .flatten()
+                    .ok_or(Trap::UndefinedElement)?;
+                if self.module.signature(function) != self.module.types.get(*type_index as usize) {
+                    return Err(Trap::IndirectCallTypeMismatch);
+                }
+                return self.call_from_frame(function, handler);
+            }
+            _ => {}
+        }
+
+        match op {
+            Op::Unreachable => return Err(Trap::Unreachable),
+            Op::Nop => {}
+            Op::Drop => {
+                self.pop()?;
+            }
+            Op::Select => {
+                let condition = self.pop_i32()?;
+                let second = self.pop()?;
+                let first = self.pop()?;
+                self.stack.push(if condition != 0 { first } else { second });
+            }
+            Op::LocalGet(index) => {
+                let value = *self
+                    .frame()
+                    .locals
+                    .get(*index as usize)
+                    .ok_or_else(|| Trap::Invalid(format!("unknown local {}", index)))?;
+                self.stack.push(value);
+            }
+            Op::LocalSet(index) | Op::LocalTee(index) => {
+                let value = self.pop()?;
+                if matches!(op, Op::LocalTee(_)) {
+                    self.stack.push(value);
+                }
+                *self
+                    .frame()
+                    .locals
+                    .get_mut(*index as usize)
+                    .ok_or_else(|| Trap::Invalid(format!("unknown local {}", index)))? = value;
+            }
+            Op::GlobalGet(index) => {
+                let value = *self
+                    .globals
+                    .get(*index as usize)
+                    .ok_or_else(|| Trap::Invalid(format!("unknown global {}", index)))?;
+                self.stack.push(value);
+            }
+            Op::GlobalSet(index) => {
+                let value = self.pop()?;
+                *self
+                    .globals
+                    .get_mut(*index as usize)
+                    .ok_or_else(|| Trap::Invalid(format!("unknown global {}", index)))? = value;
+            }
+            Op::Load(kind, offset) => {
+                let value = match kind {
+                    Load::I32 => WasmValue::I32(i32::from_le_bytes(self.load(*offset)?)),
+                    Load::I64 => WasmValue::I64(i64::from_le_bytes(self.load(*offset)?)),
+                    Load::I32_8S => WasmValue::I32(i8::from_le_bytes(self.load(*offset)?) as i32),
+                    Load::I32_8U => WasmValue::I32(u8::from_le_bytes(self.load(*offset)?) as i32),
+                    Load::I32_16S => WasmValue::I32(i16::from_le_bytes(self.load(*offset)?) as i32),
+                    Load::I32_16U => WasmValue::I32(u16::from_le_bytes(self.load(*offset)?) as i32),
+                    Load::I64_8S => WasmValue::I64(i8::from_le_bytes(self.load(*offset)?) as i64),
+                    Load::I64_8U => WasmValue::I64(u8::from_le_bytes(self.load(*offset)?) as i64),
+                    Load::I64_16S => WasmValue::I64(i16::from_le_bytes(self.load(*offset)?) as i64),
+                    Load::I64_16U => WasmValue::I64(u16::from_le_bytes(self.load(*offset)?) as i64),
+                    Load::I64_32S => WasmValue::I64(i32::from_le_bytes(self.load(*offset)?) as i64),
+                    Load::I64_32U => WasmValue::I64(u32::from_le_bytes(self.load(*offset)?) as i64),
+                };
+                self.stack.push(value);
+            }
+            Op::Store(kind, offset) => match kind {
+                Store::I32 => {
+                    let value = self.pop_i32()?;
+                    self.store(*offset, &value.to_le_bytes())?;
+                }
+                Store::I64 => {
+                    let value = self.pop_i64()?;
+                    self.store(*offset, &value.to_le_bytes())?;
+                }
+                Store::I32_8 => {
+                    let value = self.pop_i32()?;
+                    self.store(*offset, &(value as u8).to_le_bytes())?;
+                }
+                Store::I32_16 => {
+                    let value = self.pop_i32()?;
+                    self.store(*offset, &(value as u16).to_le_bytes())?;
+                }
+                Store::I64_8 => {
+                    let value = self.pop_i64()?;
+                    self.store(*offset, &(value as u8).to_le_bytes())?;
+                }
+                Store::I64_16 => {
+                    let value = self.pop_i64()?;
+                    self.store(*offset, &(value as u16).to_le_bytes())?;
+                }
+                Store::I64_32 => {
+                    let value = self.pop_i64()?;
+                    self.store(*offset, &(value as u32).to_le_bytes())?;
+                }
+            },
+            Op::MemorySize => {
+                let pages = self.memory.len() / PAGE_SIZE;
+                self.stack.push(WasmValue::I32(pages as i32));
+            }
+            Op::MemoryGrow => {
+                let delta = self.pop_i32()? as u32 as usize;
+                let pages = self.memory.len() / PAGE_SIZE;
+                if pages + delta > self.max_pages {
+                    self.stack.push(WasmValue::I32(-1));
+                } else {
+                    self.memory.resize((pages + delta) * PAGE_SIZE, 0);
+                    self.stack.push(WasmValue::I32(pages as i32));
+                }
+            }
+            Op::I32Const(value) => self.stack.push(WasmValue::I32(*value)),
+            Op::I64Const(value) => self.stack.push(WasmValue::I64(*value)),
+            Op::Numeric(num) => self.numeric(*num)?,
+            _ => unreachable!("control instructions are handled above"),
+        }
+
+        self.frame().pc += 1;
+        Ok(())
+    }
+
+    fn numeric(&mut self, op: NumOp) -> Result<(), Trap> {
+        use NumOp::*;
+
+        let value = match op {
+            I32Eqz => WasmValue::I32((self.pop_i32()? == 0) as i32),
+            I64Eqz => WasmValue::I32((self.pop_i64()? == 0) as i32),
+            I32Eq | I32Ne | I32LtS | I32LtU | I32GtS | I32GtU | I32LeS | I32LeU | I32GeS
+            | I32GeU => {
+                let b = self.pop_i32()?;
+                let a = self.pop_i32()?;
+                let (ua, ub) = (a as u32, b as u32);
+                WasmValue::I32(match op {
+                    I32Eq => a == b,
+                    I32Ne => a != b,
+                    I32LtS => a < b,
+                    I32LtU => ua < ub,
+                    I32GtS => a > b,
+                    I32GtU => ua > ub,
+                    I32LeS => a <= b,
+                    I32LeU => ua <= ub,
+                    I32GeS => a >= b,
+                    _ => ua >= ub,
+                } as i32)
+            }
+            I64Eq | I64Ne | I64LtS | I64LtU | I64GtS | I64GtU | I64LeS | I64LeU | I64GeS
+            | I64GeU => {
+                let b = self.pop_i64()?;
+                let a = self.pop_i64()?;
+                let (ua, ub) = (a as u64, b as u64);
+                WasmValue::I32(match op {
+                    I64Eq => a == b,
+                    I64Ne => a != b,
+                    I64LtS => a < b,
+                    I64LtU => ua < ub,
+                    I64GtS => a > b,
+                    I64GtU => ua > ub,
+                    I64LeS => a <= b,
+                    I64LeU => ua <= ub,
+                    I64GeS => a >= b,
+                    _ => ua >= ub,
+                } as i32)
+            }
+            I32Clz => WasmValue::I32(self.pop_i32()?.leading_zeros() as i32),
+            I32Ctz => WasmValue::I32(self.pop_i32()?.trailing_zeros() as i32),
+            I32Popcnt => WasmValue::I32(self.pop_i32()?.count_ones() as i32),
+            I64Clz => WasmValue::I64(self.pop_i64()?.leading_zeros() as i64),
+            I64Ctz => WasmValue::I64(self.pop_i64()?.trailing_zeros() as i64),
+            I64Popcnt => WasmValue::I64(self.pop_i64()?.count_ones() as i64),
+            I32Add | I32Sub | I32Mul | I32DivS | I32DivU | I32RemS | I32RemU | I32And | I32Or
+            | I32Xor | I32Shl | I32ShrS | I32ShrU | I32Rotl | I32Rotr => {
+                let b = self.pop_i32()?;
+                let a = self.pop_i32()?;
+                WasmValue::I32(binary_i32(op, a, b)?)
+            }
+            I64Add | I64Sub | I64Mul | I64DivS | I64DivU | I64RemS | I64RemU | I64And | I64Or
+            | I64Xor | I64Shl | I64ShrS | I64ShrU | I64Rotl | I64Rotr => {
+                let b = self.pop_i64()?;
+                let a = self.pop_i64()?;
+                WasmValue::I64(binary_i64(op, a, b)?)
+            }
+            I32WrapI64 => WasmValue::I32(self.pop_i64()? as i32),
+            I64ExtendI32S => WasmValue::I64(self.pop_i32()? as i64),
+            I64ExtendI32U => WasmValue::I64(self.pop_i32()? as u32 as i64),
+            I32Extend8S => WasmValue::I32(self.pop_i32()? as i8 as i32),
+            I32Extend16S => WasmValue::I32(self.pop_i32()? as i16 as i32),
+            I64Extend8S => WasmValue::I64(self.pop_i64()? as i8 as i64),
+            I64Extend16S => WasmValue::I64(self.pop_i64()? as i16 as i64),
+            I64Extend32S => WasmValue::I64(self.pop_i64()? as i32 as i64),
+        };
+        self.stack.push(value);
+        Ok(())
+    }
+}
+
+fn binary_i32(op: NumOp, a: i32, b: i32) -> Result<i32, Trap> {
+    use NumOp::*;
+
+    let (ua, ub) = (a as u32, b as u32);
+    Ok(match op {
+        I32Add => a.wrapping_add(b),
+        I32Sub => a.wrapping_sub(b),
+        I32Mul => a.wrapping_mul(b),
+        I32DivS => {
+            if b == 0 {
+                return Err(Trap::DivisionByZero);
+            }
+            a.checked_div(b).ok_or(Trap::IntegerOverflow)?
+        }
+        I32DivU => ua.checked_div(ub).ok_or(Trap::DivisionByZero)? as i32,
+        I32RemS => {
+            if b == 0 {
+                return Err(Trap::DivisionByZero);
+            }
+            a.wrapping_rem(b)
+        }
+        I32RemU => ua.checked_rem(ub).ok_or(Trap::DivisionByZero)? as i32,
+        I32And => a & b,
+        I32Or => a | b,
+        I32Xor => a ^ b,
+        I32Shl => a.wrapping_shl(ub),
+        I32ShrS => a.wrapping_shr(ub),
+        I32ShrU => ua.wrapping_shr(ub) as i32,
+        I32Rotl => ua.rotate_left(ub) as i32,
+        I32Rotr => ua.rotate_right(ub) as i32,
+        other => unreachable!("{:?} is not an i32 binary operator", other),
+    })
+}
+
+fn binary_i64(op: NumOp, a: i64, b: i64) -> Result<i64, Trap> {
+    use NumOp::*;
+
+    let (ua, ub) = (a as u64, b as u64);
+    Ok(match op {
+        I64Add => a.wrapping_add(b),
+        I64Sub => a.wrapping_sub(b),
+        I64Mul => a.wrapping_mul(b),
+        I64DivS => {
+            if b == 0 {
+                return Err(Trap::DivisionByZero);
+            }
+            a.checked_div(b).ok_or(Trap::IntegerOverflow)?
+        }
+        I64DivU => ua.checked_div(ub).ok_or(Trap::DivisionByZero)? as i64,
+        I64RemS => {
+            if b == 0 {
+                return Err(Trap::DivisionByZero);
+            }
+            a.wrapping_rem(b)
+        }
+        I64RemU => ua.checked_rem(ub).ok_or(Trap::DivisionByZero)? as i64,
+        I64And => a & b,
+        I64Or => a | b,
+        I64Xor => a ^ b,
+        I64Shl => a.wrapping_shl(ub as u32),
+        I64ShrS => a.wrapping_shr(ub as u32),
+        I64ShrU => ua.wrapping_shr(ub as u32) as i64,
+        I64Rotl => ua.rotate_left((ub % 64) as u32) as i64,
+        I64Rotr => ua.rotate_right((ub % 64) as u32) as i64,
+        other => unreachable!("{:?} is not an i64 binary operator", other),
+    })
+}
+
+/// Strkey (`C...`) of a contract ID
+fn contract_strkey(contract: &Hash) -> String {
+    soroban_env_host::xdr::ScAddress::Contract(contract.clone()).to_string()
+}
+
+/// A frame on the interpreted call stack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// Strkey (`C...`) of the contract the frame belongs to
+    pub contract_id: String,
+    /// Function index in the contract's module index space
+    pub function_index: u32,
+    /// Exported or debug name of the function, if known
+    pub function_name: Option<String>,
+    /// Byte offset of the frame's current instruction in the WASM binary:
+    /// the next to run, or the `call` the frame is making
+    pub offset: usize,
+    /// Source location of that instruction, if the contract has debug info
+    pub location: Option<SourceLocation>,
+}
+
+/// The host call a pause is about, if any
+#[derive(Clone, Copy)]
+enum PauseDetail<'a> {
+    None,
+    StorageAccess(&'a StorageAccess),
+    HostCall(&'a HostCall),
+    Trap(&'a Trap),
+}
+
+/// Execution state visible to a [`PauseHandler`]
+pub struct PauseContext<'a> {
+    machine: &'a Machine<'a>,
+    reason: PauseReason,
+    detail: PauseDetail<'a>,
+}
+
+impl PauseContext<'_> {
+    /// Why execution paused
+    pub fn reason(&self) -> PauseReason {
+        self.reason
+    }
+
+    /// The storage access a watchpoint paused on
+    pub fn storage_access(&self) -> Option<&StorageAccess> {
+        match self.detail {
+            PauseDetail::StorageAccess(access) => Some(access),
+            _ => None,
+        }
+    }
+
+    /// Why the call failed, when paused on an error
+    pub fn trap(&self) -> Option<&Trap> {
+        match self.detail {
+            PauseDetail::Trap(trap) => Some(trap),
+            _ => None,
+        }
+    }
+
+    /// Authorization checks (`require_auth`, `require_auth_for_args`) made
+    /// so far in this invocation, by the strkey of the contract making them
+    pub fn auth_checks(&self) -> Result<Vec<(String, HostCall)>, HostError> {
+        self.machine
+            .interpreter
+            .auth_checks()
+            .into_iter()
+            .map(|check| {
+                let call = host::host_call(self.machine.host, check.function, &check.args)?;
+                Ok((check.contract_id, call))
+            })
+            .collect()
+    }
+
+    /// The host call about to be made, when paused on one
+    pub fn host_call(&self) -> Option<&HostCall> {
+        match self.detail {
+            PauseDetail::HostCall(call) => Some(call),
+            _ => None,
+        }
+    }
+
+    /// Number of interpreted frames below the current one, across contracts
+    pub fn depth(&self) -> usize {
+        self.machine.depth()
+    }
+
+    /// The paused frame
+    pub fn current_frame(&self) -> FrameInfo {
+        self.machine.frame_info(
+            self.machine.frames.last().expect("paused without a frame"),
+            false,
+        )
+    }
+
+    /// Strkey of the contract the paused frame belongs to
+    pub fn contract_id(&self) -> &str {
+        &self.machine.contract_id
+    }
+
+    /// Exported name of the paused function, if it is an export
+    pub fn export_name(&self) -> Option<&str> {
+        let function = self.current_frame().function_index;
+        self.machine
+            .module
+            .exports
+            .iter()
+            .find(|(_, index)| **index == function)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Arguments the paused exported function was called with. Only
+    /// meaningful on entry, before the function reuses its parameters.
+    pub fn arguments(&self) -> Result<Vec<ScVal>, HostError> {
+        let params = self
+            .machine
+            .module
+            .signature(self.current_frame().function_index)
+            .map_or(0, |signature| signature.params.len());
+        self.locals()
+            .iter()
+            .take(params)
+            .map(|local| match local {
+                WasmValue::I64(payload) => {
+                    let val = Val::from_payload(*payload as u64);
+                    ScVal::try_from_val(self.machine.host, &val)
+                }
+                WasmValue::I32(_) => Err(Error::from_type_and_code(
+                    ScErrorType::WasmVm,
+                    ScErrorCode::InvalidInput,
+                )),
+            })
+            .collect::<Result<_, Error>>()
+            .map_err(HostError::from)
+    }
+
+    /// Host running the call, e.g. for reading storage
+    pub fn host(&self) -> &Host {
+        self.machine.host
+    }
+
+    /// Interpreted frames of every contract in the call, innermost first
+    pub fn frames(&self) -> Vec<FrameInfo> {
+        self.machine.frames_info()
+    }
+
+    /// Byte offset of the next instruction to execute
+    pub fn offset(&self) -> usize {
+        self.current_frame().offset
+    }
+
+    /// Parameters and locals of the paused frame
+    pub fn locals(&self) -> &[WasmValue] {
+        &self
+            .machine
+            .frames
+            .last()
+            .expect("paused without a frame")
+            .locals
+    }
+
+    /// Operand stack of the paused frame, bottom first
+    pub fn stack(&self) -> &[WasmValue] {
+        let base = self
+            .machine
+            .frames
+            .last()
+            .expect("paused without a frame")
+            .stack_base;
+        &self.machine.stack[base..]
+    }
+
+    /// WASM binary of the paused contract; frame offsets index into it
+    pub fn wasm(&self) -> &[u8] {
+        &self.machine.module.wasm
+    }
+
+    /// Source locations of the paused contract's instructions, if it was
+    /// built with debug info
+    pub fn source_map(&self) -> Option<&SourceMap> {
+        self.machine.module.source_map.as_ref()
+    }
+
+    /// Module globals
+    pub fn globals(&self) -> &[WasmValue] {
+        &self.machine.globals
+    }
+
+    /// The paused contract's linear memory
+    pub fn memory(&self) -> &[u8] {
+        &self.machine.memory
+    }
+
+    /// Read `len` bytes of the paused contract's linear memory at `address`
+    pub fn read_memory(&self, address: usize, len: usize) -> Option<&[u8]> {
+        self.machine.memory.get(address..address.checked_add(len)?)
+    }
+}
@@ -0,0 +1,843 @@
+//! Built-in WASM interpreter for pause/resume debugging
+//!
+//! The host normally runs a contract call to completion in one go. This
+//! interpreter executes the contract's exported function one instruction at a
+//! time instead, forwarding host imports to the Soroban [`Host`], so execution
+//! can stop mid-function with the locals, operand stack and linear memory
+//! available for inspection.
+//!
+//! Calls into other Wasm contracts through `call` and `try_call` are
+//! interpreted too, each in a contract frame of its own, so breakpoints fire
+//! on nested and cross-contract invocations. Contracts without Wasm, such as
+//! Stellar Asset contracts, run on the host.
+
+mod host;
+mod machine;
+mod module;
+
+pub use machine::{FrameInfo, PauseContext};
+
+use crate::inspector::storage::StorageDurability;
+use crate::utils::scval::scval_to_string;
+use crate::utils::SourceMap;
+
+use machine::{Machine, RunMode};
+use module::Module;
+use serde::{Deserialize, Serialize};
+use soroban_env_host::xdr::{Hash, ScErrorCode, ScErrorType, ScVal};
+use soroban_env_host::{Error, Host, HostError, Val};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+use tracing::warn;
+use wasmparser::ValType;
+
+/// A WASM operand, local or global value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WasmValue {
+    I32(i32),
+    I64(i64),
+}
+
+impl WasmValue {
+    /// Zero value of a type
+    fn zero(ty: ValType) -> Result<Self, Trap> {
+        match ty {
+            ValType::I32 => Ok(WasmValue::I32(0)),
+            ValType::I64 => Ok(WasmValue::I64(0)),
+            other => Err(Trap::Unsupported(format!("{:?} values", other))),
+        }
+    }
+
+    fn as_u32(self) -> Result<u32, String> {
+        match self {
+            WasmValue::I32(v) => Ok(v as u32),
+            WasmValue::I64(_) => Err("Expected an i32 value".to_string()),
+        }
+    }
+}
+
+impl fmt::Display for WasmValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WasmValue::I32(v) => write!(f, "i32:{}", v),
+            WasmValue::I64(v) => write!(f, "i64:{}", v),
+        }
+    }
+}
+
+/// Why the interpreter paused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PauseReason {
+    /// Entered a function with a breakpoint
+    Breakpoint,
+    /// Reached an instruction with a line breakpoint
+    LineBreakpoint,
+    /// Finished a step
+    Step,
+    /// Made a storage access the handler watches
+    Watchpoint,
+    /// About to call a host function the handler stops at
+    HostCall,
+    /// The call failed here; the failure unwinds once the handler resumes
+    Error,
+}
+
+/// Whether a storage access reads an entry or changes it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccessKind {
+    /// `get_contract_data` or `has_contract_data`
+    Read,
+    /// `put_contract_data` or `del_contract_data`
+    Write,
+}
+
+/// A contract storage access made through a host function
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageAccess {
+    pub kind: AccessKind,
+    pub durability: StorageDurability,
+    pub key: ScVal,
+    /// Value before the access; `None` if the entry did not exist
+    pub old_value: Option<ScVal>,
+    /// Value after the access; `None` if the entry does not exist
+    pub new_value: Option<ScVal>,
+}
+
+/// A host function call made by an interpreted contract
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostCall {
+    /// Host function name, e.g. `require_auth`
+    pub function: &'static str,
+    /// Arguments by parameter name. Plain integers, such as linear memory
+    /// positions, are shown as `u32`/`u64`/`i64` values.
+    pub args: Vec<(&'static str, ScVal)>,
+}
+
+impl fmt::Display for HostCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(", self.function)?;
+        for (i, (name, value)) in self.args.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", name, scval_to_string(value))?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// An authorization check (`require_auth` or `require_auth_for_args`)
+/// made during the current invocation, decoded on demand
+#[derive(Debug, Clone)]
+pub(crate) struct AuthCheck {
+    pub contract_id: String,
+    pub function: &'static str,
+    pub args: Vec<i64>,
+}
+
+/// Whether `name` is a Soroban host function, e.g. `require_auth`
+pub fn is_host_function(name: &str) -> bool {
+    host::is_host_function(name)
+}
+
+/// How to continue after a pause
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResumeAction {
+    /// Pause before the next instruction
+    StepInto,
+    /// Pause at the next instruction in this frame or its caller
+    StepOver,
+    /// Pause once the current function returns
+    StepOut,
+    /// Pause at the next instruction on another source line, including the
+    /// first line of a called function. Without debug info, as `StepInto`.
+    StepIntoLine,
+    /// Pause at the next instruction on another source line in this frame or
+    /// its caller. Without debug info, as `StepOver`.
+    StepOverLine,
+    /// Run until the next breakpoint
+    Continue,
+    /// Stop the call; its storage changes are rolled back
+    Abort,
+}
+
+/// Receives control whenever the interpreter pauses
+pub trait PauseHandler {
+    fn on_pause(&mut self, context: &PauseContext) -> ResumeAction;
+
+    /// Decide whether a function breakpoint just reached pauses, e.g. by
+    /// checking its condition. Returning `false` carries on as if there were
+    /// no breakpoint.
+    fn on_breakpoint(&mut self, _context: &PauseContext) -> bool {
+        true
+    }
+
+    /// Decide whether the storage access in
+    /// [`PauseContext::storage_access`], just made, pauses. Only called
+    /// while [`Interpreter::set_watch_storage`] is on.
+    fn on_storage_access(&mut self, _context: &PauseContext) -> bool {
+        true
+    }
+
+    /// Decide whether the host call in [`PauseContext::host_call`], about to
+    /// be made, pauses. Only called while
+    /// [`Interpreter::set_trace_host_calls`] is on.
+    fn on_host_call(&mut self, _context: &PauseContext) -> bool {
+        true
+    }
+
+    /// Show a message about the call, such as a log point's output or a
+    /// breakpoint condition that failed to evaluate. Prints to stdout unless
+    /// overridden.
+    fn on_output(&mut self, message: &str) {
+        println!("{}", message);
+    }
+
+    /// Breakpoints changed during the last pause, such as by a debugger
+    /// client while the call was paused. Asked after every pause; the
+    /// changes take effect when the call resumes.
+    fn take_breakpoint_changes(&mut self) -> Option<BreakpointChanges> {
+        None
+    }
+}
+
+/// Breakpoints a [`PauseHandler`] changed while the call was paused. Each
+/// field that is set replaces the current setting.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BreakpointChanges {
+    /// Function breakpoints, as for [`Interpreter::set_breakpoints`]
+    pub functions: Option<Vec<String>>,
+    /// Line breakpoints, as for [`Interpreter::set_line_breakpoints`]
+    pub lines: Option<Vec<usize>>,
+    /// As for [`Interpreter::set_trace_host_calls`]
+    pub trace_host_calls: Option<bool>,
+    /// As for [`Interpreter::set_break_on_error`]
+    pub break_on_error: Option<bool>,
+}
+
+impl BreakpointChanges {
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+impl<F> PauseHandler for F
+where
+    F: FnMut(&PauseContext) -> ResumeAction,
+{
+    fn on_pause(&mut self, context: &PauseContext) -> ResumeAction {
+        self(context)
+    }
+}
+
+/// Reasons an interpreted call stops without returning
+#[derive(Debug)]
+pub enum Trap {
+    /// A host function failed, including `fail_with_error`
+    Host(HostError),
+    Unreachable,
+    MemoryOutOfBounds,
+    DivisionByZero,
+    IntegerOverflow,
+    UndefinedElement,
+    IndirectCallTypeMismatch,
+    StackOverflow,
+    /// The pause handler aborted the call
+    Aborted,
+    /// The module uses something the interpreter does not support
+    Unsupported(String),
+    /// The module is malformed in a way validation should have caught
+    Invalid(String),
+}
+
+impl fmt::Display for Trap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trap::Host(e) => write!(f, "host function failed: {:?}", e.error),
+            Trap::Unreachable => write!(f, "unreachable executed"),
+            Trap::MemoryOutOfBounds => write!(f, "out of bounds memory access"),
+            Trap::DivisionByZero => write!(f, "integer divide by zero"),
+            Trap::IntegerOverflow => write!(f, "integer overflow"),
+            Trap::UndefinedElement => write!(f, "undefined table element"),
+            Trap::IndirectCallTypeMismatch => write!(f, "indirect call type mismatch"),
+            Trap::StackOverflow => write!(f, "call stack exhausted"),
+            Trap::Aborted => write!(f, "aborted by the debugger"),
+            Trap::Unsupported(what) => write!(f, "unsupported: {}", what),
+            Trap::Invalid(what) => write!(f, "invalid module: {}", what),
+        }
+    }
+}
+
+impl Trap {
+    /// Host error the trap surfaces as to the calling frame: host failures
+    /// keep their error, anything else is a Wasm VM failure as on the host VM.
+    pub fn to_error(&self) -> Error {
+        match self {
+            Trap::Host(e) => e.error,
+            _ => Error::from_type_and_code(ScErrorType::WasmVm, ScErrorCode::InvalidAction),
+        }
+    }
+}
+
+impl From<HostError> for Trap {
+    fn from(e: HostError) -> Self {
+        Trap::Host(e)
+    }
+}
+
+/// Interprets the exported functions of a contract and of the contracts it
+/// calls
+pub struct Interpreter {
+    module: Module,
+    /// Modules of called contracts by contract ID; `None` when the contract
+    /// runs on the host
+    callees: RefCell<HashMap<Hash, Option<Rc<Module>>>>,
+    breakpoints: RefCell<HashSet<String>>,
+    /// Byte offsets of instructions of the primary contract to pause before
+    line_breakpoints: RefCell<HashSet<usize>>,
+    /// Bumped whenever breakpoints change, so running calls pick them up
+    breakpoints_version: Cell<u64>,
+    step_on_entry: bool,
+    watch_storage: bool,
+    trace_host_calls: Cell<bool>,
+    break_on_error: Cell<bool>,
+    /// Set once the failure unwinding through the frames has been reported,
+    /// so each failure pauses once
+    error_reported: Cell<bool>,
+    auth_checks: RefCell<Vec<AuthCheck>>,
+}
+
+impl Interpreter {
+    /// Decode a contract for interpretation
+    pub fn new(wasm: &[u8]) -> Result<Self, String> {
+        Ok(Self {
+            module: Module::parse(wasm)?,
+            callees: RefCell::new(HashMap::new()),
+            breakpoints: RefCell::new(HashSet::new()),
+            line_breakpoints: RefCell::new(HashSet::new()),
+            breakpoints_version: Cell::new(0),
+            step_on_entry: false,
+            watch_storage: false,
+            trace_host_calls: Cell::new(false),
+            break_on_error: Cell::new(false),
+            error_reported: Cell::new(false),
+            auth_checks: RefCell::new(Vec::new()),
+        })
+    }
+
+    /// Pause whenever the named function is entered, in any contract. Both
+    /// exported names and names from the `name` section are recognized.
+    /// `CID::function` limits the breakpoint to the contract with strkey
+    /// `CID`.
+    pub fn add_breakpoint(&mut self, function: &str) {
+        self.breakpoints.get_mut().insert(function.to_string());
+    }
+
+    /// Remove a function breakpoint
+    pub fn remove_breakpoint(&mut self, function: &str) -> bool {
+        self.breakpoints.get_mut().remove(function)
+    }
+
+    /// Replace all function breakpoints
+    pub fn set_breakpoints(&mut self, functions: impl IntoIterator<Item = String>) {
+        *self.breakpoints.get_mut() = functions.into_iter().collect();
+    }
+
+    /// Replace all line breakpoints: pause before the instructions of the
+    /// primary contract at these byte offsets, such as those
+    /// [`SourceMap::line_offsets`] finds for a source line
+    pub fn set_line_breakpoints(&mut self, offsets: impl IntoIterator<Item = usize>) {
+        *self.line_breakpoints.get_mut() = offsets.into_iter().collect();
+    }
+
+    /// Source locations of the primary contract, if it was built with
+    /// debug info
+    pub fn source_map(&self) -> Option<&SourceMap> {
+        self.module.source_map.as_ref()
+    }
+
+    /// Apply breakpoints a pause handler changed while the call was paused
+    pub(crate) fn apply_breakpoint_changes(&self, changes: BreakpointChanges) {
+        if let Some(functions) = changes.functions {
+            *self.breakpoints.borrow_mut() = functions.into_iter().collect();
+        }
+        if let Some(lines) = changes.lines {
+            *self.line_breakpoints.borrow_mut() = lines.into_iter().collect();
+        }
+        if let Some(trace_host_calls) = changes.trace_host_calls {
+            self.trace_host_calls.set(trace_host_calls);
+        }
+        if let Some(break_on_error) = changes.break_on_error {
+            self.break_on_error.set(break_on_error);
+        }
+        self.breakpoints_version
+            .set(self.breakpoints_version.get() + 1);
+    }
+
+    /// Changes whenever breakpoints change during a call
+    pub(crate) fn breakpoints_version(&self) -> u64 {
+        self.breakpoints_version.get()
+    }
+
+    /// Pause before the first instruction of every invocation
+    pub fn set_step_on_entry(&mut self, step_on_entry: bool) {
+        self.step_on_entry = step_on_entry;
+    }
+
+    /// Report every contract storage access to the pause handler, which
+    /// can pause on it. Decoding the accessed entries is charged to the
+    /// call's budget, so this is off by default.
+    pub fn set_watch_storage(&mut self, watch_storage: bool) {
+        self.watch_storage = watch_storage;
+    }
+
+    pub(crate) fn watches_storage(&self) -> bool {
+        self.watch_storage
+    }
+
+    /// Report every host function call, with decoded arguments, to the
+    /// pause handler before it is made. Decoding is charged to the call's
+    /// budget, so this is off by default.
+    pub fn set_trace_host_calls(&mut self, trace_host_calls: bool) {
+        self.trace_host_calls.set(trace_host_calls);
+    }
+
+    pub(crate) fn traces_host_calls(&self) -> bool {
+        self.trace_host_calls.get()
+    }
+
+    /// Pause where a call fails, before the failure unwinds and storage
+    /// changes are rolled back, so the state at the failure point can be
+    /// inspected. Failures a `try_call` recovers from pause too.
+    pub fn set_break_on_error(&mut self, break_on_error: bool) {
+        self.break_on_error.set(break_on_error);
+    }
+
+    pub(crate) fn breaks_on_error(&self) -> bool {
+        self.break_on_error.get()
+    }
+
+    /// Mark the failure being unwound as reported, returning whether it
+    /// already was
+    pub(crate) fn report_error(&self) -> bool {
+        self.error_reported.replace(true)
+    }
+
+    /// Forget the reported failure once a caller recovered from it
+    pub(crate) fn clear_reported_error(&self) {
+        self.error_reported.set(false);
+    }
+
+    pub(crate) fn record_auth_check(&self, check: AuthCheck) {
+        self.auth_checks.borrow_mut().push(check);
+    }
+
+    pub(crate) fn auth_checks(&self) -> Vec<AuthCheck> {
+        self.auth_checks.borrow().clone()
+    }
+
+    /// Name of a function in the primary contract's index space, if known
+    pub fn function_name(&self, function_index: u32) -> Option<&str> {
+        self.module.names.get(&function_index).map(String::as_str)
+    }
+
+    /// Run an exported function of the primary contract to completion,
+    /// pausing at breakpoints.
+    ///
+    /// Must be called with a frame for `contract` on the host's context
+    /// stack, e.g. inside `Host::with_test_contract_frame`.
+    pub fn invoke(
+        &self,
+        host: &Host,
+        contract: &Hash,
+        function: &str,
+        args: &[Val],
+        handler: &mut dyn PauseHandler,
+    ) -> Result<Val, Trap> {
+        self.error_reported.set(false);
+        self.auth_checks.borrow_mut().clear();
+        let mode = if self.step_on_entry {
+            RunMode::Step
+        } else {
+            RunMode::Continue
+        };
+        let (result, _) = self.call_export(
+            host,
+            &self.module,
+            contract,
+            function,
+            args,
+            Vec::new(),
+            mode,
+            handler,
+        );
+        result
+    }
+
+    /// Run an exported function of `module`, the code of `contract`, below
+    /// the `outer` frames of its callers. Returns the run mode the caller
+    /// continues with.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn call_export(
+        &self,
+        host: &Host,
+        module: &Module,
+        contract: &Hash,
+        function: &str,
+        args: &[Val],
+        outer: Vec<FrameInfo>,
+        mode: RunMode,
+        handler: &mut dyn PauseHandler,
+    ) -> (Result<Val, Trap>, RunMode) {
+        let mut machine = match Machine::new(self, module, host, contract, outer, mode.clone()) {
+            Ok(machine) => machine,
+            Err(trap) => return (Err(trap), mode),
+        };
+        let result = run_export(&mut machine, module, function, args, handler);
+        (result, machine.mode())
+    }
+
+    /// Module of a called contract, or `None` if it should run on the host
+    pub(crate) fn callee_module(
+        &self,
+        host: &Host,
+        contract: &Hash,
+    ) -> Result<Option<Rc<Module>>, HostError> {
+        if let Some(module) = self.callees.borrow().get(contract) {
+            return Ok(module.clone());
+        }
+        let module = match host::contract_wasm(host, contract)? {
+            Some(wasm) => match Module::parse(&wasm) {
+                Ok(module) => Some(Rc::new(module)),
+                Err(e) => {
+                    warn!("Running called contract on the host: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+        self.callees
+            .borrow_mut()
+            .insert(contract.clone(), module.clone());
+        Ok(module)
+    }
+
+    /// Offsets of the line breakpoints in `module`; they only apply to the
+    /// primary contract
+    pub(crate) fn line_breakpoints_in(&self, module: &Module) -> HashSet<usize> {
+        if std::ptr::eq(module, &self.module) {
+            self.line_breakpoints.borrow().clone()
+        } else {
+            HashSet::new()
+        }
+    }
+
+    /// Indices of the functions in `module` with a breakpoint, for the
+    /// contract with strkey `contract_id`
+    pub(crate) fn breakpoints_in(&self, module: &Module, contract_id: &str) -> HashSet<u32> {
+        let breakpoints = self.breakpoints.borrow();
+        let functions: HashSet<&str> = breakpoints
+            .iter()
+            .filter_map(|spec| match spec.split_once("::") {
+                Some((contract, function)) => (contract == contract_id).then_some(function),
+                None => Some(spec.as_str()),
+            })
+            .collect();
+        module
+            .exports
+            .iter()
+            .chain(module.names.iter().map(|(index, name)| (name, index)))
+            .filter(|(name, _)| functions.contains(name.as_str()))
+            .map(|(_, index)| *index)
+            .collect()
+    }
+}
+
+/// Check the arguments of an exported function, run it and decode its result
+fn run_export(
+    machine: &mut Machine,
+    module: &Module,
+    function: &str,
+    args: &[Val],
+    handler: &mut dyn PauseHandler,
+) -> Result<Val, Trap> {
+    let index = *module
+        .exports
+        .get(function)
+        .ok_or_else(|| Trap::Invalid(format!("function {} is not exported", function)))?;
+    let signature = module
+        .signature(index)
+        .ok_or_else(|| Trap::Invalid(format!("function {} has no signature", function)))?;
+    if signature.params.len() != args.len() || signature.params.iter().any(|ty| *ty != ValType::I64)
+    {
+        return Err(Trap::Invalid(format!(
+            "function {} expects {} arguments, got {}",
+            function,
+            signature.params.len(),
+            args.len()
+        )));
+    }
+
+    let args: Vec<WasmValue> = args
+        .iter()
+        .map(|val| WasmValue::I64(val.get_payload() as i64))
+        .collect();
+    match machine.run(index, &args, handler)? {
+        Some(WasmValue::I64(payload)) => {
+            let val = Val::from_payload(payload as u64);
+            if val.is_good() {
+                Ok(val)
+            } else {
+                Err(Trap::Invalid(format!(
+                    "returned malformed value {:#x}",
+                    payload
+                )))
+            }
+        }
+        None => Ok(Val::VOID.into()),
+        Some(other) => Err(Trap::Invalid(format!("returned non-Val {}", other))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::U32Val;
+    use walrus::ir::{BinaryOp, UnaryOp};
+    use walrus::{FunctionBuilder, InstrSeqBuilder, LocalId, Module as WalrusModule, ValType};
+
+    /// Leave `value` on the stack as the payload of a `U32Val`
+    fn push_u32_val(body: &mut InstrSeqBuilder, value: LocalId) {
+        body.local_get(value)
+            .unop(UnaryOp::I64ExtendUI32)
+            .i64_const(32)
+            .binop(BinaryOp::I64Shl)
+            .i64_const(4)
+            .binop(BinaryOp::I64Or);
+    }
+
+    /// `sum()` adds 1..=10 in a loop; `outer()` calls an internal `inner()`
+    /// that returns 7.
+    fn test_module() -> Vec<u8> {
+        let mut module = WalrusModule::default();
+
+        let mut builder = FunctionBuilder::new(&mut module.types, &[], &[ValType::I64]);
+        let i = module.locals.add(ValType::I32);
+        let sum = module.locals.add(ValType::I32);
+        let mut body = builder.func_body();
+        body.block(None, |done| {
+            let done_id = done.id();
+            done.loop_(None, |next| {
+                let next_id = next.id();
+                next.local_get(i)
+                    .i32_const(10)
+                    .binop(BinaryOp::I32GeS)
+                    .br_if(done_id)
+                    .local_get(i)
+                    .i32_const(1)
+                    .binop(BinaryOp::I32Add)
+                    .local_tee(i)
+                    .local_get(sum)
+                    .binop(BinaryOp::I32Add)
+                    .local_set(sum)
+                    .br(next_id);
+            });
+        });
+        push_u32_val(&mut body, sum);
+        let sum_fn = builder.finish(vec![], &mut module.funcs);
+        module.exports.add("sum", sum_fn);
+
+        let mut builder = FunctionBuilder::new(&mut module.types, &[], &[ValType::I64]);
+        let seven = module.locals.add(ValType::I32);
+        let mut body = builder.func_body();
+        body.i32_const(7).local_set(seven);
+        push_u32_val(&mut body, seven);
+        let inner = builder.finish(vec![], &mut module.funcs);
+        module.funcs.get_mut(inner).name = Some("inner".to_string());
+
+        let mut builder = FunctionBuilder::new(&mut module.types, &[], &[ValType::I64]);
+        builder.func_body().call(inner);
+        let outer = builder.finish(vec![], &mut module.funcs);
+        module.exports.add("outer", outer);
+
+        let mut builder = FunctionBuilder::new(&mut module.types, &[], &[ValType::I64]);
+        builder.func_body().unreachable();
+        let trap = builder.finish(vec![], &mut module.funcs);
+        module.exports.add("trap", trap);
+
+        module.emit_wasm()
+    }
+
+    fn run(
+        interpreter: &Interpreter,
+        function: &str,
+        handler: &mut dyn PauseHandler,
+    ) -> Result<u32, Trap> {
+        let env = soroban_sdk::Env::default();
+        let contract = Hash([0; 32]);
+        let val = interpreter.invoke(env.host(), &contract, function, &[], handler)?;
+        Ok(U32Val::try_from(val).expect("result is not a u32").into())
+    }
+
+    #[test]
+    fn test_runs_loops_to_completion() {
+        let interpreter = Interpreter::new(&test_module()).unwrap();
+        let mut pauses = 0;
+        let result = run(&interpreter, "sum", &mut |_: &PauseContext| {
+            pauses += 1;
+            ResumeAction::Continue
+        });
+        assert_eq!(result.unwrap(), 55);
+        assert_eq!(pauses, 0);
+    }
+
+    #[test]
+    fn test_step_exposes_locals_and_stack() {
+        let mut interpreter = Interpreter::new(&test_module()).unwrap();
+        interpreter.add_breakpoint("sum");
+
+        let mut snapshots = Vec::new();
+        let result = run(&interpreter, "sum", &mut |ctx: &PauseContext| {
+            snapshots.push((ctx.reason(), ctx.locals().to_vec(), ctx.stack().to_vec()));
+            if snapshots.len() < 20 {
+                ResumeAction::StepInto
+            } else {
+                ResumeAction::Continue
+            }
+        });
+        assert_eq!(result.unwrap(), 55);
+
+        assert_eq!(snapshots[0].0, PauseReason::Breakpoint);
+        assert_eq!(snapshots[0].1, vec![WasmValue::I32(0), WasmValue::I32(0)]);
+        assert!(snapshots[1..]
+            .iter()
+            .all(|(reason, _, _)| *reason == PauseReason::Step));
+        // One pass through the loop body has run by the 20th pause
+        assert!(snapshots
+            .iter()
+            .any(|(_, locals, _)| locals == &[WasmValue::I32(1), WasmValue::I32(1)]));
+        assert!(snapshots
+            .iter()
+            .any(|(_, _, stack)| stack == &[WasmValue::I32(0), WasmValue::I32(10)]));
+    }
+
+    #[test]
+    fn test_step_over_and_out_of_calls() {
+        let mut interpreter = Interpreter::new(&test_module()).unwrap();
+        interpreter.add_breakpoint("outer");
+
+        // Stepping over the call stays in `outer`
+        let mut visited = Vec::new();
+        let result = run(&interpreter, "outer", &mut |ctx: &PauseContext| {
+            visited.push(ctx.current_frame().function_name.unwrap());
+            ResumeAction::StepOver
+        });
+        assert_eq!(result.unwrap(), 7);
+        assert!(visited.iter().all(|name| name == "outer"));
+        assert_eq!(visited.len(), 2);
+
+        // Stepping into the call and out again returns to `outer`
+        let mut frames = Vec::new();
+        run(&interpreter, "outer", &mut |ctx: &PauseContext| {
+            frames.push(ctx.frames().len());
+            if ctx.depth() == 0 && frames.len() == 1 {
+                ResumeAction::StepInto
+            } else if ctx.depth() == 1 {
+                ResumeAction::StepOut
+            } else {
+                ResumeAction::Continue
+            }
+        })
+        .unwrap();
+        assert_eq!(frames, vec![1, 2, 1]);
+    }
+
+    #[test]
+    fn test_line_steps_fall_back_without_debug_info() {
+        let mut interpreter = Interpreter::new(&test_module()).unwrap();
+        interpreter.add_breakpoint("outer");
+
+        let mut visited = Vec::new();
+        let result = run(&interpreter, "outer", &mut |ctx: &PauseContext| {
+            visited.push(ctx.current_frame().function_name.unwrap());
+            ResumeAction::StepOverLine
+        });
+        assert_eq!(result.unwrap(), 7);
+        assert_eq!(visited, vec!["outer", "outer"]);
+
+        let mut depths = Vec::new();
+        run(&interpreter, "outer", &mut |ctx: &PauseContext| {
+            depths.push(ctx.depth());
+            if depths.len() < 3 {
+                ResumeAction::StepIntoLine
+            } else {
+                ResumeAction::Continue
+            }
+        })
+        .unwrap();
+        // `outer` calls `inner` first thing
+        assert_eq!(depths, vec![0, 1, 1]);
+    }
+
+    #[test]
+    fn test_traps_and_abort() {
+        let mut interpreter = Interpreter::new(&test_module()).unwrap();
+        assert!(matches!(
+            run(&interpreter, "trap", &mut |_: &PauseContext| {
+                ResumeAction::Continue
+            }),
+            Err(Trap::Unreachable)
+        ));
+
+        interpreter.set_step_on_entry(true);
+        assert!(matches!(
+            run(&interpreter, "sum", &mut |_: &PauseContext| {
+                ResumeAction::Abort
+            }),
+            Err(Trap::Aborted)
+        ));
+        assert!(matches!(
+            run(&interpreter, "missing", &mut |_: &PauseContext| {
+                ResumeAction::Continue
+            }),
+            Err(Trap::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_qualified_breakpoints_match_their_contract() {
+        // `run` invokes as the contract with the all-zero ID
+        let this = "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABSC4";
+        let other = "CBKMUZNFQIAL775XBB2W2GP5CNHBM5YGH6C3XB7AY6SUVO2IBU3VYK2V";
+        let mut interpreter = Interpreter::new(&test_module()).unwrap();
+
+        let pause_in = |interpreter: &Interpreter| {
+            let mut contracts = Vec::new();
+            run(interpreter, "outer", &mut |ctx: &PauseContext| {
+                contracts.push(ctx.current_frame().contract_id);
+                ResumeAction::Continue
+            })
+            .unwrap();
+            contracts
+        };
+
+        interpreter.add_breakpoint(&format!("{}::inner", other));
+        assert!(pause_in(&interpreter).is_empty());
+
+        interpreter.add_breakpoint(&format!("{}::inner", this));
+        assert_eq!(pause_in(&interpreter), vec![this.to_string()]);
+    }
+
+    #[test]
+    fn test_rejects_unknown_imports() {
+        let mut module = WalrusModule::default();
+        let ty = module.types.add(&[], &[]);
+        module.add_import_func("env", "not_a_host_function", ty);
+        let error = Interpreter::new(&module.emit_wasm()).err().unwrap();
+        assert!(error.contains("env.not_a_host_function"));
+    }
+}
@@ -0,0 +1,629 @@
+//! Decoded form of a contract module, ready for interpretation
+
+use super::host;
+use super::WasmValue;
+use crate::utils::wasm::demangle_name;
+use crate::utils::SourceMap;
+use std::collections::HashMap;
+use wasmparser::{
+    BlockType, CompositeType, ConstExpr, DataKind, ElementItems, ElementKind, ExternalKind, Name,
+    NameSectionReader, Operator, Parser, Payload, TypeRef, ValType,
+};
+
+/// Size of a linear memory page in bytes
+pub(crate) const PAGE_SIZE: usize = 65536;
+
+/// Function signature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Signature {
+    pub params: Vec<ValType>,
+    pub results: Vec<ValType>,
+}
+
+/// A host function imported by the contract
+#[derive(Debug, Clone)]
+pub(crate) struct Import {
+    /// Host function name, e.g. `put_contract_data`
+    pub function: &'static str,
+    pub type_index: u32,
+}
+
+/// A function defined in the module
+#[derive(Debug, Clone)]
+pub(crate) struct Function {
+    pub type_index: u32,
+    /// Declared locals, after the parameters
+    pub locals: Vec<ValType>,
+    pub code: Vec<Instr>,
+}
+
+/// A decoded instruction and its offset in the WASM binary
+#[derive(Debug, Clone)]
+pub(crate) struct Instr {
+    pub op: Op,
+    pub offset: usize,
+}
+
+/// Instructions understood by the interpreter.
+///
+/// Structured control flow is resolved while decoding: blocks carry the index
+/// of their matching `end` (and `else`), so branches need no scanning.
+#[derive(Debug, Clone)]
+pub(crate) enum Op {
+    Unreachable,
+    Nop,
+    Block {
+        results: usize,
+        params: usize,
+        end: usize,
+    },
+    Loop {
+        params: usize,
+    },
+    If {
+        results: usize,
+        params: usize,
+        else_at: Option<usize>,
+        end: usize,
+    },
+    Else {
+        end: usize,
+    },
+    End,
+    Br(u32),
+    BrIf(u32),
+    BrTable(Box<[u32]>, u32),
+    Return,
+    Call(u32),
+    CallIndirect(u32),
+    Drop,
+    Select,
+    LocalGet(u32),
+    LocalSet(u32),
+    LocalTee(u32),
+    GlobalGet(u32),
+    GlobalSet(u32),
+    Load(Load, u64),
+    Store(Store, u64),
+    MemorySize,
+    MemoryGrow,
+    I32Const(i32),
+    I64Const(i64),
+    Numeric(NumOp),
+}
+
+/// Memory loads, by result type and width
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Load {
+    I32,
+    I64,
+    I32_8S,
+    I32_8U,
+    I32_16S,
+    I32_16U,
+    I64_8S,
+    I64_8U,
+    I64_16S,
+    I64_16U,
+    I64_32S,
+    I64_32U,
+}
+
+/// Memory stores, by operand type and width
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Store {
+    I32,
+    I64,
+    I32_8,
+    I32_16,
+    I64_8,
+    I64_16,
+    I64_32,
+}
+
+/// Integer arithmetic, comparison and conversion instructions
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum NumOp {
+    I32Eqz,
+    I32Eq,
+    I32Ne,
+    I32LtS,
+    I32LtU,
+    I32GtS,
+    I32GtU,
+    I32LeS,
+    I32LeU,
+    I32GeS,
+    I32GeU,
+    I64Eqz,
+    I64Eq,
+    I64Ne,
+    I64LtS,
+    I64LtU,
+    I64GtS,
+    I64GtU,
+    I64LeS,
+    I64LeU,
+    I64GeS,
+    I64GeU,
+    I32Clz,
+    I32Ctz,
+    I32Popcnt,
+    I32Add,
+    I32Sub,
+    I32Mul,
+    I32DivS,
+    I32DivU,
+    I32RemS,
+    I32RemU,
+    I32And,
+    I32Or,
+    I32Xor,
+    I32Shl,
+    I32ShrS,
+    I32ShrU,
+    I32Rotl,
+    I32Rotr,
+    I64Clz,
+    I64Ctz,
+    I64Popcnt,
+    I64Add,
+    I64Sub,
+    I64Mul,
+    I64DivS,
+    I64DivU,
+    I64RemS,
+    I64RemU,
+    I64And,
+    I64Or,
+    I64Xor,
+    I64Shl,
+    I64ShrS,
+    I64ShrU,
+    I64Rotl,
+    I64Rotr,
+    I32WrapI64,
+    I64ExtendI32S,
+    I64ExtendI32U,
+    I32Extend8S,
+    I32Extend16S,
+    I64Extend8S,
+    I64Extend16S,
+    I64Extend32S,
+}
+
+/// A contract module decoded for the interpreter
+#[derive(Debug, Default)]
+pub(crate) struct Module {
+    pub types: Vec<Signature>,
+    pub imports: Vec<Import>,
+    pub functions: Vec<Function>,
+    /// Function table used by `call_indirect`
+    pub table: Vec<Option<u32>>,
+    /// Initial and maximum memory size in pages
+    pub memory: Option<(usize, Option<usize>)>,
+    pub globals: Vec<WasmValue>,
+    /// Active data segments as (address, bytes)
+    pub data: Vec<(usize, Vec<u8>)>,
+    /// Exported functions by name
+    pub exports: HashMap<String, u32>,
+    /// Function names from exports, else from the `name` section
+    pub names: HashMap<u32, String>,
+    /// The binary the module was decoded from
+    pub wasm: Vec<u8>,
+    /// Source locations from the module's DWARF line tables, if it has them
+    pub source_map: Option<SourceMap>,
+}
+
+impl Module {
+    /// Decode a contract module.
+    ///
+    /// Only the feature set Soroban accepts is supported: integer MVP
+    /// instructions plus sign extension. Every import must be a Soroban host
+    /// function.
+    pub fn parse(wasm: &[u8]) -> Result<Self, String> {
+        let mut module = Module {
+            wasm: wasm.to_vec(),
+            ..Module::default()
+        };
+        let mut function_types = Vec::new();
+        let mut table_size = 0;
+        let mut elements = Vec::new();
+        let mut debug_names = HashMap::new();
+
+        for payload in Parser::new(0).parse_all(wasm) {
+            match payload.map_err(parse_error)? {
+                Payload::TypeSection(reader) => {
+                    for rec_group in reader {
+                        for ty in rec_group.map_err(parse_error)?.types() {
+                            let CompositeType::Func(func_type) = &ty.composite_type else {
+                                return Err("Unsupported non-function type".to_string());
+                            };
+                            module.types.push(Signature {
+                                params: func_type.params().to_vec(),
+                                results: func_type.results().to_vec(),
+                            });
+                        }
+                    }
+                }
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        let import = import.map_err(parse_error)?;
+                        let TypeRef::Func(type_index) = import.ty else {
+                            return Err(format!(
+                                "Unsupported import {}.{}: only host functions can be imported",
+                                import.module, import.name
+                            ));
+                        };
+                        let function = host::host_function_name(import.module, import.name)
+                            .ok_or_else(|| {
+                                format!("Unknown host function {}.{}", import.module, import.name)
+                            })?;
+                        module.imports.push(Import {
+                            function,
+                            type_index,
+                        });
+                    }
+                }
+                Payload::FunctionSection(reader) => {
+                    for type_index in reader {
+                        function_types.push(type_index.map_err(parse_error)?);
+                    }
+                }
+                Payload::TableSection(reader) => {
+                    for table in reader {
+                        table_size = table.map_err(parse_error)?.ty.initial as usize;
+                    }
+                }
+                Payload::MemorySection(reader) => {
+                    for memory in reader {
+                        let memory = memory.map_err(parse_error)?;
+                        module.memory = Some((
+                            memory.initial as usize,
+                            memory.maximum.map(|max| max as usize),
+                        ));
+                    }
+                }
+                Payload::GlobalSection(reader) => {
+                    for global in reader {
+                        let global = global.map_err(parse_error)?;
+                        let value = eval_const(&global.init_expr, &module.globals)?;
+                        module.globals.push(value);
+                    }
+                }
+                Payload::ExportSection(reader) => {
+                    for export in reader {
+                        let export = export.map_err(parse_error)?;
+                        if export.kind == ExternalKind::Func {
+                            module.exports.insert(export.name.to_string(), export.index);
+                        }
+                    }
+                }
+                Payload::ElementSection(reader) => {
+                    for element in reader {
+                        let element = element.map_err(parse_error)?;
+                        let ElementKind::Active { offset_expr, .. } = element.kind else {
+                            continue;
+                        };
+                        let offset = eval_const(&offset_expr, &module.globals)?.as_u32()?;
+                        let ElementItems::Functions(items) = element.items else {
+                            return Err("Unsupported element segment expressions".to_string());
+                        };
+                        let items = items
+                            .into_iter()
+                            .collect::<Result<Vec<u32>, _>>()
+                            .map_err(parse_error)?;
+                        elements.push((offset as usize, items));
+                    }
+                }
+                Payload::DataSection(reader) => {
+                    for data in reader {
+                        let data = data.map_err(parse_error)?;
+                        if let DataKind::Active { offset_expr, .. } = data.kind {
+                            let offset = eval_const(&offset_expr, &module.globals)?.as_u32()?;
+                            module.data.push((offset as usize, data.data.to_vec()));
+                        }
+                    }
+                }
+                Payload::CodeSectionEntry(body) => {
+                    let type_index = *function_types
+                        .get(module.functions.len())
+                        .ok_or_else(|| "Function body without a declaration".to_string())?;
+
+                    let mut locals = Vec::new();
+                    for local in body.get_locals_reader().map_err(parse_error)? {
+                        let (count, ty) = local.map_err(parse_error)?;
+                        locals.extend(std::iter::repeat_n(ty, count as usize));
+                    }
+
+                    let code = decode_body(&body, &module.types)?;
+                    module.functions.push(Function {
+                        type_index,
+                        locals,
+                        code,
+                    });
+                }
+                Payload::CustomSection(reader) if reader.name() == "name" => {
+                    // Names are only for display; a malformed section is ignored
+                    let _ = read_function_names(
+                        NameSectionReader::new(reader.data(), reader.data_offset()),
+                        &mut debug_names,
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        module.table = vec![None; table_size];
+        for (offset, items) in elements {
+            for (i, function) in items.into_iter().enumerate() {
+                let slot = module
+                    .table
+                    .get_mut(offset + i)
+                    .ok_or_else(|| "Element segment does not fit in table".to_string())?;
+                *slot = Some(function);
+            }
+        }
+        // Exported names take precedence over debug names
+        module.names = debug_names;
+        for (name, index) in &module.exports {
+            module.names.insert(*index, name.clone());
+        }
+        module.source_map = SourceMap::from_wasm(wasm);
+
+        Ok(module)
+    }
+
+    /// Signature of a function in the module's index space
+    pub fn signature(&self, function_index: u32) -> Option<&Signature> {
+        let type_index = match self.imports.get(function_index as usize) {
+            Some(import) => import.type_index,
+            None => self.function(function_index)?.type_index,
+        };
+        self.types.get(type_index as usize)
+    }
+
+    /// A defined function, by its index in the module's index space
+    pub fn function(&self, function_index: u32) -> Option<&Function> {
+        let defined = (function_index as usize).checked_sub(self.imports.len())?;
+        self.functions.get(defined)
+    }
+}
+
+fn parse_error(e: wasmparser::BinaryReaderError) -> String {
+    format!("WASM parsing error: {}", e)
+}
+
+fn read_function_names(
+    reader: NameSectionReader,
+    names: &mut HashMap<u32, String>,
+) -> wasmparser::Result<()> {
+    for name in reader {
+        if let Name::Function(map) = name? {
+            for naming in map {
+                let naming = naming?;
+                names.insert(naming.index, demangle_name(naming.name));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Evaluate a constant initializer expression
+fn eval_const(expr: &ConstExpr, globals: &[WasmValue]) -> Result<WasmValue, String> {
+    let mut reader = expr.get_operators_reader();
+    let mut value = None;
+    while !reader.eof() {
+        match reader.read().map_err(parse_error)? {
+            Operator::I32Const { value: v } => value = Some(WasmValue::I32(v)),
+            Operator::I64Const { value: v } => value = Some(WasmValue::I64(v)),
+            Operator::GlobalGet { global_index } => {
+                value = globals.get(global_index as usize).copied();
+            }
+            Operator::End => break,
+            other => return Err(format!("Unsupported constant expression: {:?}", other)),
+        }
+    }
+    value.ok_or_else(|| "Invalid constant expression".to_string())
+}
+
+/// Block arity as (params, results)
+fn block_arity(ty: BlockType, types: &[Signature]) -> Result<(usize, usize), String> {
+    match ty {
+        BlockType::Empty => Ok((0, 0)),
+        BlockType::Type(_) => Ok((0, 1)),
+        BlockType::FuncType(index) => types
+            .get(index as usize)
+            .map(|sig| (sig.params.len(), sig.results.len()))
+            .ok_or_else(|| format!("Unknown block type {}", index)),
+    }
+}
+
+/// Decode a function body, resolving block, else and end targets.
+fn decode_body(body: &wasmparser::FunctionBody, types: &[Signature]) -> Result<Vec<Instr>, String> {
+    let mut reader = body.get_operators_reader().map_err(parse_error)?;
+    let mut code: Vec<Instr> = Vec::new();
+    // Indices of open block, loop and if instructions
+    let mut open = Vec::new();
+
+    while !reader.eof() {
+        let offset = reader.original_position();
+        let operator = reader.read().map_err(parse_error)?;
+        let index = code.len();
+
+        let op = match operator {
+            Operator::Block { blockty } => {
+                let (params, results) = block_arity(blockty, types)?;
+                open.push(index);
+                Op::Block {
+                    results,
+                    params,
+                    end: 0,
+                }
+            }
+            Operator::Loop { blockty } => {
+                let (params, _) = block_arity(blockty, types)?;
+                open.push(index);
+                Op::Loop { params }
+            }
+            Operator::If { blockty } => {
+                let (params, results) = block_arity(blockty, types)?;
+                open.push(index);
+                Op::If {
+                    results,
+                    params,
+                    else_at: None,
+                    end: 0,
+                }
+            }
+            Operator::Else => {
+                let opener = *open.last().ok_or("else outside of if")?;
+                match &mut code[opener].op {
+                    Op::If { else_at, .. } => *else_at = Some(index),
+                    _ => return Err("else outside of if".to_string()),
+                }
+                Op::Else { end: 0 }
+            }
+            Operator::End => {
+                // The function's own `end` has no opener
+                if let Some(opener) = open.pop() {
+                    match &mut code[opener].op {
+                        Op::Block { end, .. } => *end = index,
+                        Op::If { end, else_at, .. } => {
+                            *end = index;
+                            if let Some(else_at) = *else_at {
+                                code[else_at].op = Op::Else { end: index };
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Op::End
+            }
+            other => decode_operator(other)?,
+        };
+        code.push(Instr { op, offset });
+    }
+
+    Ok(code)
+}
+
+/// Decode an instruction that does not open or close a block
+fn decode_operator(operator: Operator) -> Result<Op, String> {
+    use NumOp::*;
+
+    Ok(match operator {
+        Operator::Unreachable => Op::Unreachable,
+        Operator::Nop => Op::Nop,
+        Operator::Br { relative_depth } => Op::Br(relative_depth),
+        Operator::BrIf { relative_depth } => Op::BrIf(relative_depth),
+        Operator::BrTable { targets } => {
+            let default = targets.default();
+            let targets = targets
+                .targets()
+                .collect::<Result<Vec<u32>, _>>()
+                .map_err(parse_error)?;
+            Op::BrTable(targets.into_boxed_slice(), default)
+        }
+        Operator::Return => Op::Return,
+        Operator::Call { function_index } => Op::Call(function_index),
+        Operator::CallIndirect { type_index, .. } => Op::CallIndirect(type_index),
+        Operator::Drop => Op::Drop,
+        Operator::Select => Op::Select,
+        Operator::LocalGet { local_index } => Op::LocalGet(local_index),
+        Operator::LocalSet { local_index } => Op::LocalSet(local_index),
+        Operator::LocalTee { local_index } => Op::LocalTee(local_index),
+        Operator::GlobalGet { global_index } => Op::GlobalGet(global_index),
+        Operator::GlobalSet { global_index } => Op::GlobalSet(global_index),
+        Operator::I32Load { memarg } => Op::Load(Load::I32, memarg.offset),
+        Operator::I64Load { memarg } => Op::Load(Load::I64, memarg.offset),
+        Operator::I32Load8S { memarg } => Op::Load(Load::I32_8S, memarg.offset),
+        Operator::I32Load8U { memarg } => Op::Load(Load::I32_8U, memarg.offset),
+        Operator::I32Load16S { memarg } => Op::Load(Load::I32_16S, memarg.offset),
+        Operator::I32Load16U { memarg } => Op::Load(Load::I32_16U, memarg.offset),
+        Operator::I64Load8S { memarg } => Op::Load(Load::I64_8S, memarg.offset),
+        Operator::I64Load8U { memarg } => Op::Load(Load::I64_8U, memarg.offset),
+        Operator::I64Load16S { memarg } => Op::Load(Load::I64_16S, memarg.offset),
+        Operator::I64Load16U { memarg } => Op::Load(Load::I64_16U, memarg.offset),
+        Operator::I64Load32S { memarg } => Op::Load(Load::I64_32S, memarg.offset),
+        Operator::I64Load32U { memarg } => Op::Load(Load::I64_32U, memarg.offset),
+        Operator::I32Store { memarg } => Op::Store(Store::I32, memarg.offset),
+        Operator::I64Store { memarg } => Op::Store(Store::I64, memarg.offset),
+        Operator::I32Store8 { memarg } => Op::Store(Store::I32_8, memarg.offset),
+        Operator::I32Store16 { memarg } => Op::Store(Store::I32_16, memarg.offset),
+        Operator::I64Store8 { memarg } => Op::Store(Store::I64_8, memarg.offset),
+        Operator::I64Store16 { memarg } => Op::Store(Store::I64_16, memarg.offset),
+        Operator::I64Store32 { memarg } => Op::Store(Store::I64_32, memarg.offset),
+        Operator::MemorySize { .. } => Op::MemorySize,
+        Operator::MemoryGrow { .. } => Op::MemoryGrow,
+        Operator::I32Const { value } => Op::I32Const(value),
+        Operator::I64Const { value } => Op::I64Const(value),
+        Operator::I32Eqz => Op::Numeric(I32Eqz),
+        Operator::I32Eq => Op::Numeric(I32Eq),
+        Operator::I32Ne => Op::Numeric(I32Ne),
+        Operator::I32LtS => Op::Numeric(I32LtS),
+        Operator::I32LtU => Op::Numeric(I32LtU),
+        Operator::I32GtS => Op::Numeric(I32GtS),
+        Operator::I32GtU => Op::Numeric(I32GtU),
+        Operator::I32LeS => Op::Numeric(I32LeS),
+        Operator::I32LeU => Op::Numeric(I32LeU),
+        Operator::I32GeS => Op::Numeric(I32GeS),
+        Operator::I32GeU => Op::Numeric(I32GeU),
+        Operator::I64Eqz => Op::Numeric(I64Eqz),
+        Operator::I64Eq => Op::Numeric(I64Eq),
+        Operator::I64Ne => Op::Numeric(I64Ne),
+        Operator::I64LtS => Op::Numeric(I64LtS),
+        Operator::I64LtU => Op::Numeric(I64LtU),
+        Operator::I64GtS => Op::Numeric(I64GtS),
+        Operator::I64GtU => Op::Numeric(I64GtU),
+        Operator::I64LeS => Op::Numeric(I64LeS),
+        Operator::I64LeU => Op::Numeric(I64LeU),
+        Operator::I64GeS => Op::Numeric(I64GeS),
+        Operator::I64GeU => Op::Numeric(I64GeU),
+        Operator::I32Clz => Op::Numeric(I32Clz),
+        Operator::I32Ctz => Op::Numeric(I32Ctz),
+        Operator::I32Popcnt => Op::Numeric(I32Popcnt),
+        Operator::I32Add => Op::Numeric(I32Add),
+        Operator::I32Sub => Op::Numeric(I32Sub),
+        Operator::I32Mul => Op::Numeric(I32Mul),
+        Operator::I32DivS => Op::Numeric(I32DivS),
+        Operator::I32DivU => Op::Numeric(I32DivU),
+        Operator::I32RemS => Op::Numeric(I32RemS),
+        Operator::I32RemU => Op::Numeric(I32RemU),
+        Operator::I32And => Op::Numeric(I32And),
+        Operator::I32Or => Op::Numeric(I32Or),
+        Operator::I32Xor => Op::Numeric(I32Xor),
+        Operator::I32Shl => Op::Numeric(I32Shl),
+        Operator::I32ShrS => Op::Numeric(I32ShrS),
+        Operator::I32ShrU => Op::Numeric(I32ShrU),
+        Operator::I32Rotl => Op::Numeric(I32Rotl),
+        Operator::I32Rotr => Op::Numeric(I32Rotr),
+        Operator::I64Clz => Op::Numeric(I64Clz),
+        Operator::I64Ctz => Op::Numeric(I64Ctz),
+        Operator::I64Popcnt => Op::Numeric(I64Popcnt),
+        Operator::I64Add => Op::Numeric(I64Add),
+        Operator::I64Sub => Op::Numeric(I64Sub),
+        Operator::I64Mul => Op::Numeric(I64Mul),
+        Operator::I64DivS => Op::Numeric(I64DivS),
+        Operator::I64DivU => Op::Numeric(I64DivU),
+        Operator::I64RemS => Op::Numeric(I64RemS),
+        Operator::I64RemU => Op::Numeric(I64RemU),
+        Operator::I64And => Op::Numeric(I64And),
+        Operator::I64Or => Op::Numeric(I64Or),
+        Operator::I64Xor => Op::Numeric(I64Xor),
+        Operator::I64Shl => Op::Numeric(I64Shl),
+        Operator::I64ShrS => Op::Numeric(I64ShrS),
+        Operator::I64ShrU => Op::Numeric(I64ShrU),
+        Operator::I64Rotl => Op::Numeric(I64Rotl),
+        Operator::I64Rotr => Op::Numeric(I64Rotr),
+        Operator::I32WrapI64 => Op::Numeric(I32WrapI64),
+        Operator::I64ExtendI32S => Op::Numeric(I64ExtendI32S),
+        Operator::I64ExtendI32U => Op::Numeric(I64ExtendI32U),
+        Operator::I32Extend8S => Op::Numeric(I32Extend8S),
+        Operator::I32Extend16S => Op::Numeric(I32Extend16S),
+        Operator::I64Extend8S => Op::Numeric(I64Extend8S),
+        Operator::I64Extend16S => Op::Numeric(I64Extend16S),
+        Operator::I64Extend32S => Op::Numeric(I64Extend32S),
+        other => return Err(format!("Unsupported instruction: {:?}", other)),
+    })
+}
@@ -0,0 +1,130 @@
+//! Multi-contract workspace manifests
+//!
+//! A manifest lists contracts to register next to the primary contract, so
+//! cross-contract calls made by the primary contract reach real callees:
+//!
+//! ```json
+//! {
+//!   "contracts": [
+//!     {
+//!       "name": "echo",
+//!       "wasm": "echo.wasm",
+//!       "contract_id": "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABSC4",
+//!       "constructor_args": []
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! `wasm` paths are relative to the manifest file. `name`, `contract_id` and
+//! `constructor_args` are optional.
+
+use crate::{DebuggerError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Contracts to register in a debugging session
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContractManifest {
+    pub contracts: Vec<ManifestContract>,
+}
+
+/// A single contract in a manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestContract {
+    /// Name used to attribute calls, events and storage (defaults to the WASM file name)
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Path to the contract WASM
+    pub wasm: PathBuf,
+
+    /// Fixed contract ID (`C...` strkey or 32-byte hex)
+    #[serde(default)]
+    pub contract_id: Option<String>,
+
+    /// Constructor arguments as a JSON array
+    #[serde(default)]
+    pub constructor_args: Option<serde_json::Value>,
+}
+
+impl ManifestContract {
+    /// Name of the contract, falling back to the WASM file stem
+    pub fn name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| {
+            self.wasm
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| self.wasm.to_string_lossy().to_string())
+        })
+    }
+}
+
+impl ContractManifest {
+    /// Load a manifest from a JSON file, resolving WASM paths against its directory
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|e| {
+            DebuggerError::WasmLoadError(format!("Failed to read manifest {:?}: {}", path, e))
+        })?;
+        let mut manifest: ContractManifest = serde_json::from_str(&contents).map_err(|e| {
+            DebuggerError::WasmLoadError(format!("Invalid manifest {:?}: {}", path, e))
+        })?;
+
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+        for contract in &mut manifest.contracts {
+            if contract.wasm.is_relative() {
+                contract.wasm = base_dir.join(&contract.wasm);
+            }
+            if let Some(args) = &contract.constructor_args {
+                if !args.is_array() {
+                    return Err(DebuggerError::InvalidArguments(format!(
+                        "Constructor arguments of contract {} must be a JSON array",
+                        contract.name()
+                    ))
+                    .into());
+                }
+            }
+        }
+
+        info!(
+            "Loaded contract manifest with {} contracts",
+            manifest.contracts.len()
+        );
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_manifest_resolves_relative_paths() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(br#"{"contracts": [{"wasm": "echo.wasm"}]}"#)
+            .unwrap();
+
+        let manifest = ContractManifest::from_file(file.path()).unwrap();
+        let contract = &manifest.contracts[0];
+        assert_eq!(
+            contract.wasm,
+            file.path().parent().unwrap().join("echo.wasm")
+        );
+        assert_eq!(contract.name(), "echo");
+        assert!(contract.contract_id.is_none());
+    }
+
+    #[test]
+    fn test_manifest_rejects_non_array_constructor_args() {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(br#"{"contracts": [{"wasm": "a.wasm", "constructor_args": 1}]}"#)
+            .unwrap();
+
+        assert!(ContractManifest::from_file(file.path()).is_err());
+    }
+}
@@ -2,8 +2,12 @@ pub mod env;
 pub mod executor;
 pub mod instruction;
 pub mod instrumentation;
+pub mod interpreter;
+pub mod manifest;
 
 pub use env::DebugEnv;
 pub use executor::ContractExecutor;
 pub use instruction::{Instruction, InstructionParser};
-pub use instrumentation::{InstructionHook, Instrumenter};
+pub use instrumentation::{InstructionHook, Instrumenter, Probe, ProbeKind, ProbeMode};
+pub use interpreter::{Interpreter, PauseContext, PauseHandler, ResumeAction};
+pub use manifest::ContractManifest;
@@ -1,21 +1,63 @@
 //! Snapshot loading and host injection
 //!
 //! This module handles loading network snapshots from files and applying them
-//! to the Soroban debugger environment.
-
-use super::state::{AccountState, ContractState, NetworkSnapshot, SimulatorError};
+//! to the Soroban debugger environment. Besides the debugger's own
+//! [`NetworkSnapshot`] schema, it reads ledger snapshots written by
+//! `stellar snapshot create` and the `test_snapshots/**/*.json` files written
+//! by soroban-sdk tests.
+
+use super::state::{
+    AccountState, ContractState, LedgerEntryState, LedgerMetadata, NetworkSnapshot, SimulatorError,
+};
+use crate::inspector::storage::StorageDurability;
+use crate::runtime::executor::{decode_storage_json, put_storage_entry};
 use crate::Result;
+use soroban_env_host::xdr::{
+    AccountEntry, AccountEntryExt, Hash, LedgerEntry, LedgerEntryData, LedgerEntryExt, LedgerKey,
+    LedgerKeyAccount, ScAddress, ScVal, SequenceNumber, String32, Thresholds,
+};
+use soroban_ledger_snapshot::LedgerSnapshot;
+use soroban_sdk::testutils::Ledger as _;
+use soroban_sdk::{Address, Bytes, Env, TryFromVal};
 use std::fs;
-use std::path::Path;
-use tracing::{debug, info};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::str::FromStr;
+use tracing::{debug, info, warn};
+
+/// Passphrases of well-known networks, keyed by network ID
+const KNOWN_NETWORKS: &[(&str, &str)] = &[
+    (
+        "7ac33997544e3175d266bd022439b22cdb16508c01163f26e5cb2a3e1045a979",
+        "Public Global Stellar Network ; September 2015",
+    ),
+    (
+        "cee0302d59844d32bdca915c8203dd44b33fbb7edc19051ea37abedf28ecd472",
+        "Test SDF Network ; September 2015",
+    ),
+    (
+        "a3a1c6a78286713e29be0e9785670fa838d13917cd8eaeb4a3579ff1debc7fd5",
+        "Test SDF Future Network ; October 2022",
+    ),
+    (
+        "baefd734b8d3e48472cff83912375fedbc7573701912fe308af730180f97d74a",
+        "Standalone Network ; February 2017",
+    ),
+];
 
 /// Loads and applies network snapshots to a debug environment
+#[derive(Debug, Clone)]
 pub struct SnapshotLoader {
     snapshot: NetworkSnapshot,
+    /// Directory `wasm_ref` paths are resolved against (the snapshot file's directory)
+    base_dir: Option<PathBuf>,
 }
 
 impl SnapshotLoader {
     /// Load a snapshot from a JSON file
+    ///
+    /// The format is detected from the content: a debugger `NetworkSnapshot`,
+    /// a stellar-cli ledger snapshot, or a soroban-sdk test snapshot.
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let path = path.as_ref();
         info!("Loading network snapshot from: {:?}", path);
@@ -24,26 +66,87 @@ impl SnapshotLoader {
         let contents = fs::read_to_string(path).map_err(SimulatorError::IoError)?;
 
         // Parse JSON
-        let snapshot: NetworkSnapshot =
+        let mut value: serde_json::Value =
             serde_json::from_str(&contents).map_err(SimulatorError::JsonError)?;
+        let snapshot = if value
+            .get("ledger")
+            .is_some_and(|ledger| ledger.get("ledger_entries").is_some())
+        {
+            info!("Detected soroban-sdk test snapshot");
+            let ledger = serde_json::from_value(value["ledger"].take())
+                .map_err(SimulatorError::JsonError)?;
+            Self::convert_ledger_snapshot(ledger)
+        } else if value.get("ledger_entries").is_some() {
+            info!("Detected stellar-cli ledger snapshot");
+            let ledger = serde_json::from_value(value).map_err(SimulatorError::JsonError)?;
+            Self::convert_ledger_snapshot(ledger)
+        } else {
+            serde_json::from_value::<NetworkSnapshot>(value).map_err(SimulatorError::JsonError)?
+        };
 
         // Validate the snapshot
         snapshot.validate()?;
 
         info!(
-            "Snapshot loaded: {} accounts, {} contracts, ledger seq={}",
+            "Snapshot loaded: {} accounts, {} contracts, {} ledger entries, ledger seq={}",
             snapshot.accounts.len(),
             snapshot.contracts.len(),
+            snapshot.ledger_entries.len(),
             snapshot.ledger.sequence
         );
 
-        Ok(Self { snapshot })
+        Ok(Self {
+            snapshot,
+            base_dir: path.parent().map(Path::to_path_buf),
+        })
     }
 
     /// Create a snapshot from a NetworkSnapshot struct directly
+    ///
+    /// Relative `wasm_ref` paths are resolved against the working directory.
     pub fn from_snapshot(snapshot: NetworkSnapshot) -> Result<Self> {
         snapshot.validate()?;
-        Ok(Self { snapshot })
+        Ok(Self {
+            snapshot,
+            base_dir: None,
+        })
+    }
+
+    /// Create a snapshot from a stellar-cli / soroban-sdk ledger snapshot
+    pub fn from_ledger_snapshot(ledger: LedgerSnapshot) -> Result<Self> {
+        Self::from_snapshot(Self::convert_ledger_snapshot(ledger))
+    }
+
+    /// Convert a ledger snapshot into a `NetworkSnapshot` holding its raw entries
+    fn convert_ledger_snapshot(ledger: LedgerSnapshot) -> NetworkSnapshot {
+        let network_id = hex_encode(&ledger.network_id);
+        let network_passphrase = KNOWN_NETWORKS
+            .iter()
+            .find(|(id, _)| *id == network_id)
+            .map(|(_, passphrase)| passphrase.to_string())
+            .unwrap_or_else(|| format!("Unknown network ({})", network_id));
+
+        NetworkSnapshot {
+            ledger: LedgerMetadata {
+                // soroban-sdk tests run at ledger 0, which is not a valid
+                // network ledger; start one ledger later instead
+                sequence: ledger.sequence_number.max(1),
+                timestamp: ledger.timestamp,
+                network_passphrase,
+                network_id: Some(network_id),
+            },
+            accounts: Vec::new(),
+            contracts: Vec::new(),
+            ledger_entries: ledger
+                .ledger_entries
+                .into_iter()
+                .map(|(key, (entry, live_until_ledger))| LedgerEntryState {
+                    key: *key,
+                    entry: *entry,
+                    live_until_ledger,
+                })
+                .collect(),
+        }
     }
 
     /// Get reference to the underlying snapshot
@@ -58,18 +161,62 @@ impl SnapshotLoader {
 
     /// Apply snapshot state to the debugger environment
     ///
-    /// This prepares the environment with the accounts and contracts
-    /// defined in the snapshot. The exact behavior may be limited by
-    /// the Soroban SDK's test environment capabilities.
-    pub fn apply_to_environment(&self) -> Result<LoadedSnapshot> {
+    /// Sets the ledger sequence, timestamp and network passphrase on `env`,
+    /// registers every contract that has a `wasm_ref` at its contract ID with
+    /// its instance storage, writes every account entry into the ledger and
+    /// finally writes the raw ledger entries with their TTLs. Apply the
+    /// snapshot before registering any other contract so that its entries are
+    /// live at the snapshot's ledger.
+    pub fn apply_to_environment(&self, env: &Env) -> Result<LoadedSnapshot> {
         info!("Applying snapshot to environment");
 
+        let ledger = &self.snapshot.ledger;
+        let network_id = match &ledger.network_id {
+            Some(network_id) => hex_decode_32(network_id).ok_or_else(|| {
+                SimulatorError::ValidationError(format!("Invalid network ID: {}", network_id))
+            })?,
+            None => env
+                .crypto()
+                .sha256(&Bytes::from_slice(
+                    env,
+                    ledger.network_passphrase.as_bytes(),
+                ))
+                .to_array(),
+        };
+        env.ledger().with_mut(|info| {
+            info.sequence_number = ledger.sequence;
+            info.timestamp = ledger.timestamp;
+            info.network_id = network_id;
+        });
+
+        for contract in &self.snapshot.contracts {
+            self.apply_contract(env, contract)?;
+        }
+
+        for account in &self.snapshot.accounts {
+            Self::apply_account(env, account)?;
+        }
+
+        let mut account_count = self.snapshot.accounts.len();
+        let mut contract_count = self.snapshot.contracts.len();
+        for state in &self.snapshot.ledger_entries {
+            match &state.key {
+                LedgerKey::Account(_) => account_count += 1,
+                LedgerKey::ContractData(data) if data.key == ScVal::LedgerKeyContractInstance => {
+                    contract_count += 1
+                }
+                _ => {}
+            }
+            Self::apply_ledger_entry(env, state)?;
+        }
+
         let snapshot_info = SnapshotInfo {
-            ledger_sequence: self.snapshot.ledger.sequence,
-            ledger_timestamp: self.snapshot.ledger.timestamp,
-            network_passphrase: self.snapshot.ledger.network_passphrase.clone(),
-            account_count: self.snapshot.accounts.len(),
-            contract_count: self.snapshot.contracts.len(),
+            ledger_sequence: ledger.sequence,
+            ledger_timestamp: ledger.timestamp,
+            network_passphrase: ledger.network_passphrase.clone(),
+            account_count,
+            contract_count,
+            ledger_entry_count: self.snapshot.ledger_entries.len(),
         };
 
         debug!("Snapshot info: {:?}", snapshot_info);
@@ -80,6 +227,134 @@ impl SnapshotLoader {
         })
     }
 
+    /// Register a snapshot contract and write its instance storage
+    fn apply_contract(&self, env: &Env, contract: &ContractState) -> Result<()> {
+        let Some(wasm_ref) = &contract.wasm_ref else {
+            warn!(
+                "Contract {} has no wasm_ref; it will not be registered",
+                contract.contract_id
+            );
+            return Ok(());
+        };
+
+        let wasm_path = match &self.base_dir {
+            Some(dir) => dir.join(wasm_ref),
+            None => PathBuf::from(wasm_ref),
+        };
+        let wasm = fs::read(&wasm_path).map_err(|e| {
+            SimulatorError::ValidationError(format!(
+                "Failed to read WASM {:?} for contract {}: {}",
+                wasm_path, contract.contract_id, e
+            ))
+        })?;
+
+        let wasm_hash = hex_encode(
+            &env.crypto()
+                .sha256(&Bytes::from_slice(env, &wasm))
+                .to_array(),
+        );
+        let expected_hash = contract.wasm_hash.trim_start_matches("0x").to_lowercase();
+        if wasm_hash != expected_hash {
+            warn!(
+                "WASM hash mismatch for contract {}: snapshot has {}, {:?} hashes to {}",
+                contract.contract_id, contract.wasm_hash, wasm_path, wasm_hash
+            );
+        }
+
+        let address = parse_contract_address(env, &contract.contract_id)?;
+        env.register_at(&address, wasm.as_slice(), ());
+
+        for (raw_key, raw_value) in &contract.storage {
+            let key = decode_storage_json(env, &serde_json::Value::String(raw_key.clone()))?;
+            let val = decode_storage_json(env, raw_value)?;
+            put_storage_entry(env, &address, StorageDurability::Instance, key, val);
+        }
+
+        info!(
+            "Registered snapshot contract {} ({} storage entries)",
+            contract.contract_id,
+            contract.storage.len()
+        );
+        Ok(())
+    }
+
+    /// Write a raw ledger entry into the host's storage
+    fn apply_ledger_entry(env: &Env, state: &LedgerEntryState) -> Result<()> {
+        match &state.key {
+            LedgerKey::Account(_)
+            | LedgerKey::Trustline(_)
+            | LedgerKey::ContractData(_)
+            | LedgerKey::ContractCode(_) => {
+                env.host().add_ledger_entry(
+                    &Rc::new(state.key.clone()),
+                    &Rc::new(state.entry.clone()),
+                    state.live_until_ledger,
+                )?;
+            }
+            other => {
+                // The Soroban host only stores these four entry types
+                warn!("Skipping unsupported ledger entry: {:?}", other);
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a snapshot account into the ledger
+    fn apply_account(env: &Env, account: &AccountState) -> Result<()> {
+        let account_id = match ScAddress::from_str(&account.address) {
+            Ok(ScAddress::Account(account_id)) => account_id,
+            _ => {
+                return Err(SimulatorError::InvalidAddress(format!(
+                    "Not a Stellar account address: {}",
+                    account.address
+                ))
+                .into())
+            }
+        };
+        let balance = account.balance.parse::<i64>().map_err(|_| {
+            SimulatorError::InvalidBalance(format!(
+                "Balance does not fit in an account entry: {}",
+                account.balance
+            ))
+        })?;
+        let sequence = i64::try_from(account.sequence).map_err(|_| {
+            SimulatorError::ValidationError(format!(
+                "Sequence number too large for account {}: {}",
+                account.address, account.sequence
+            ))
+        })?;
+
+        if account.data.as_ref().is_some_and(|data| !data.is_empty()) {
+            // The Soroban host only stores account, trustline and contract
+            // entries, so classic data entries cannot be recreated.
+            warn!(
+                "Account {} has data entries; these are not loaded into the host",
+                account.address
+            );
+        }
+
+        let entry = AccountEntry {
+            account_id: account_id.clone(),
+            balance,
+            seq_num: SequenceNumber(sequence),
+            num_sub_entries: 0,
+            inflation_dest: None,
+            flags: account.flags.unwrap_or(0),
+            home_domain: String32::default(),
+            thresholds: Thresholds([1, 0, 0, 0]),
+            signers: Default::default(),
+            ext: AccountEntryExt::V0,
+        };
+        add_ledger_entry(
+            env,
+            LedgerKey::Account(LedgerKeyAccount { account_id }),
+            LedgerEntryData::Account(entry),
+        )?;
+
+        debug!("Created snapshot account {}", account.address);
+        Ok(())
+    }
+
     /// Validate the snapshot without applying it
     pub fn validate(&self) -> Result<()> {
         info!("Validating snapshot");
@@ -135,6 +410,49 @@ impl SnapshotLoader {
     }
 }
 
+/// Resolve a snapshot contract ID (strkey or 32-byte hex) to an address
+pub(crate) fn parse_contract_address(env: &Env, contract_id: &str) -> Result<Address> {
+    let sc_address = if let Some(bytes) = hex_decode_32(contract_id) {
+        ScAddress::Contract(Hash(bytes))
+    } else {
+        match ScAddress::from_str(contract_id) {
+            Ok(address @ ScAddress::Contract(_)) => address,
+            _ => return Err(SimulatorError::InvalidContractId(contract_id.to_string()).into()),
+        }
+    };
+    Address::try_from_val(env, &sc_address)
+        .map_err(|_| SimulatorError::InvalidContractId(contract_id.to_string()).into())
+}
+
+/// Write a classic ledger entry into the host's storage
+fn add_ledger_entry(env: &Env, key: LedgerKey, data: LedgerEntryData) -> Result<()> {
+    let entry = LedgerEntry {
+        last_modified_ledger_seq: env.ledger().sequence(),
+        data,
+        ext: LedgerEntryExt::V0,
+    };
+    env.host()
+        .add_ledger_entry(&Rc::new(key), &Rc::new(entry), None)?;
+    Ok(())
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Decode 32 bytes of hex, with or without a `0x` prefix
+fn hex_decode_32(hex: &str) -> Option<[u8; 32]> {
+    let hex = hex.trim_start_matches("0x");
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+    let mut bytes = [0u8; 32];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
 /// Information about a successfully loaded snapshot
 #[derive(Debug, Clone)]
 pub struct LoadedSnapshot {
@@ -163,6 +481,11 @@ impl LoadedSnapshot {
         self.info.contract_count
     }
 
+    /// Get raw ledger entry count from loaded snapshot
+    pub fn ledger_entry_count(&self) -> usize {
+        self.info.ledger_entry_count
+    }
+
     /// Get underlying snapshot
     pub fn snapshot(&self) -> &NetworkSnapshot {
         &self.snapshot
@@ -181,12 +504,14 @@ impl LoadedSnapshot {
             Timestamp: {}\n  \
             Network: {}\n  \
             Accounts: {}\n  \
-            Contracts: {}",
+            Contracts: {}\n  \
+            Ledger Entries: {}",
             self.info.ledger_sequence,
             self.info.ledger_timestamp,
             self.info.network_passphrase,
             self.info.account_count,
-            self.info.contract_count
+            self.info.contract_count,
+            self.info.ledger_entry_count
         )
     }
 }
@@ -199,14 +524,51 @@ pub struct SnapshotInfo {
     network_passphrase: String,
     account_count: usize,
     contract_count: usize,
+    ledger_entry_count: usize,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use soroban_env_host::budget::AsBudget;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
+    const ACCOUNT: &str = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+    /// An environment on testnet at ledger 100 holding the `ACCOUNT` entry
+    fn env_with_account() -> Env {
+        let mut snapshot = NetworkSnapshot::new(100, KNOWN_NETWORKS[1].1, 1234567890);
+        snapshot
+            .add_account(AccountState::new(ACCOUNT, "1000000", 7))
+            .unwrap();
+        let env = Env::default();
+        SnapshotLoader::from_snapshot(snapshot)
+            .unwrap()
+            .apply_to_environment(&env)
+            .unwrap();
+        env
+    }
+
+    fn account_entry(env: &Env) -> Option<LedgerEntry> {
+        let account_id = match ScAddress::from_str(ACCOUNT).unwrap() {
+            ScAddress::Account(account_id) => account_id,
+            _ => unreachable!(),
+        };
+        let key = Rc::new(LedgerKey::Account(LedgerKeyAccount { account_id }));
+        env.host()
+            .with_mut_storage(|storage| {
+                Ok(storage
+                    .map
+                    .get::<Rc<LedgerKey>>(&key, env.host().as_budget())?
+                    .cloned())
+            })
+            .unwrap()
+            .flatten()
+            .map(|(entry, _)| (*entry).clone())
+    }
+
+    const CONTRACT: &str = "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAD2KM";
+
     #[test]
     fn test_load_from_file() {
         let snapshot = NetworkSnapshot::new(100, "Test Network", 1234567890);
@@ -230,10 +592,127 @@ mod tests {
     fn test_apply_to_environment() {
         let snapshot = NetworkSnapshot::new(100, "Test Network", 1234567890);
         let loader = SnapshotLoader::from_snapshot(snapshot).unwrap();
-        let loaded = loader.apply_to_environment().unwrap();
+        let env = Env::default();
+        let loaded = loader.apply_to_environment(&env).unwrap();
 
         assert_eq!(loaded.ledger_sequence(), 100);
         assert_eq!(loaded.network_passphrase(), "Test Network");
+        assert_eq!(env.ledger().sequence(), 100);
+        assert_eq!(env.ledger().timestamp(), 1234567890);
+        assert_eq!(
+            env.ledger().get().network_id,
+            env.crypto()
+                .sha256(&Bytes::from_slice(&env, b"Test Network"))
+                .to_array()
+        );
+    }
+
+    #[test]
+    fn test_apply_creates_account_entries() {
+        let mut snapshot = NetworkSnapshot::new(100, "Test Network", 1234567890);
+        snapshot
+            .add_account(AccountState::new(ACCOUNT, "1000000", 7))
+            .unwrap();
+
+        let loader = SnapshotLoader::from_snapshot(snapshot).unwrap();
+        let env = Env::default();
+        loader.apply_to_environment(&env).unwrap();
+
+        let entry = account_entry(&env).expect("account entry should exist");
+
+        match &entry.data {
+            LedgerEntryData::Account(account) => {
+                assert_eq!(account.balance, 1000000);
+                assert_eq!(account.seq_num, SequenceNumber(7));
+            }
+            other => panic!("unexpected entry: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_load_stellar_cli_ledger_snapshot() {
+        let env = env_with_account();
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(
+            serde_json::to_string(&env.to_ledger_snapshot())
+                .unwrap()
+                .as_bytes(),
+        )
+        .unwrap();
+
+        let loader = SnapshotLoader::from_file(file.path()).unwrap();
+        let ledger = &loader.snapshot().ledger;
+        assert_eq!(ledger.sequence, 100);
+        assert_eq!(
+            ledger.network_passphrase,
+            "Test SDF Network ; September 2015"
+        );
+        assert!(!loader.snapshot().ledger_entries.is_empty());
+
+        let restored = Env::default();
+        let loaded = loader.apply_to_environment(&restored).unwrap();
+        assert_eq!(loaded.account_count(), 1);
+        assert_eq!(
+            restored.ledger().get().network_id,
+            env.ledger().get().network_id
+        );
+        assert_eq!(account_entry(&restored), account_entry(&env));
+    }
+
+    #[test]
+    fn test_load_soroban_sdk_test_snapshot() {
+        let env = env_with_account();
+        let file = NamedTempFile::new().unwrap();
+        env.to_snapshot().write_file(file.path()).unwrap();
+
+        let loader = SnapshotLoader::from_file(file.path()).unwrap();
+        assert_eq!(loader.snapshot().ledger.sequence, 100);
+        assert_eq!(
+            loader.snapshot().ledger.network_id.as_deref(),
+            Some(KNOWN_NETWORKS[1].0)
+        );
+
+        let restored = Env::default();
+        let loaded = loader.apply_to_environment(&restored).unwrap();
+        assert_eq!(
+            loaded.ledger_entry_count(),
+            loader.snapshot().ledger_entries.len()
+        );
+        assert_eq!(account_entry(&restored), account_entry(&env));
+    }
+
+    #[test]
+    fn test_ledger_snapshot_at_sequence_zero_is_accepted() {
+        let ledger = Env::default().to_ledger_snapshot();
+        let loader = SnapshotLoader::from_ledger_snapshot(ledger).unwrap();
+        assert_eq!(loader.snapshot().ledger.sequence, 1);
+        assert!(loader
+            .snapshot()
+            .ledger
+            .network_passphrase
+            .starts_with("Unknown network"));
+    }
+
+    #[test]
+    fn test_apply_rejects_invalid_account_address() {
+        let mut snapshot = NetworkSnapshot::new(100, "Test Network", 1234567890);
+        snapshot
+            .add_account(AccountState::new("GABCD123", "1000000", 1))
+            .unwrap();
+
+        let loader = SnapshotLoader::from_snapshot(snapshot).unwrap();
+        assert!(loader.apply_to_environment(&Env::default()).is_err());
+    }
+
+    #[test]
+    fn test_apply_rejects_missing_wasm() {
+        let mut snapshot = NetworkSnapshot::new(100, "Test Network", 1234567890);
+        let mut contract = ContractState::new(CONTRACT, "aabbccdd");
+        contract.set_wasm_ref("does/not/exist.wasm");
+        snapshot.add_contract(contract).unwrap();
+
+        let loader = SnapshotLoader::from_snapshot(snapshot).unwrap();
+        assert!(loader.apply_to_environment(&Env::default()).is_err());
     }
 
     #[test]
@@ -2,7 +2,8 @@
 //!
 //! This module provides comprehensive network state simulation for Soroban debugging.
 //! It allows users to:
-//! - Load network snapshots from JSON files
+//! - Load network snapshots from JSON files, including stellar-cli ledger
+//!   snapshots and soroban-sdk test snapshots
 //! - Configure mock ledger state (accounts, contracts, balances)
 //! - Pre-deploy contract instances with populated storage
 //! - Save and restore ledger state for iterative debugging
@@ -13,4 +14,6 @@ pub mod state;
 
 pub use loader::{LoadedSnapshot, SnapshotLoader};
 pub use snapshot::{AccountDiff, ContractDiff, SnapshotDiff, SnapshotManager};
-pub use state::{AccountState, ContractState, LedgerMetadata, NetworkSnapshot, SimulatorError};
+pub use state::{
+    AccountState, ContractState, LedgerEntryState, LedgerMetadata, NetworkSnapshot, SimulatorError,
+};
@@ -5,6 +5,7 @@
 //! including ledger metadata, accounts, and deployed contracts.
 
 use serde::{Deserialize, Serialize};
+use soroban_env_host::xdr::{LedgerEntry, LedgerKey};
 use std::collections::BTreeMap;
 use thiserror::Error;
 
@@ -54,6 +55,10 @@ pub struct NetworkSnapshot {
 
     /// Deployed contracts
     pub contracts: Vec<ContractState>,
+
+    /// Raw ledger entries, e.g. imported from a stellar-cli or soroban-sdk snapshot
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ledger_entries: Vec<LedgerEntryState>,
 }
 
 impl NetworkSnapshot {
@@ -64,9 +69,11 @@ impl NetworkSnapshot {
                 sequence,
                 timestamp,
                 network_passphrase: network_passphrase.into(),
+                network_id: None,
             },
             accounts: Vec::new(),
             contracts: Vec::new(),
+            ledger_entries: Vec::new(),
         }
     }
 
@@ -171,6 +178,11 @@ pub struct LedgerMetadata {
 
     /// Network passphrase (e.g., "Test SDF Network ; September 2015")
     pub network_passphrase: String,
+
+    /// Network ID (hex SHA-256 of the passphrase); takes precedence over the
+    /// passphrase when the passphrase is not known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_id: Option<String>,
 }
 
 impl LedgerMetadata {
@@ -189,10 +201,34 @@ impl LedgerMetadata {
             .into());
         }
 
+        if let Some(network_id) = &self.network_id {
+            if network_id.len() != 64 || !network_id.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(SimulatorError::ValidationError(format!(
+                    "Network ID must be 32 bytes of hex: {}",
+                    network_id
+                ))
+                .into());
+            }
+        }
+
         Ok(())
     }
 }
 
+/// Raw ledger entry with its TTL, as stored in stellar-cli and soroban-sdk snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntryState {
+    /// Ledger key
+    pub key: LedgerKey,
+
+    /// Ledger entry
+    pub entry: LedgerEntry,
+
+    /// Ledger the entry is live until (contract data and code only)
+    #[serde(default)]
+    pub live_until_ledger: Option<u32>,
+}
+
 /// Account state snapshot
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountState {
@@ -1,5 +1,6 @@
 use crate::debugger::instruction_pointer::StepMode;
 use crate::runtime::instruction::Instruction;
+use crate::utils::{SourceLocation, SourceMap};
 use crossterm::style::Stylize;
 use std::sync::atomic::{AtomicBool, Ordering};
 
@@ -45,12 +46,17 @@ impl Formatter {
         )
     }
 
-    /// Format a single instruction for display.
-    pub fn format_instruction(instruction: &Instruction, is_current: bool) -> String {
+    /// Format a single instruction for display, followed by its source
+    /// location when known.
+    pub fn format_instruction(
+        instruction: &Instruction,
+        is_current: bool,
+        location: Option<&SourceLocation>,
+    ) -> String {
         let prefix = if is_current { "►" } else { " " };
         let operands = instruction.operands();
 
-        if operands.is_empty() {
+        let mut line = if operands.is_empty() {
             format!(
                 "{} {:08x}: {}",
                 prefix,
@@ -65,26 +71,44 @@ impl Formatter {
                 instruction.name(),
                 operands
             )
+        };
+        if let Some(location) = location {
+            line.push_str(&format!("  ; {}", location));
         }
+        line
     }
 
-    /// Format instruction context with surrounding instructions.
+    /// Format instruction context with surrounding instructions. With a
+    /// source map, each source line the instructions come from is shown
+    /// above them.
     pub fn format_instruction_context(
         context: &[(usize, Instruction, bool)],
         _context_size: usize,
+        source_map: Option<&SourceMap>,
     ) -> String {
         if context.is_empty() {
             return "No instructions available".to_string();
         }
 
         let mut lines = vec!["Instruction Context".to_string()];
-        lines.extend(context.iter().map(|(idx, instruction, is_current)| {
-            format!(
+        let mut source_line = None;
+        for (idx, instruction, is_current) in context {
+            let location = source_map.and_then(|map| map.get_location(instruction.offset));
+            if let Some(location) = &location {
+                let line = (location.file.clone(), location.line);
+                if source_line.as_ref() != Some(&line) {
+                    if let Some(text) = location.source_line() {
+                        lines.push(format!("      {:>4} | {}", location.line, text));
+                    }
+                    source_line = Some(line);
+                }
+            }
+            lines.push(format!(
                 "{:>4}: {}",
                 idx,
-                Self::format_instruction(instruction, *is_current)
-            )
-        }));
+                Self::format_instruction(instruction, *is_current, location.as_ref())
+            ));
+        }
         lines.join("\n")
     }
 
@@ -100,6 +124,8 @@ impl Formatter {
             Some(StepMode::StepOver) => "Step Over",
             Some(StepMode::StepOut) => "Step Out",
             Some(StepMode::StepBlock) => "Step Block",
+            Some(StepMode::StepIntoLine) => "Step Into Line",
+            Some(StepMode::StepOverLine) => "Step Over Line",
             None => "None",
         };
 
@@ -134,9 +160,10 @@ impl Formatter {
     pub fn format_stepping_help() -> String {
         [
             "Stepping commands:",
-            "  n, next       Step to next instruction",
-            "  s, step, into Step into calls",
-            "  o, over       Step over calls",
+            "  n, next       Step to next source line, over calls",
+            "  s, step       Step to next source line, into calls",
+            "  into          Step to next instruction, into calls",
+            "  o, over       Step to next instruction, over calls",
             "  u, out        Step out of function",
             "  b, block      Step to next basic block",
             "  p, prev       Step back",
@@ -145,6 +172,58 @@ impl Formatter {
             "  ctx, context  Show instruction context",
             "  h, help       Show this help",
             "  q, quit       Exit stepping mode",
+            "Without debug info, n and s step by instruction.",
+        ]
+        .join("\n")
+    }
+
+    /// Format help for the prompt shown while an interpreted call is paused.
+    pub fn format_pause_help() -> String {
+        [
+            "Pause commands:",
+            "  n, next       Step to next source line, over calls",
+            "  s, step       Step to next source line, into calls",
+            "  into          Step to next instruction, into calls",
+            "  o, over       Step to next instruction, over calls",
+            "  u, out        Run until the current function returns",
+            "  c, continue   Continue to the next breakpoint",
+            "  locals        Show locals of the current frame",
+            "  stack         Show the operand stack of the current frame",
+            "  globals       Show module globals",
+            "  mem <addr> [len]  Dump linear memory",
+            "  bt, where     Show the call stack",
+            "  ctx, context  Show instruction context",
+            "  storage       Show contract storage as it is now",
+            "  events        Show contract events emitted so far",
+            "  diag          Show the diagnostic event log",
+            "  budget        Show budget usage so far",
+            "  auth          Show authorization checks made so far",
+            "  h, help       Show this help",
+            "  q, quit       Abort the call",
+            "Without debug info, n and s step by instruction.",
+        ]
+        .join("\n")
+    }
+
+    /// Format the list of commands available while replaying a recording.
+    pub fn format_replay_help() -> String {
+        [
+            "Replay commands:",
+            "  n, next, s        Step forward to the next host call",
+            "  rs, back          Step back to the previous host call",
+            "  c, continue       Run forward to the next breakpoint or watchpoint",
+            "  rc                Run backward to the previous breakpoint or watchpoint",
+            "  goto <step>       Jump to a step (0 is the first host call)",
+            "  start, end        Jump to the start or the end of the recording",
+            "  bt, where         Show the call stack",
+            "  steps             List the recorded host calls",
+            "  storage           Show contract storage at this point",
+            "  events            Show contract events emitted so far",
+            "  budget            Show budget usage so far",
+            "  break <location>  Set breakpoint on a function or host:<name>",
+            "  watch <pattern> [read|write]  Set storage watchpoint",
+            "  h, help           Show this help",
+            "  q, quit           Stop replaying",
         ]
         .join("\n")
     }
@@ -1,5 +1,10 @@
 pub mod formatter;
+pub mod pause_prompt;
+pub mod replay_prompt;
+pub mod screen;
 pub mod tui;
 
 pub use formatter::Formatter;
+pub use pause_prompt::PausePrompt;
+pub use replay_prompt::ReplayPrompt;
 pub use tui::DebuggerUI;
@@ -0,0 +1,326 @@
+//! Console prompt shown whenever an interpreted call pauses
+
+use crate::inspector::events::{ContractEvent, EventInspector};
+use crate::inspector::{BudgetInspector, StorageInspector};
+use crate::runtime::interpreter::{
+    AccessKind, FrameInfo, PauseContext, PauseHandler, PauseReason, ResumeAction, StorageAccess,
+    Trap,
+};
+use crate::runtime::{Instruction, InstructionParser};
+use crate::ui::formatter::Formatter;
+use crate::utils::scval::scval_to_string;
+use crate::utils::ContractSpec;
+use soroban_env_host::xdr::{ScError, ScErrorCode};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+/// Instructions shown either side of the paused one
+const CONTEXT_SIZE: usize = 3;
+/// Bytes shown by `mem` when no length is given
+const DEFAULT_MEMORY_LEN: usize = 64;
+
+/// Reads stepping commands from stdin while an interpreted call is paused
+#[derive(Default)]
+pub struct PausePrompt {
+    /// Decoded instructions by contract ID, parsed on the first pause in
+    /// each contract
+    instructions: HashMap<String, Vec<Instruction>>,
+}
+
+impl PausePrompt {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn show_location(&mut self, context: &PauseContext) {
+        let frame = context.current_frame();
+        println!(
+            "\n{} in {} at {:#x} (depth {})",
+            describe_reason(context.reason()),
+            frame_name(&frame),
+            frame.offset,
+            context.depth()
+        );
+        if let Some(location) = &frame.location {
+            println!("At {}", location);
+        }
+        if spans_contracts(&context.frames()) {
+            println!("Contract: {}", frame.contract_id);
+        }
+        if let Some(access) = context.storage_access() {
+            println!("{}", describe_access(access));
+            print_frames(context);
+        }
+        if let Some(call) = context.host_call() {
+            println!("Calling {}", call);
+            print_frames(context);
+        }
+        if let Some(trap) = context.trap() {
+            println!("Reason: {}", describe_trap(context, trap));
+            print_frames(context);
+            println!(
+                "Storage, events, diag, budget and auth show the state at the failure; \
+                 continue lets it unwind."
+            );
+        }
+
+        let instructions = self
+            .instructions
+            .entry(frame.contract_id.clone())
+            .or_insert_with(|| {
+                InstructionParser::new()
+                    .parse(context.wasm())
+                    .map(<[Instruction]>::to_vec)
+                    .unwrap_or_default()
+            });
+        let Some(current) = instructions
+            .iter()
+            .position(|instruction| instruction.offset == frame.offset)
+        else {
+            return;
+        };
+        let start = current.saturating_sub(CONTEXT_SIZE);
+        let end = (current + CONTEXT_SIZE + 1).min(instructions.len());
+        let window: Vec<_> = instructions[start..end]
+            .iter()
+            .enumerate()
+            .filter(|(_, instruction)| instruction.function_index == frame.function_index)
+            .map(|(i, instruction)| (start + i, instruction.clone(), start + i == current))
+            .collect();
+        println!(
+            "{}",
+            Formatter::format_instruction_context(&window, CONTEXT_SIZE, context.source_map())
+        );
+    }
+
+    fn show_memory(context: &PauseContext, args: &[&str]) {
+        let Some(address) = args.first().and_then(|arg| parse_number(arg)) else {
+            println!("Usage: mem <addr> [len]");
+            return;
+        };
+        let len = args
+            .get(1)
+            .and_then(|arg| parse_number(arg))
+            .unwrap_or(DEFAULT_MEMORY_LEN);
+        match context.read_memory(address, len) {
+            Some(bytes) => {
+                for (row, chunk) in bytes.chunks(16).enumerate() {
+                    let hex: Vec<_> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+                    println!("  {:08x}: {}", address + row * 16, hex.join(" "));
+                }
+            }
+            None => println!(
+                "Address range {:#x}..{:#x} is outside linear memory ({} bytes)",
+                address,
+                address.saturating_add(len),
+                context.memory().len()
+            ),
+        }
+    }
+}
+
+impl PauseHandler for PausePrompt {
+    fn on_pause(&mut self, context: &PauseContext) -> ResumeAction {
+        self.show_location(context);
+
+        let stdin = io::stdin();
+        loop {
+            print!("(paused) > ");
+            let _ = io::stdout().flush();
+
+            let mut input = String::new();
+            match stdin.lock().read_line(&mut input) {
+                // Closed stdin leaves nobody to step, so run to completion
+                Ok(0) | Err(_) => return ResumeAction::Continue,
+                Ok(_) => {}
+            }
+            let input = input.trim().to_lowercase();
+            let parts: Vec<&str> = input.split_whitespace().collect();
+
+            match parts.first().copied().unwrap_or("") {
+                "n" | "next" => return ResumeAction::StepOverLine,
+                "s" | "step" => return ResumeAction::StepIntoLine,
+                "into" | "" => return ResumeAction::StepInto,
+                "o" | "over" => return ResumeAction::StepOver,
+                "u" | "out" => return ResumeAction::StepOut,
+                "c" | "continue" => return ResumeAction::Continue,
+                "q" | "quit" | "abort" => return ResumeAction::Abort,
+                "locals" => print_values("Locals", context.locals()),
+                "stack" => print_values("Stack", context.stack()),
+                "globals" => print_values("Globals", context.globals()),
+                "mem" | "memory" => Self::show_memory(context, &parts[1..]),
+                "bt" | "where" | "frames" => print_frames(context),
+                "ctx" | "context" => self.show_location(context),
+                "storage" => show_storage(context),
+                "events" => match EventInspector::get_events(context.host()) {
+                    Ok(events) => print_events("Events", &events),
+                    Err(e) => println!("Events unavailable: {}", e),
+                },
+                "diag" | "diagnostics" => {
+                    match EventInspector::get_diagnostic_events(context.host()) {
+                        Ok(events) => print_events("Diagnostic events", &events),
+                        Err(e) => println!("Diagnostic events unavailable: {}", e),
+                    }
+                }
+                "budget" => {
+                    let info = BudgetInspector::get_cpu_usage(context.host());
+                    println!(
+                        "{}",
+                        Formatter::format_budget(
+                            info.cpu_instructions,
+                            info.cpu_limit,
+                            info.memory_bytes,
+                            info.memory_limit
+                        )
+                    );
+                }
+                "auth" => show_auth_checks(context),
+                "h" | "help" => println!("{}", Formatter::format_pause_help()),
+                other => println!("Unknown command: {} (type 'help')", other),
+            }
+        }
+    }
+}
+
+pub(crate) fn describe_reason(reason: PauseReason) -> &'static str {
+    match reason {
+        PauseReason::Breakpoint | PauseReason::LineBreakpoint => "Breakpoint hit",
+        PauseReason::Step => "Paused",
+        PauseReason::Watchpoint => "Watchpoint hit",
+        PauseReason::HostCall => "Host call breakpoint hit",
+        PauseReason::Error => "Execution failed",
+    }
+}
+
+pub(crate) fn frame_name(frame: &FrameInfo) -> String {
+    frame
+        .function_name
+        .clone()
+        .unwrap_or_else(|| format!("func[{}]", frame.function_index))
+}
+
+fn print_frames(context: &PauseContext) {
+    let frames = context.frames();
+    let show_contract = spans_contracts(&frames);
+    for (i, frame) in frames.iter().enumerate() {
+        print!("  #{} {} at {:#x}", i, frame_name(frame), frame.offset);
+        if let Some(location) = &frame.location {
+            print!(" ({})", location);
+        }
+        if show_contract {
+            print!(" in {}", frame.contract_id);
+        }
+        println!();
+    }
+}
+
+/// One-line summary of a watched storage access, e.g.
+/// `Write instance COUNTER: 1 -> 2`
+pub(crate) fn describe_access(access: &StorageAccess) -> String {
+    let render = |value: &Option<_>| match value {
+        Some(value) => scval_to_string(value),
+        None => "(none)".to_string(),
+    };
+    let key = scval_to_string(&access.key);
+    let durability = access.durability.as_str();
+    match access.kind {
+        AccessKind::Read => format!("Read {} {}: {}", durability, key, render(&access.new_value)),
+        AccessKind::Write => format!(
+            "Write {} {}: {} -> {}",
+            durability,
+            key,
+            render(&access.old_value),
+            render(&access.new_value)
+        ),
+    }
+}
+
+/// Explain a failure: contract errors are named from the spec and host
+/// errors decoded, e.g. `budget exceeded`
+pub(crate) fn describe_trap(context: &PauseContext, trap: &Trap) -> String {
+    let Trap::Host(e) = trap else {
+        return match trap {
+            Trap::Unreachable => format!("{} (the contract panicked)", trap),
+            _ => trap.to_string(),
+        };
+    };
+    match ScError::try_from(e.error) {
+        Ok(ScError::Contract(code)) => {
+            let spec = ContractSpec::from_wasm(context.wasm()).unwrap_or_default();
+            format!(
+                "contract error {}",
+                spec.describe_error(context.export_name(), code)
+            )
+        }
+        Ok(ScError::Budget(ScErrorCode::ExceededLimit)) => {
+            "budget exceeded: the call ran out of CPU instructions or memory".to_string()
+        }
+        Ok(ScError::Auth(code)) => format!("authorization failed ({:?})", code),
+        Ok(error) => format!("host error {:?}", error),
+        Err(_) => format!("host error {:?}", e.error),
+    }
+}
+
+fn show_storage(context: &PauseContext) {
+    match StorageInspector::capture_snapshot(context.host()) {
+        Ok(entries) => StorageInspector::display_entries_by_contract(&entries, |id| id.to_string()),
+        Err(e) => println!("Storage unavailable: {}", e),
+    }
+}
+
+pub(crate) fn print_events(title: &str, events: &[ContractEvent]) {
+    if events.is_empty() {
+        println!("{}: (none)", title);
+        return;
+    }
+    println!("{}:", title);
+    for (i, event) in events.iter().enumerate() {
+        println!(
+            "  #{} {} topics: [{}] data: {}",
+            i,
+            event.contract_id.as_deref().unwrap_or("<host>"),
+            event.topics_text(),
+            event.data
+        );
+    }
+}
+
+/// Authorization checks the contracts made so far in this call
+fn show_auth_checks(context: &PauseContext) {
+    match context.auth_checks() {
+        Ok(checks) if checks.is_empty() => println!("No authorization checks in this call"),
+        Ok(checks) => {
+            println!("Authorization checks:");
+            for (contract_id, call) in checks {
+                println!("  {} in {}", call, contract_id);
+            }
+        }
+        Err(e) => println!("Authorization checks unavailable: {:?}", e.error),
+    }
+}
+
+/// Whether the call has reached into other contracts
+fn spans_contracts(frames: &[FrameInfo]) -> bool {
+    frames
+        .windows(2)
+        .any(|pair| pair[0].contract_id != pair[1].contract_id)
+}
+
+fn print_values<T: std::fmt::Display>(title: &str, values: &[T]) {
+    if values.is_empty() {
+        println!("{}: (empty)", title);
+        return;
+    }
+    println!("{}:", title);
+    for (i, value) in values.iter().enumerate() {
+        println!("  [{}] {}", i, value);
+    }
+}
+
+/// Parse a decimal or `0x`-prefixed hexadecimal number
+fn parse_number(s: &str) -> Option<usize> {
+    match s.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
@@ -0,0 +1,226 @@
+//! Console prompt for stepping through a recorded session
+
+use crate::debugger::recording::{RecordedAccess, RecordedStep, Replay};
+use crate::debugger::{BreakpointManager, WatchpointManager};
+use crate::inspector::StorageInspector;
+use crate::runtime::interpreter::AccessKind;
+use crate::ui::formatter::Formatter;
+use crate::ui::pause_prompt::print_events;
+use std::io::{self, BufRead, Write};
+
+/// Reads replay commands from stdin, moving through a recording in either
+/// direction
+pub struct ReplayPrompt {
+    replay: Replay,
+    breakpoints: BreakpointManager,
+    watchpoints: WatchpointManager,
+}
+
+impl ReplayPrompt {
+    pub fn new(
+        replay: Replay,
+        breakpoints: BreakpointManager,
+        watchpoints: WatchpointManager,
+    ) -> Self {
+        Self {
+            replay,
+            breakpoints,
+            watchpoints,
+        }
+    }
+
+    /// Run the prompt until `quit` or the end of stdin
+    pub fn run(&mut self) {
+        self.show_location();
+
+        let stdin = io::stdin();
+        loop {
+            print!("(replay) > ");
+            let _ = io::stdout().flush();
+
+            let mut input = String::new();
+            match stdin.lock().read_line(&mut input) {
+                Ok(0) | Err(_) => return,
+                Ok(_) => {}
+            }
+            let parts: Vec<&str> = input.split_whitespace().collect();
+
+            match parts.first().copied().unwrap_or("") {
+                "n" | "next" | "s" | "step" | "" => {
+                    if self.replay.step_forward() {
+                        self.show_location();
+                    } else {
+                        println!("Already at the end of the recording");
+                    }
+                }
+                "rs" | "reverse-step" | "back" => {
+                    if self.replay.step_back() {
+                        self.show_location();
+                    } else {
+                        println!("Already at the start of the recording");
+                    }
+                }
+                "c" | "continue" => {
+                    self.replay
+                        .continue_forward(&self.breakpoints, &self.watchpoints);
+                    self.show_location();
+                }
+                "rc" | "reverse-continue" => {
+                    self.replay
+                        .reverse_continue(&self.breakpoints, &self.watchpoints);
+                    self.show_location();
+                }
+                "goto" => match parts.get(1).and_then(|step| step.parse().ok()) {
+                    Some(step) if self.replay.seek(step) => self.show_location(),
+                    _ => println!(
+                        "Usage: goto <step> (0 to {})",
+                        self.replay.recording().steps.len()
+                    ),
+                },
+                "start" => {
+                    self.replay.seek(0);
+                    self.show_location();
+                }
+                "end" => {
+                    self.replay.seek(self.replay.recording().steps.len());
+                    self.show_location();
+                }
+                "bt" | "where" | "frames" => match self.replay.current() {
+                    Some(step) => print_frames(step),
+                    None => println!("The call has returned"),
+                },
+                "steps" => self.list_steps(),
+                "storage" => {
+                    StorageInspector::display_entries_by_contract(&self.replay.storage(), |id| {
+                        id.to_string()
+                    })
+                }
+                "events" => print_events("Events", self.replay.events()),
+                "budget" => {
+                    let budget = self.replay.budget();
+                    let recording = self.replay.recording();
+                    println!(
+                        "{}",
+                        Formatter::format_budget(
+                            budget.cpu_instructions,
+                            recording.cpu_limit,
+                            budget.memory_bytes,
+                            recording.memory_limit
+                        )
+                    );
+                }
+                "break" | "b" => match parts.get(1..).filter(|rest| !rest.is_empty()) {
+                    Some(spec) => match self.breakpoints.add_spec(&spec.join(" ")) {
+                        Ok(()) => println!("Breakpoint set at {}", spec.join(" ")),
+                        Err(e) => println!("{}", e),
+                    },
+                    None => println!("Usage: break <location>"),
+                },
+                "watch" => match parts.get(1..).filter(|rest| !rest.is_empty()) {
+                    Some(spec) => match self.watchpoints.add(&spec.join(" ")) {
+                        Ok(()) => println!("Watchpoint set on {}", spec.join(" ")),
+                        Err(e) => println!("{}", e),
+                    },
+                    None => println!("Usage: watch <pattern> [read|write]"),
+                },
+                "h" | "help" => println!("{}", Formatter::format_replay_help()),
+                "q" | "quit" | "exit" => return,
+                other => println!("Unknown command: {} (type 'help')", other),
+            }
+        }
+    }
+
+    fn show_location(&self) {
+        let recording = self.replay.recording();
+        let total = recording.steps.len();
+        match self.replay.current() {
+            Some(step) => {
+                println!(
+                    "\nStep {}/{} in {}",
+                    self.replay.position(),
+                    total,
+                    frame_name(step)
+                );
+                println!("Calling {}", step.call);
+                if let Some(access) = &step.access {
+                    println!("{}", describe_access(access));
+                }
+                print_frames(step);
+            }
+            None if recording.failed => {
+                println!("\nStep {}/{}: the call failed", total, total);
+                println!("Error: {}", recording.outcome);
+            }
+            None => {
+                println!("\nStep {}/{}: the call returned", total, total);
+                println!("Result: {}", recording.outcome);
+            }
+        }
+    }
+
+    fn list_steps(&self) {
+        let recording = self.replay.recording();
+        for (i, step) in recording.steps.iter().enumerate() {
+            let marker = if i == self.replay.position() {
+                "►"
+            } else {
+                " "
+            };
+            println!("{} {:>4}: {}", marker, i, step.call);
+        }
+        let marker = if self.replay.at_end() { "►" } else { " " };
+        let end = if recording.failed {
+            "failed"
+        } else {
+            "returned"
+        };
+        println!("{} {:>4}: ({})", marker, recording.steps.len(), end);
+    }
+}
+
+fn frame_name(step: &RecordedStep) -> &str {
+    step.frames
+        .first()
+        .and_then(|frame| frame.function.as_deref())
+        .unwrap_or("<unknown>")
+}
+
+fn print_frames(step: &RecordedStep) {
+    let show_contract = step
+        .frames
+        .windows(2)
+        .any(|pair| pair[0].contract_id != pair[1].contract_id);
+    for (i, frame) in step.frames.iter().enumerate() {
+        print!(
+            "  #{} {} at {:#x}",
+            i,
+            frame.function.as_deref().unwrap_or("<unknown>"),
+            frame.offset
+        );
+        if show_contract {
+            print!(" in {}", frame.contract_id);
+        }
+        println!();
+    }
+}
+
+/// One-line summary of a recorded storage access, as at a watchpoint
+fn describe_access(access: &RecordedAccess) -> String {
+    let render = |value: &Option<String>| value.as_deref().unwrap_or("(none)").to_string();
+    let durability = access.durability.as_str();
+    match access.kind {
+        AccessKind::Read => format!(
+            "Read {} {}: {}",
+            durability,
+            access.key,
+            render(&access.new_value)
+        ),
+        AccessKind::Write => format!(
+            "Write {} {}: {} -> {}",
+            durability,
+            access.key,
+            render(&access.old_value),
+            render(&access.new_value)
+        ),
+    }
+}
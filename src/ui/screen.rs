@@ -0,0 +1,481 @@
+//! Full-screen layout of the interactive debugger
+//!
+//! [`Screen`] owns the terminal while the interface is open and draws a
+//! [`View`]: disassembly, call stack, storage, events, budget gauges, an
+//! output log and a command line. Building the view from the debugger's
+//! state is left to the caller, so the same layout serves an idle session
+//! and a paused interpreted call.
+
+use crate::inspector::budget::BudgetInfo;
+use crate::inspector::events::ContractEvent;
+use crate::inspector::storage::StorageEntry;
+use crate::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Gauge, Paragraph};
+use ratatui::{Frame, Terminal};
+use std::io::{self, Stdout};
+
+/// Lines kept in the output log
+const OUTPUT_LIMIT: usize = 500;
+
+/// Key bindings shown on the command line when it is not being edited
+pub const KEY_HINTS: &str =
+    "n next  s step  i into  o over  u out  p back  c continue  b breakpoint  : command  q quit";
+
+/// What a key press asks for, outside the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    StepInto,
+    StepOver,
+    /// Step to the next source line, entering calls
+    StepIntoLine,
+    /// Step to the next source line, over calls
+    StepOverLine,
+    StepOut,
+    StepBack,
+    Continue,
+    ToggleBreakpoint,
+    /// Start typing a command
+    Command,
+    Quit,
+}
+
+impl Action {
+    /// Action bound to a key, if any. Letters follow the console prompts;
+    /// function keys follow common graphical debuggers.
+    pub fn for_key(key: KeyEvent) -> Option<Self> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            return matches!(key.code, KeyCode::Char('c') | KeyCode::Char('d'))
+                .then_some(Action::Quit);
+        }
+        let action = match key.code {
+            KeyCode::F(11) if key.modifiers.contains(KeyModifiers::SHIFT) => Action::StepOut,
+            KeyCode::Char('s') | KeyCode::F(11) => Action::StepIntoLine,
+            KeyCode::Char('n') | KeyCode::F(10) => Action::StepOverLine,
+            KeyCode::Char('i') => Action::StepInto,
+            KeyCode::Char('o') => Action::StepOver,
+            KeyCode::Char('u') => Action::StepOut,
+            KeyCode::Char('p') => Action::StepBack,
+            KeyCode::Char('c') | KeyCode::F(5) => Action::Continue,
+            KeyCode::Char('b') | KeyCode::F(9) => Action::ToggleBreakpoint,
+            KeyCode::Char(':') | KeyCode::Enter => Action::Command,
+            KeyCode::Char('q') => Action::Quit,
+            _ => return None,
+        };
+        Some(action)
+    }
+}
+
+/// One line of the disassembly pane
+#[derive(Debug, Clone, Default)]
+pub struct DisassemblyLine {
+    pub text: String,
+    /// The instruction execution is at
+    pub current: bool,
+    /// A function breakpoint is set on this instruction's function, and this
+    /// is its first instruction
+    pub breakpoint: bool,
+}
+
+/// Everything one frame of the screen shows
+#[derive(Debug, Clone, Default)]
+pub struct View {
+    /// Shown in the command line's border, e.g. where execution is paused
+    pub status: String,
+    pub disassembly: Vec<DisassemblyLine>,
+    /// Shown when there is no disassembly
+    pub disassembly_note: String,
+    pub frames: Vec<String>,
+    pub storage: Vec<String>,
+    pub events: Vec<String>,
+    pub budget: BudgetInfo,
+    pub output: Vec<String>,
+    /// Text being typed on the command line, while it is being edited
+    pub command: Option<String>,
+}
+
+impl View {
+    /// Append to the output log, dropping the oldest lines past the limit
+    pub fn print(&mut self, line: impl Into<String>) {
+        self.output.push(line.into());
+        if self.output.len() > OUTPUT_LIMIT {
+            let excess = self.output.len() - OUTPUT_LIMIT;
+            self.output.drain(..excess);
+        }
+    }
+
+    /// Show storage entries, labelling each contract with `describe` when
+    /// there is more than one
+    pub fn set_storage(&mut self, entries: &[StorageEntry], describe: impl Fn(&str) -> String) {
+        let mut contracts: Vec<&str> = entries.iter().map(|e| e.contract.as_str()).collect();
+        contracts.dedup();
+        let labelled = contracts.len() > 1;
+
+        self.storage.clear();
+        for contract in contracts {
+            if labelled {
+                self.storage.push(describe(contract));
+            }
+            for entry in entries.iter().filter(|e| e.contract == contract) {
+                self.storage.push(format!(
+                    "{}[{}] {} = {}",
+                    if labelled { "  " } else { "" },
+                    entry.durability,
+                    entry.key,
+                    entry.value
+                ));
+            }
+        }
+    }
+
+    pub fn set_events(&mut self, events: &[ContractEvent]) {
+        self.events = events
+            .iter()
+            .enumerate()
+            .map(|(i, event)| {
+                format!(
+                    "#{} {} [{}] {}",
+                    i,
+                    event.contract_id.as_deref().unwrap_or("<host>"),
+                    event.topics_text(),
+                    event.data
+                )
+            })
+            .collect();
+    }
+}
+
+/// The terminal, in raw mode on the alternate screen until dropped.
+///
+/// Log output is muted meanwhile, so it does not draw over the screen.
+pub struct Screen {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl Screen {
+    pub fn open() -> Result<Self> {
+        enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        crate::logging::set_muted(true);
+        let terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+        Ok(Self { terminal })
+    }
+
+    pub fn draw(&mut self, view: &View) -> Result<()> {
+        self.terminal.draw(|frame| render(frame, view))?;
+        Ok(())
+    }
+
+    /// Draw from scratch, wiping anything printed over the screen
+    pub fn redraw(&mut self, view: &View) -> Result<()> {
+        self.terminal.clear()?;
+        self.draw(view)
+    }
+
+    /// Wait for the next key press. `None` means the terminal was resized
+    /// and needs redrawing.
+    pub fn read_key(&mut self) -> Result<Option<KeyEvent>> {
+        loop {
+            match event::read()? {
+                Event::Key(key) if key.kind != KeyEventKind::Release => return Ok(Some(key)),
+                Event::Resize(..) => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        crate::logging::set_muted(false);
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen);
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+/// Edit the command line with a key press. Returns the command once Enter
+/// is pressed; Esc abandons it.
+pub fn edit_command(view: &mut View, key: KeyEvent) -> Option<String> {
+    let command = view.command.as_mut()?;
+    match key.code {
+        KeyCode::Enter => return view.command.take(),
+        KeyCode::Esc => view.command = None,
+        KeyCode::Backspace => {
+            command.pop();
+        }
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => view.command = None,
+        KeyCode::Char(c) => command.push(c),
+        _ => {}
+    }
+    None
+}
+
+/// Lay out and draw every pane
+pub fn render(frame: &mut Frame, view: &View) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(frame.size());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(rows[0]);
+    let left = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(columns[0]);
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(35),
+            Constraint::Percentage(35),
+            Constraint::Min(3),
+            Constraint::Length(4),
+        ])
+        .split(columns[1]);
+
+    render_disassembly(frame, left[0], view);
+    render_lines(frame, left[1], "Output", &view.output, true);
+    render_lines(frame, right[0], "Call Stack", &view.frames, false);
+    render_lines(frame, right[1], "Storage", &view.storage, false);
+    render_lines(frame, right[2], "Events", &view.events, true);
+    render_budget(frame, right[3], &view.budget);
+    render_command_line(frame, rows[1], view);
+}
+
+fn pane(title: &str) -> Block<'_> {
+    Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} ", title))
+}
+
+/// Plain text lines; `follow` keeps the newest lines in view
+fn render_lines(frame: &mut Frame, area: Rect, title: &str, lines: &[String], follow: bool) {
+    let height = area.height.saturating_sub(2) as usize;
+    let skip = if follow {
+        lines.len().saturating_sub(height)
+    } else {
+        0
+    };
+    let text: Vec<Line> = lines
+        .iter()
+        .skip(skip)
+        .map(|line| Line::from(line.as_str()))
+        .collect();
+    frame.render_widget(Paragraph::new(text).block(pane(title)), area);
+}
+
+/// Disassembly scrolled to keep the current instruction in the middle
+fn render_disassembly(frame: &mut Frame, area: Rect, view: &View) {
+    let block = pane("Disassembly");
+    if view.disassembly.is_empty() {
+        frame.render_widget(
+            Paragraph::new(view.disassembly_note.as_str()).block(block),
+            area,
+        );
+        return;
+    }
+
+    let height = area.height.saturating_sub(2) as usize;
+    let current = view
+        .disassembly
+        .iter()
+        .position(|line| line.current)
+        .unwrap_or(0);
+    let skip = current
+        .saturating_sub(height / 2)
+        .min(view.disassembly.len().saturating_sub(height));
+    let text: Vec<Line> = view
+        .disassembly
+        .iter()
+        .skip(skip)
+        .take(height)
+        .map(|line| {
+            let marker = if line.breakpoint {
+                Span::styled("● ", Style::default().fg(Color::Red))
+            } else {
+                Span::raw("  ")
+            };
+            let pointer = if line.current { "► " } else { "  " };
+            let style = if line.current {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            Line::from(vec![
+                marker,
+                Span::styled(format!("{}{}", pointer, line.text), style),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(text).block(block), area);
+}
+
+fn render_budget(frame: &mut Frame, area: Rect, budget: &BudgetInfo) {
+    let block = pane("Budget");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1)])
+        .split(inner);
+    let gauges = [
+        (
+            "CPU",
+            budget.cpu_instructions,
+            budget.cpu_limit,
+            Color::Green,
+        ),
+        ("Mem", budget.memory_bytes, budget.memory_limit, Color::Blue),
+    ];
+    for ((name, used, limit, color), row) in gauges.into_iter().zip(rows.iter()) {
+        let ratio = if limit == 0 {
+            0.0
+        } else {
+            (used as f64 / limit as f64).clamp(0.0, 1.0)
+        };
+        let gauge = Gauge::default()
+            .gauge_style(Style::default().fg(color))
+            .ratio(ratio)
+            .label(format!("{} {} / {}", name, used, limit));
+        frame.render_widget(gauge, *row);
+    }
+}
+
+fn render_command_line(frame: &mut Frame, area: Rect, view: &View) {
+    let (text, style) = match &view.command {
+        Some(command) => (format!(":{}", command), Style::default()),
+        None => (KEY_HINTS.to_string(), Style::default().fg(Color::DarkGray)),
+    };
+    let paragraph = Paragraph::new(Line::styled(text, style)).block(pane(&view.status));
+    frame.render_widget(paragraph, area);
+
+    if let Some(command) = &view.command {
+        let x = area.x + 2 + command.chars().count() as u16;
+        frame.set_cursor(x.min(area.right().saturating_sub(2)), area.y + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    fn screen_text(view: &View) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| render(frame, view)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer.get(x, y).symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_render_shows_every_pane() {
+        let mut view = View {
+            status: "Paused in increment".to_string(),
+            frames: vec!["#0 increment at 0x3b7".to_string()],
+            budget: BudgetInfo {
+                cpu_instructions: 50,
+                cpu_limit: 100,
+                memory_bytes: 0,
+                memory_limit: 100,
+            },
+            ..View::default()
+        };
+        view.disassembly = (0..40)
+            .map(|i| DisassemblyLine {
+                text: format!("{:08x}: nop", i),
+                current: i == 30,
+                breakpoint: i == 0,
+            })
+            .collect();
+        view.print("Result: 1");
+
+        let text = screen_text(&view);
+        for expected in [
+            "Disassembly",
+            "Call Stack",
+            "Storage",
+            "Events",
+            "Budget",
+            "Output",
+            "Paused in increment",
+            "#0 increment at 0x3b7",
+            "Result: 1",
+            "CPU 50 / 100",
+            KEY_HINTS,
+        ] {
+            assert!(text.contains(expected), "missing {:?}", expected);
+        }
+        // The current instruction stays in view, the start scrolls away
+        assert!(text.contains("► 0000001e: nop"));
+        assert!(!text.contains("00000000: nop"));
+    }
+
+    #[test]
+    fn test_keys_map_to_actions() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        assert_eq!(
+            Action::for_key(key(KeyCode::Char('s'))),
+            Some(Action::StepIntoLine)
+        );
+        assert_eq!(
+            Action::for_key(key(KeyCode::F(10))),
+            Some(Action::StepOverLine)
+        );
+        assert_eq!(
+            Action::for_key(key(KeyCode::Char('o'))),
+            Some(Action::StepOver)
+        );
+        assert_eq!(
+            Action::for_key(KeyEvent::new(KeyCode::F(11), KeyModifiers::SHIFT)),
+            Some(Action::StepOut)
+        );
+        assert_eq!(
+            Action::for_key(key(KeyCode::F(9))),
+            Some(Action::ToggleBreakpoint)
+        );
+        assert_eq!(
+            Action::for_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(Action::Quit)
+        );
+        assert_eq!(Action::for_key(key(KeyCode::Char('z'))), None);
+    }
+
+    #[test]
+    fn test_command_line_editing() {
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        let mut view = View {
+            command: Some(String::new()),
+            ..View::default()
+        };
+        for c in "calx".chars() {
+            assert_eq!(edit_command(&mut view, key(KeyCode::Char(c))), None);
+        }
+        edit_command(&mut view, key(KeyCode::Backspace));
+        edit_command(&mut view, key(KeyCode::Char('l')));
+        assert_eq!(
+            edit_command(&mut view, key(KeyCode::Enter)),
+            Some("call".to_string())
+        );
+        assert_eq!(view.command, None);
+    }
+}
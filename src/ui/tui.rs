@@ -1,20 +1,42 @@
-use crate::debugger::engine::DebuggerEngine;
+use crate::debugger::engine::{CallReport, DebuggerEngine, ExecutionBackend};
+use crate::debugger::StepMode;
+use crate::inspector::events::EventInspector;
 use crate::inspector::{BudgetInspector, StorageInspector};
+use crate::runtime::executor::ExecutionResult;
+use crate::runtime::interpreter::{PauseContext, PauseHandler, ResumeAction};
+use crate::runtime::{Instruction, InstructionParser};
+use crate::ui::pause_prompt::{
+    describe_access, describe_reason, describe_trap, frame_name, print_events,
+};
+use crate::ui::screen::{edit_command, Action, DisassemblyLine, Screen, View};
+use crate::utils::wasm::parse_function_names;
+use crate::utils::SourceMap;
 use crate::Result;
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::io::{self, Write};
+use std::rc::Rc;
+
+/// Instructions of the recorded path shown either side of the current one
+const DISASSEMBLY_CONTEXT: usize = 40;
+
+/// The screen and what it shows, shared by the full-screen loop and the
+/// pause handler of interpreted calls
+struct FullScreen {
+    screen: Screen,
+    view: View,
+    /// Breakpoint locations when the running call started
+    breakpoints: Vec<String>,
+}
 
 /// Terminal user interface for interactive debugging.
 pub struct DebuggerUI {
     engine: DebuggerEngine,
-    storage_inspector: StorageInspector,
 }
 
 impl DebuggerUI {
     pub fn new(engine: DebuggerEngine) -> Result<Self> {
-        Ok(Self {
-            engine,
-            storage_inspector: StorageInspector::new(),
-        })
+        Ok(Self { engine })
     }
 
     /// Run the interactive UI loop.
@@ -55,6 +77,19 @@ impl DebuggerUI {
         }
 
         match parts[0] {
+            "call" => {
+                // JSON arguments may contain spaces
+                let rest = command.trim_start()[parts[0].len()..].trim();
+                let (function, args) = match rest.split_once(char::is_whitespace) {
+                    Some((function, args)) => (function, Some(args.trim())),
+                    None => (rest, None),
+                };
+                if function.is_empty() {
+                    tracing::warn!("call command missing function name");
+                } else {
+                    self.call(function, args)?;
+                }
+            }
             "s" | "step" => {
                 self.engine.step()?;
                 if let Ok(state) = self.engine.state().lock() {
@@ -69,7 +104,11 @@ impl DebuggerUI {
                 self.inspect();
             }
             "storage" => {
-                self.storage_inspector.display();
+                let executor = self.engine.executor();
+                let entries = executor.get_workspace_storage_snapshot()?;
+                StorageInspector::display_entries_by_contract(&entries, |id| {
+                    executor.describe_contract(id)
+                });
             }
             "stack" => {
                 if let Ok(state) = self.engine.state().lock() {
@@ -80,11 +119,13 @@ impl DebuggerUI {
                 BudgetInspector::display(self.engine.executor().host());
             }
             "break" => {
-                if parts.len() < 2 {
+                // Conditions and log messages may contain spaces
+                let spec = command.trim_start()[parts[0].len()..].trim();
+                if spec.is_empty() {
                     tracing::warn!("breakpoint set without function name");
                 } else {
-                    self.engine.breakpoints_mut().add(parts[1]);
-                    crate::logging::log_breakpoint_set(parts[1]);
+                    self.engine.breakpoints_mut().add_spec(spec)?;
+                    crate::logging::log_breakpoint_set(spec);
                 }
             }
             "list-breaks" => {
@@ -106,6 +147,29 @@ impl DebuggerUI {
                     tracing::debug!(breakpoint = parts[1], "No breakpoint found at function");
                 }
             }
+            "watch" => {
+                let spec = command.trim_start()[parts[0].len()..].trim();
+                if spec.is_empty() {
+                    let watchpoints = self.engine.watchpoints_mut().list();
+                    if watchpoints.is_empty() {
+                        println!("No watchpoints set");
+                    }
+                    for watchpoint in watchpoints {
+                        println!("- {}", watchpoint);
+                    }
+                } else {
+                    self.engine.watchpoints_mut().add(spec)?;
+                    tracing::info!(watchpoint = spec, "Watchpoint set");
+                }
+            }
+            "unwatch" => {
+                let pattern = command.trim_start()[parts[0].len()..].trim();
+                if pattern.is_empty() {
+                    tracing::warn!("unwatch command missing key pattern");
+                } else if !self.engine.watchpoints_mut().remove(pattern) {
+                    tracing::debug!(pattern, "No watchpoint found on pattern");
+                }
+            }
             "help" => self.print_help(),
             "q" | "quit" | "exit" => {
                 tracing::info!("Exiting debugger");
@@ -117,6 +181,356 @@ impl DebuggerUI {
         Ok(false)
     }
 
+    /// Call a contract function, then print its result and what it changed:
+    /// new events, storage changes and the budget it used. Storage persists
+    /// from one call to the next.
+    fn call(&mut self, function: &str, args: Option<&str>) -> Result<()> {
+        let report = self.engine.execute_with_report(function, args)?;
+        println!("{}", result_line(&report.result));
+        print_events("Events", &report.events);
+        StorageInspector::display_diff(&report.storage);
+        println!(
+            "Budget: {} CPU instructions, {} bytes",
+            report.cpu_instructions, report.memory_bytes
+        );
+        Ok(())
+    }
+
+    /// Run the full-screen interface until `q`.
+    ///
+    /// With the host backend each call records the path it takes, and the
+    /// stepping keys walk that path. With the interpreter backend calls
+    /// pause at breakpoints and the stepping keys drive the paused call.
+    pub fn run_full_screen(&mut self, wasm_bytes: &[u8]) -> Result<()> {
+        let names = parse_function_names(wasm_bytes).unwrap_or_default();
+        if self.engine.backend() == ExecutionBackend::Host {
+            self.engine.enable_instruction_debug(wasm_bytes)?;
+            self.engine.start_instruction_stepping(StepMode::StepInto)?;
+        }
+
+        let shared = Rc::new(RefCell::new(FullScreen {
+            screen: Screen::open()?,
+            view: View::default(),
+            breakpoints: Vec::new(),
+        }));
+        if self.engine.backend() == ExecutionBackend::Interpreter {
+            self.engine
+                .set_pause_handler(ScreenPause::new(Rc::clone(&shared)));
+        }
+        shared
+            .borrow_mut()
+            .view
+            .print("Type :call <function> [args] to call a function, :help for help");
+
+        let mut redraw = true;
+        loop {
+            {
+                let mut guard = shared.borrow_mut();
+                let FullScreen { screen, view, .. } = &mut *guard;
+                self.refresh(view, &names)?;
+                if redraw {
+                    screen.redraw(view)?;
+                } else {
+                    screen.draw(view)?;
+                }
+            }
+            redraw = false;
+
+            let Some(key) = shared.borrow_mut().screen.read_key()? else {
+                redraw = true;
+                continue;
+            };
+
+            let command = {
+                let mut guard = shared.borrow_mut();
+                let view = &mut guard.view;
+                if view.command.is_some() {
+                    match edit_command(view, key) {
+                        Some(command) => command,
+                        None => continue,
+                    }
+                } else {
+                    match Action::for_key(key) {
+                        Some(Action::Command) => {
+                            view.command = Some(String::new());
+                            continue;
+                        }
+                        Some(Action::Quit) => break,
+                        Some(action) => {
+                            self.screen_action(action, view, &names);
+                            continue;
+                        }
+                        None => continue,
+                    }
+                }
+            };
+
+            // Interpreted calls may pause, and the pause handler draws on
+            // the shared screen, so nothing may be borrowed meanwhile
+            {
+                let mut guard = shared.borrow_mut();
+                guard.view.print(format!(":{}", command.trim()));
+                guard.breakpoints = self.engine.breakpoints().locations();
+            }
+            let (output, quit) = self.screen_command(command.trim());
+            let mut guard = shared.borrow_mut();
+            for line in output {
+                guard.view.print(line);
+            }
+            if quit {
+                break;
+            }
+            // Anything printed to the terminal meanwhile is wiped
+            redraw = true;
+        }
+
+        Ok(())
+    }
+
+    /// Run a command typed on the full-screen command line, returning its
+    /// output and whether to quit
+    fn screen_command(&mut self, command: &str) -> (Vec<String>, bool) {
+        let mut output = Vec::new();
+        let (name, rest) = match command.split_once(char::is_whitespace) {
+            Some((name, rest)) => (name, rest.trim()),
+            None => (command, ""),
+        };
+
+        let result: Result<()> = match name {
+            "" => Ok(()),
+            "call" => {
+                let (function, args) = match rest.split_once(char::is_whitespace) {
+                    Some((function, args)) => (function, Some(args.trim())),
+                    None => (rest, None),
+                };
+                if function.is_empty() {
+                    output.push("Usage: call <function> [args]".to_string());
+                    Ok(())
+                } else {
+                    self.engine
+                        .execute_with_report(function, args)
+                        .map(|report| {
+                            output.extend(describe_report(&report));
+                            if self.engine.is_instruction_debug_enabled() {
+                                let _ = self.engine.start_instruction_stepping(StepMode::StepInto);
+                            }
+                        })
+                }
+            }
+            "break" | "b" if rest.is_empty() => {
+                output.push("Usage: break <function> [if <cond>] [hit <count>] [log <msg>]".into());
+                Ok(())
+            }
+            "break" | "b" => self.engine.breakpoints_mut().add_spec(rest).map(|()| {
+                output.push(format!("Breakpoint set at {}", rest));
+            }),
+            "list-breaks" | "breaks" => {
+                let breakpoints = self.engine.breakpoints().list();
+                if breakpoints.is_empty() {
+                    output.push("No breakpoints set".to_string());
+                }
+                output.extend(breakpoints.into_iter().map(|bp| format!("- {}", bp)));
+                Ok(())
+            }
+            "clear" => {
+                if self.engine.breakpoints_mut().remove(rest) {
+                    output.push(format!("Breakpoint cleared at {}", rest));
+                } else {
+                    output.push(format!("No breakpoint at {}", rest));
+                }
+                Ok(())
+            }
+            "watch" if rest.is_empty() => {
+                let watchpoints = self.engine.watchpoints_mut().list();
+                if watchpoints.is_empty() {
+                    output.push("No watchpoints set".to_string());
+                }
+                output.extend(watchpoints.into_iter().map(|wp| format!("- {}", wp)));
+                Ok(())
+            }
+            "watch" => self.engine.watchpoints_mut().add(rest).map(|()| {
+                output.push(format!("Watchpoint set on {}", rest));
+            }),
+            "unwatch" => {
+                if self.engine.watchpoints_mut().remove(rest) {
+                    output.push(format!("Watchpoint cleared on {}", rest));
+                } else {
+                    output.push(format!("No watchpoint on {}", rest));
+                }
+                Ok(())
+            }
+            "help" | "h" => {
+                output.extend(SCREEN_HELP.lines().map(str::to_string));
+                Ok(())
+            }
+            "q" | "quit" | "exit" => return (output, true),
+            other => {
+                output.push(format!("Unknown command: {} (type :help)", other));
+                Ok(())
+            }
+        };
+
+        if let Err(e) = result {
+            output.push(format!("Error: {}", e));
+        }
+        (output, false)
+    }
+
+    /// Handle a key binding while no call is paused
+    fn screen_action(&mut self, action: Action, view: &mut View, names: &HashMap<u32, String>) {
+        if action == Action::ToggleBreakpoint {
+            self.toggle_breakpoint(view, names);
+            return;
+        }
+        if !self.engine.is_instruction_debug_enabled() {
+            view.print("Nothing is paused: calls pause at breakpoints, watchpoints and errors");
+            return;
+        }
+
+        let stepped = match action {
+            Action::StepInto => self.engine.step_into(),
+            Action::StepOver => self.engine.step_over(),
+            Action::StepIntoLine => self.engine.step_into_line(),
+            Action::StepOverLine => self.engine.step_over_line(),
+            Action::StepOut => self.engine.step_out(),
+            Action::StepBack => self.engine.step_back(),
+            Action::Continue => self.continue_on_trace(names),
+            _ => Ok(true),
+        };
+        match stepped {
+            Ok(true) => {}
+            Ok(false) if action == Action::StepBack => view.print("At the start of the path"),
+            Ok(false) => view.print("At the end of the path"),
+            Err(e) => view.print(format!("Error: {}", e)),
+        }
+    }
+
+    /// Step along the recorded path to the entry of the next function with a
+    /// breakpoint. Returns `false` at the end of the path.
+    fn continue_on_trace(&mut self, names: &HashMap<u32, String>) -> Result<bool> {
+        while self.engine.step_into()? {
+            let Some(instruction) = self.engine.current_instruction() else {
+                continue;
+            };
+            let entered = instruction.local_index == 0
+                && names
+                    .get(&instruction.function_index)
+                    .is_some_and(|name| self.engine.breakpoints().should_break(name));
+            if entered {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Set or clear a breakpoint on the function at the current instruction,
+    /// or on the last called function when there is none
+    fn toggle_breakpoint(&mut self, view: &mut View, names: &HashMap<u32, String>) {
+        let function = match self.engine.current_instruction() {
+            Some(instruction) if self.engine.is_instruction_debug_enabled() => {
+                match names.get(&instruction.function_index) {
+                    Some(name) => name.clone(),
+                    None => {
+                        view.print(format!(
+                            "func[{}] has no name to set a breakpoint on",
+                            instruction.function_index
+                        ));
+                        return;
+                    }
+                }
+            }
+            _ => {
+                let current = self
+                    .engine
+                    .state()
+                    .lock()
+                    .ok()
+                    .and_then(|state| state.current_function().map(str::to_string));
+                match current {
+                    Some(function) => function,
+                    None => {
+                        view.print("No function to set a breakpoint on: :break <function>");
+                        return;
+                    }
+                }
+            }
+        };
+
+        let breakpoints = self.engine.breakpoints_mut();
+        if breakpoints.remove(&function) {
+            view.print(format!("Breakpoint cleared at {}", function));
+        } else {
+            breakpoints.add(&function);
+            view.print(format!("Breakpoint set at {}", function));
+        }
+    }
+
+    /// Fill the panes from the session's current state
+    fn refresh(&self, view: &mut View, names: &HashMap<u32, String>) -> Result<()> {
+        let executor = self.engine.executor();
+        view.set_storage(&executor.get_workspace_storage_snapshot()?, |id| {
+            executor.describe_contract(id)
+        });
+        view.set_events(&executor.get_events()?);
+        view.budget = BudgetInspector::get_cpu_usage(executor.host());
+
+        let state = self.engine.state();
+        let Ok(state) = state.lock() else {
+            return Ok(());
+        };
+        view.frames = state
+            .call_stack()
+            .get_stack()
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, frame)| format!("#{} {}", i, frame.function))
+            .collect();
+
+        if !self.engine.is_instruction_debug_enabled() {
+            view.status = "Ready".to_string();
+            view.disassembly.clear();
+            view.disassembly_note =
+                "Calls pause at breakpoints, watchpoints and errors; the paused \
+                 instruction shows here."
+                    .to_string();
+            return Ok(());
+        }
+
+        let breakpoints = self.engine.breakpoints();
+        view.disassembly = state
+            .get_instruction_context(DISASSEMBLY_CONTEXT)
+            .into_iter()
+            .map(|(_, instruction, current)| {
+                disassembly_line(
+                    instruction,
+                    current,
+                    names,
+                    self.engine.source_map(),
+                    |name| breakpoints.should_break(name),
+                )
+            })
+            .collect();
+
+        let ip = state.instruction_pointer();
+        let trace = state.trace();
+        view.status = if trace.is_empty() {
+            "No call recorded yet".to_string()
+        } else {
+            format!(
+                "Step {}/{} of the last call, call depth {}",
+                ip.trace_position() + 1,
+                trace.len(),
+                ip.call_stack_depth()
+            )
+        };
+        if let Some(instruction) = state.current_instruction() {
+            view.frames
+                .insert(0, format!("at {}", function_label(instruction, names)));
+        }
+        Ok(())
+    }
+
     fn inspect(&self) {
         println!("\n=== Current State ===");
         if let Ok(state) = self.engine.state().lock() {
@@ -136,16 +550,278 @@ impl DebuggerUI {
 
     fn print_help(&self) {
         println!("Interactive debugger commands:");
+        println!("  call <func>        Call a function and show what it changed");
+        println!("    [args]           ...with arguments as a JSON array");
         println!("  step | s           Step execution");
         println!("  continue | c       Continue execution");
         println!("  inspect | i        Show current state");
-        println!("  storage            Show tracked storage view");
+        println!("  storage            Show contract storage");
         println!("  stack              Show call stack");
         println!("  budget             Show budget usage");
         println!("  break <func>       Set breakpoint");
+        println!("  break host:<name>  Set breakpoint on a host function call");
+        println!("    [if <cond>]      ...that stops only when <cond> holds");
+        println!("    [hit <count>]    ...on the hits <count> allows (3, >= 3, % 3)");
+        println!("    [log <message>]  ...that prints <message> instead of stopping");
         println!("  list-breaks        List breakpoints");
         println!("  clear <func>       Clear breakpoint");
+        println!("  watch <pattern>    Pause when a matching storage key is accessed");
+        println!("    [read|write]     ...only on reads or only on writes");
+        println!("  watch              List watchpoints");
+        println!("  unwatch <pattern>  Clear watchpoint");
         println!("  help               Show this help");
         println!("  quit | q           Exit debugger");
     }
 }
+
+/// Commands and keys of the full-screen interface
+const SCREEN_HELP: &str = "\
+Keys: s/F11 step into, o/F10 step over, u/Shift+F11 step out, p step back,
+  c/F5 continue, b/F9 toggle a breakpoint on the current function,
+  : command line (Esc cancels), q quit
+Commands: call <function> [args], break <spec>, clear <function>,
+  list-breaks, watch [<pattern> [read|write]], unwatch <pattern>, quit";
+
+/// Output lines for a call's result, events, storage changes and budget
+/// `Result: <value>` rendered against the spec, or the error the call
+/// failed with
+fn result_line(result: &ExecutionResult) -> String {
+    match &result.value {
+        Some(value) => format!("Result: {}", value),
+        None => result.result.clone(),
+    }
+}
+
+fn describe_report(report: &CallReport) -> Vec<String> {
+    let mut lines = vec![result_line(&report.result)];
+    if !report.events.is_empty() {
+        lines.push(format!("{} new events", report.events.len()));
+    }
+
+    let diff = &report.storage;
+    let mut changes: Vec<String> = diff
+        .added
+        .iter()
+        .map(|(key, value)| format!("  + {} = {}", key, value))
+        .chain(
+            diff.modified
+                .iter()
+                .map(|(key, (old, new))| format!("  ~ {}: {} -> {}", key, old, new)),
+        )
+        .chain(diff.deleted.iter().map(|key| format!("  - {}", key)))
+        .collect();
+    changes.sort_by(|a, b| a[4..].cmp(&b[4..]));
+    if changes.is_empty() {
+        lines.push("Storage: (no changes)".to_string());
+    } else {
+        lines.push("Storage changes:".to_string());
+        lines.extend(changes);
+    }
+
+    lines.push(format!(
+        "Budget: {} CPU instructions, {} bytes",
+        report.cpu_instructions, report.memory_bytes
+    ));
+    lines
+}
+
+/// Name of an instruction's function, e.g. `increment` or `func[12]`
+fn function_label(instruction: &Instruction, names: &HashMap<u32, String>) -> String {
+    names
+        .get(&instruction.function_index)
+        .cloned()
+        .unwrap_or_else(|| format!("func[{}]", instruction.function_index))
+}
+
+/// A disassembly pane line. Function entries are labelled, and marked when
+/// `has_breakpoint` holds for the function. With a source map, the source
+/// location follows.
+fn disassembly_line(
+    instruction: &Instruction,
+    current: bool,
+    names: &HashMap<u32, String>,
+    source_map: Option<&SourceMap>,
+    has_breakpoint: impl Fn(&str) -> bool,
+) -> DisassemblyLine {
+    let entry = instruction.local_index == 0;
+    let name = names.get(&instruction.function_index);
+    let mut notes = Vec::new();
+    if entry {
+        notes.push(function_label(instruction, names));
+    }
+    if let Some(location) = source_map.and_then(|map| map.get_location(instruction.offset)) {
+        notes.push(location.to_string());
+    }
+    DisassemblyLine {
+        text: if notes.is_empty() {
+            instruction.to_string()
+        } else {
+            format!("{}  ; {}", instruction, notes.join(" "))
+        },
+        current,
+        breakpoint: entry && name.is_some_and(|name| has_breakpoint(name)),
+    }
+}
+
+/// Shows interpreted calls paused at breakpoints, watchpoints and errors on
+/// the full-screen interface, with the stepping keys resuming them
+struct ScreenPause {
+    shared: Rc<RefCell<FullScreen>>,
+    /// Decoded instructions and function names by contract ID, parsed on
+    /// the first pause in each contract
+    modules: HashMap<String, (Vec<Instruction>, HashMap<u32, String>)>,
+}
+
+impl ScreenPause {
+    fn new(shared: Rc<RefCell<FullScreen>>) -> Self {
+        Self {
+            shared,
+            modules: HashMap::new(),
+        }
+    }
+
+    /// Fill the panes from the paused call's live state
+    fn show(&mut self, context: &PauseContext, view: &mut View, breakpoints: &[String]) {
+        let frame = context.current_frame();
+        view.status = format!(
+            "{} in {} at {:#x} (depth {})",
+            describe_reason(context.reason()),
+            frame_name(&frame),
+            frame.offset,
+            context.depth()
+        );
+        view.print(view.status.clone());
+        if let Some(location) = &frame.location {
+            match location.source_line() {
+                Some(text) => view.print(format!("At {}: {}", location, text)),
+                None => view.print(format!("At {}", location)),
+            }
+        }
+        if let Some(access) = context.storage_access() {
+            view.print(describe_access(access));
+        }
+        if let Some(call) = context.host_call() {
+            view.print(format!("Calling {}", call));
+        }
+        if let Some(trap) = context.trap() {
+            view.print(format!("Reason: {}", describe_trap(context, trap)));
+            view.print("Continue lets the failure unwind");
+        }
+
+        let (instructions, names) = self
+            .modules
+            .entry(frame.contract_id.clone())
+            .or_insert_with(|| {
+                let instructions = InstructionParser::new()
+                    .parse(context.wasm())
+                    .map(<[Instruction]>::to_vec)
+                    .unwrap_or_default();
+                let names = parse_function_names(context.wasm()).unwrap_or_default();
+                (instructions, names)
+            });
+        view.disassembly = instructions
+            .iter()
+            .filter(|instruction| instruction.function_index == frame.function_index)
+            .map(|instruction| {
+                disassembly_line(
+                    instruction,
+                    instruction.offset == frame.offset,
+                    names,
+                    context.source_map(),
+                    |name| breakpoints.iter().any(|location| location == name),
+                )
+            })
+            .collect();
+
+        let frames = context.frames();
+        let spans_contracts = frames
+            .windows(2)
+            .any(|pair| pair[0].contract_id != pair[1].contract_id);
+        view.frames = frames
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                let mut line = format!("#{} {} at {:#x}", i, frame_name(frame), frame.offset);
+                if let Some(location) = &frame.location {
+                    line.push_str(&format!(" ({})", location));
+                }
+                if spans_contracts {
+                    line.push_str(&format!(" in {}", frame.contract_id));
+                }
+                line
+            })
+            .collect();
+        view.frames.push(String::new());
+        view.frames.push("Locals:".to_string());
+        view.frames.extend(
+            context
+                .locals()
+                .iter()
+                .enumerate()
+                .map(|(i, value)| format!("  ${} = {}", i, value)),
+        );
+
+        match StorageInspector::capture_snapshot(context.host()) {
+            Ok(entries) => view.set_storage(&entries, str::to_string),
+            Err(e) => view.storage = vec![format!("Storage unavailable: {}", e)],
+        }
+        match EventInspector::get_events(context.host()) {
+            Ok(events) => view.set_events(&events),
+            Err(e) => view.events = vec![format!("Events unavailable: {}", e)],
+        }
+        view.budget = BudgetInspector::get_cpu_usage(context.host());
+    }
+}
+
+impl PauseHandler for ScreenPause {
+    fn on_pause(&mut self, context: &PauseContext) -> ResumeAction {
+        let shared = Rc::clone(&self.shared);
+        let mut guard = shared.borrow_mut();
+        let FullScreen {
+            screen,
+            view,
+            breakpoints,
+        } = &mut *guard;
+        self.show(context, view, breakpoints);
+        view.command = None;
+
+        let mut redraw = true;
+        loop {
+            let drawn = if redraw {
+                screen.redraw(view)
+            } else {
+                screen.draw(view)
+            };
+            redraw = false;
+            let key = match drawn.and_then(|()| screen.read_key()) {
+                Ok(Some(key)) => key,
+                Ok(None) => {
+                    redraw = true;
+                    continue;
+                }
+                // Nobody can step without a terminal, so run to completion
+                Err(_) => return ResumeAction::Continue,
+            };
+
+            match Action::for_key(key) {
+                Some(Action::StepInto) => return ResumeAction::StepInto,
+                Some(Action::StepOver) => return ResumeAction::StepOver,
+                Some(Action::StepIntoLine) => return ResumeAction::StepIntoLine,
+                Some(Action::StepOverLine) => return ResumeAction::StepOverLine,
+                Some(Action::StepOut) => return ResumeAction::StepOut,
+                Some(Action::Continue) => return ResumeAction::Continue,
+                Some(Action::Quit) => {
+                    view.print("Call aborted");
+                    return ResumeAction::Abort;
+                }
+                Some(Action::StepBack) => {
+                    view.print("A live call cannot step back; record it and replay it instead")
+                }
+                Some(Action::ToggleBreakpoint) | Some(Action::Command) => {
+                    view.print("Breakpoints and commands are available once the call returns")
+                }
+                None => {}
+            }
+        }
+    }
+}
@@ -18,17 +18,43 @@
 //! | `i64`    | `{"type": "i64", "value": -999}`         | Signed 64-bit integer          |
 //! | `u128`   | `{"type": "u128", "value": 100}`         | Unsigned 128-bit integer       |
 //! | `i128`   | `{"type": "i128", "value": 100}`         | Signed 128-bit integer         |
+//! | `u256`   | `{"type": "u256", "value": "0xff"}`      | Unsigned 256-bit integer       |
+//! | `i256`   | `{"type": "i256", "value": "-5"}`        | Signed 256-bit integer         |
 //! | `bool`   | `{"type": "bool", "value": true}`        | Boolean                        |
 //! | `symbol` | `{"type": "symbol", "value": "hello"}`   | Soroban Symbol (≤32 chars)     |
 //! | `string` | `{"type": "string", "value": "long..."}`  | Soroban String (any length)    |
+//! | `timepoint` | `{"type": "timepoint", "value": 1700000000}` | Ledger timestamp  |
+//! | `duration` | `{"type": "duration", "value": 3600}`  | Time span in seconds           |
+//! | `address`| `{"type": "address", "value": "GA..."}`  | Account (`G...`) or contract (`C...`) |
+//! | `bytes`  | `{"type": "bytes", "value": "0xdead"}`   | Bytes from `0x` hex            |
+//! | `bytesn` | `{"type": "bytesn", "value": "0x..", "length": 32}` | Fixed-length bytes  |
+//! | `vec`    | `{"type": "vec", "element": "u32", "value": [1, 2]}` | Typed Vec          |
+//! | `map`    | `{"type": "map", "key_type": "address", "value_type": "i128", "value": {...}}` | Typed Map |
+//!
+//! 128- and 256-bit integers also take decimal strings, and 256-bit integers
+//! `0x` hex strings of up to 64 digits (two's complement for `i256`).
+//!
+//! `bytes` and `bytesn` take base64 in a `base64` field instead of `value`,
+//! e.g. `{"type": "bytes", "base64": "3q2+7w=="}`. Hex needs its `0x` prefix,
+//! so that a string is never read as both.
+//!
+//! Element types of `vec` and `map` are either a type name or a type
+//! annotation without a value, e.g. `{"type": "bytesn", "length": 32}`.
+//! Map values may be given as an object or as an array of `[key, value]`
+//! pairs, which also allows non-string keys.
 //!
 //! Bare values (without type annotation) still work:
 //! - Numbers → `i128`
 //! - Strings → `Symbol`
 //! - Booleans → `Bool`
 
+use base64::Engine;
 use serde_json::Value;
-use soroban_sdk::{Env, Map, String as SorobanString, Symbol, TryFromVal, Val, Vec as SorobanVec};
+use soroban_env_host::xdr::{Duration, Int256Parts, ScAddress, ScVal, TimePoint, UInt256Parts};
+use soroban_sdk::{
+    Bytes, Env, Map, String as SorobanString, Symbol, TryFromVal, Val, Vec as SorobanVec,
+};
+use std::str::FromStr;
 use thiserror::Error;
 use tracing::{debug, warn};
 
@@ -38,7 +64,7 @@ pub enum ArgumentParseError {
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
 
-    #[error("Unsupported type: {0}. Supported types: u32, i32, u64, i64, u128, i128, bool, string, symbol, option, tuple")]
+    #[error("Unsupported type: {0}. Supported types: u32, i32, u64, i64, u128, i128, u256, i256, bool, string, symbol, timepoint, duration, address, bytes, bytesn, vec, map, option, tuple")]
     UnsupportedType(String),
 
     #[error("Failed to convert value: {0}")]
@@ -60,6 +86,13 @@ pub enum ArgumentParseError {
         min: String,
         max: String,
     },
+
+    #[error("Parameter '{name}' ({type_name}): {reason}")]
+    InvalidParameter {
+        name: String,
+        type_name: String,
+        reason: String,
+    },
 }
 
 impl From<serde_json::Error> for ArgumentParseError {
@@ -68,6 +101,9 @@ impl From<serde_json::Error> for ArgumentParseError {
     }
 }
 
+/// Keys besides `type` and `value` that a type annotation may carry
+const ANNOTATION_MODIFIERS: &[&str] = &["arity", "length", "element", "key_type", "value_type"];
+
 /// Argument parser for converting JSON to Soroban values
 pub struct ArgumentParser {
     env: Env,
@@ -108,6 +144,14 @@ impl ArgumentParser {
         self.parse_value(&value)
     }
 
+    /// Parse a single JSON value into exactly one Soroban value
+    ///
+    /// Unlike [`parse_args_string`](Self::parse_args_string), a top-level
+    /// array is not spread into separate arguments but becomes one `Vec`.
+    pub fn parse_single_value(&self, value: &Value) -> Result<Val, ArgumentParseError> {
+        self.json_to_soroban_val(value)
+    }
+
     /// Parse a JSON value into a Vec of Soroban values
     ///
     /// If the JSON is an array, each element becomes a separate argument.
@@ -150,13 +194,23 @@ impl ArgumentParser {
         }
     }
 
-    /// Check if a JSON value is a type annotation object `{"type": "...", "value": ...}`
-    fn is_typed_annotation(&self, value: &Value) -> bool {
+    /// Check if a JSON value is a type annotation object `{"type": "...", "value": ...}`,
+    /// or `{"type": "bytes", "base64": "..."}`
+    pub(crate) fn is_typed_annotation(&self, value: &Value) -> bool {
         if let Value::Object(obj) = value {
-            (obj.len() == 2 || (obj.len() == 3 && obj.contains_key("arity")))
-                && obj.contains_key("type")
-                && obj.contains_key("value")
+            let base64 = matches!(
+                obj.get("type").and_then(Value::as_str),
+                Some("bytes" | "bytesn")
+            ) && obj.contains_key("base64");
+            obj.contains_key("type")
+                && (obj.contains_key("value") || base64)
                 && obj["type"].is_string()
+                && obj.keys().all(|k| {
+                    k == "type"
+                        || k == "value"
+                        || (base64 && k == "base64")
+                        || ANNOTATION_MODIFIERS.contains(&k.as_str())
+                })
         } else {
             false
         }
@@ -172,7 +226,7 @@ impl ArgumentParser {
             ArgumentParseError::InvalidArgument("Type field must be a string".to_string())
         })?;
 
-        let val = &obj["value"];
+        let val = obj.get("value").unwrap_or(&Value::Null);
 
         match type_name {
             "u32" => self.convert_u32(val),
@@ -181,9 +235,18 @@ impl ArgumentParser {
             "i64" => self.convert_i64(val),
             "u128" => self.convert_u128(val),
             "i128" => self.convert_i128(val),
+            "u256" => self.convert_u256(val),
+            "i256" => self.convert_i256(val),
             "bool" => self.convert_bool(val),
             "string" => self.convert_string(val),
             "symbol" => self.convert_symbol(val),
+            "timepoint" => self.convert_timepoint(val),
+            "duration" => self.convert_duration(val),
+            "address" => self.convert_address(val),
+            "bytes" => self.convert_bytes(obj),
+            "bytesn" => self.convert_bytesn(obj),
+            "vec" => self.convert_vec(val, obj),
+            "map" => self.convert_map(val, obj),
             "option" => self.convert_option(val),
             "tuple" => self.convert_tuple(val, obj),
             other => Err(ArgumentParseError::UnsupportedType(other.to_string())),
@@ -264,34 +327,100 @@ impl ArgumentParser {
         })
     }
 
-    /// Convert a JSON number to u128 Val
+    /// Convert a JSON number or decimal string to u128 Val
     fn convert_u128(&self, value: &Value) -> Result<Val, ArgumentParseError> {
-        let n = value
-            .as_u64()
-            .ok_or_else(|| ArgumentParseError::TypeMismatch {
-                expected: "u128 (non-negative integer)".to_string(),
-                actual: format!("{}", value),
-            })?;
+        let n = match value {
+            Value::String(s) => s.parse::<u128>().ok(),
+            _ => value.as_u64().map(u128::from),
+        }
+        .ok_or_else(|| ArgumentParseError::TypeMismatch {
+            expected: "u128 (non-negative integer or decimal string)".to_string(),
+            actual: format!("{}", value),
+        })?;
 
-        Val::try_from_val(&self.env, &(n as u128)).map_err(|e| {
+        Val::try_from_val(&self.env, &n).map_err(|e| {
             ArgumentParseError::ConversionError(format!("Failed to convert u128 to Val: {:?}", e))
         })
     }
 
-    /// Convert a JSON number to i128 Val
+    /// Convert a JSON number or decimal string to i128 Val
     fn convert_i128(&self, value: &Value) -> Result<Val, ArgumentParseError> {
-        let n = value
-            .as_i64()
-            .ok_or_else(|| ArgumentParseError::TypeMismatch {
-                expected: "i128 (integer)".to_string(),
-                actual: format!("{}", value),
-            })?;
+        let n = match value {
+            Value::String(s) => s.parse::<i128>().ok(),
+            _ => value.as_i64().map(i128::from),
+        }
+        .ok_or_else(|| ArgumentParseError::TypeMismatch {
+            expected: "i128 (integer or decimal string)".to_string(),
+            actual: format!("{}", value),
+        })?;
 
-        Val::try_from_val(&self.env, &(n as i128)).map_err(|e| {
+        Val::try_from_val(&self.env, &n).map_err(|e| {
             ArgumentParseError::ConversionError(format!("Failed to convert i128 to Val: {:?}", e))
         })
     }
 
+    /// Convert a JSON number, decimal string or `0x` hex string to a U256 Val
+    fn convert_u256(&self, value: &Value) -> Result<Val, ArgumentParseError> {
+        let [hi_hi, hi_lo, lo_hi, lo_lo] = match value {
+            Value::String(s) => match s.strip_prefix("0x") {
+                Some(hex) => parse_hex_256(hex),
+                None => parse_decimal_256(s),
+            },
+            _ => value.as_u64().map(|n| [0, 0, 0, n]),
+        }
+        .ok_or_else(|| ArgumentParseError::TypeMismatch {
+            expected: "u256 (non-negative integer, decimal string or 0x hex string)".to_string(),
+            actual: format!("{}", value),
+        })?;
+
+        let parts = UInt256Parts {
+            hi_hi,
+            hi_lo,
+            lo_hi,
+            lo_lo,
+        };
+        Val::try_from_val(&self.env, &ScVal::U256(parts)).map_err(|e| {
+            ArgumentParseError::ConversionError(format!("Failed to convert u256 to Val: {:?}", e))
+        })
+    }
+
+    /// Convert a JSON number, decimal string or `0x` hex string (two's
+    /// complement) to an I256 Val
+    fn convert_i256(&self, value: &Value) -> Result<Val, ArgumentParseError> {
+        const SIGN: u64 = 1 << 63;
+        let [hi_hi, hi_lo, lo_hi, lo_lo] = match value {
+            Value::String(s) => {
+                if let Some(hex) = s.strip_prefix("0x") {
+                    parse_hex_256(hex)
+                } else if let Some(magnitude) = s.strip_prefix('-') {
+                    parse_decimal_256(magnitude)
+                        .filter(|words| *words <= [SIGN, 0, 0, 0])
+                        .map(negate_256)
+                } else {
+                    parse_decimal_256(s).filter(|words| words[0] < SIGN)
+                }
+            }
+            _ => value.as_i64().map(|n| {
+                let fill = if n < 0 { u64::MAX } else { 0 };
+                [fill, fill, fill, n as u64]
+            }),
+        }
+        .ok_or_else(|| ArgumentParseError::TypeMismatch {
+            expected: "i256 (integer, decimal string or 0x hex string)".to_string(),
+            actual: format!("{}", value),
+        })?;
+
+        let parts = Int256Parts {
+            hi_hi: hi_hi as i64,
+            hi_lo,
+            lo_hi,
+            lo_lo,
+        };
+        Val::try_from_val(&self.env, &ScVal::I256(parts)).map_err(|e| {
+            ArgumentParseError::ConversionError(format!("Failed to convert i256 to Val: {:?}", e))
+        })
+    }
+
     /// Convert a JSON boolean to Bool Val
     fn convert_bool(&self, value: &Value) -> Result<Val, ArgumentParseError> {
         let b = value
@@ -336,6 +465,244 @@ impl ArgumentParser {
         })
     }
 
+    /// Convert a JSON number of seconds since the epoch to a Timepoint Val
+    fn convert_timepoint(&self, value: &Value) -> Result<Val, ArgumentParseError> {
+        let n = value
+            .as_u64()
+            .ok_or_else(|| ArgumentParseError::TypeMismatch {
+                expected: "timepoint (non-negative integer)".to_string(),
+                actual: format!("{}", value),
+            })?;
+
+        Val::try_from_val(&self.env, &ScVal::Timepoint(TimePoint(n))).map_err(|e| {
+            ArgumentParseError::ConversionError(format!(
+                "Failed to convert Timepoint to Val: {:?}",
+                e
+            ))
+        })
+    }
+
+    /// Convert a JSON number of seconds to a Duration Val
+    fn convert_duration(&self, value: &Value) -> Result<Val, ArgumentParseError> {
+        let n = value
+            .as_u64()
+            .ok_or_else(|| ArgumentParseError::TypeMismatch {
+                expected: "duration (non-negative integer)".to_string(),
+                actual: format!("{}", value),
+            })?;
+
+        Val::try_from_val(&self.env, &ScVal::Duration(Duration(n))).map_err(|e| {
+            ArgumentParseError::ConversionError(format!(
+                "Failed to convert Duration to Val: {:?}",
+                e
+            ))
+        })
+    }
+
+    /// Convert a `G...` or `C...` strkey to an Address Val
+    fn convert_address(&self, value: &Value) -> Result<Val, ArgumentParseError> {
+        let s = value
+            .as_str()
+            .ok_or_else(|| ArgumentParseError::TypeMismatch {
+                expected: "address (G... or C... strkey)".to_string(),
+                actual: format!("{}", value),
+            })?;
+
+        let address = ScAddress::from_str(s).map_err(|_| {
+            ArgumentParseError::InvalidArgument(format!("Invalid address strkey: {}", s))
+        })?;
+        Val::try_from_val(&self.env, &ScVal::Address(address)).map_err(|e| {
+            ArgumentParseError::ConversionError(format!(
+                "Failed to convert Address to Val: {:?}",
+                e
+            ))
+        })
+    }
+
+    /// Convert a `0x` hex or base64 JSON string to a Bytes Val
+    fn convert_bytes(
+        &self,
+        obj: &serde_json::Map<String, Value>,
+    ) -> Result<Val, ArgumentParseError> {
+        let bytes = self.decode_bytes(obj)?;
+        Ok(Bytes::from_slice(&self.env, &bytes).into())
+    }
+
+    /// Convert a `0x` hex or base64 JSON string to a fixed-length BytesN Val
+    fn convert_bytesn(
+        &self,
+        obj: &serde_json::Map<String, Value>,
+    ) -> Result<Val, ArgumentParseError> {
+        let length = obj.get("length").and_then(Value::as_u64).ok_or_else(|| {
+            ArgumentParseError::InvalidArgument(
+                "bytesn requires a numeric \"length\" field".to_string(),
+            )
+        })?;
+
+        let bytes = self.decode_bytes(obj)?;
+        if bytes.len() as u64 != length {
+            return Err(ArgumentParseError::TypeMismatch {
+                expected: format!("bytesn of length {}", length),
+                actual: format!("{} bytes", bytes.len()),
+            });
+        }
+
+        Ok(Bytes::from_slice(&self.env, &bytes).into())
+    }
+
+    /// Decode the bytes of a `bytes` or `bytesn` annotation: a `0x` hex
+    /// string in `value`, or a base64 string in `base64`
+    fn decode_bytes(
+        &self,
+        obj: &serde_json::Map<String, Value>,
+    ) -> Result<Vec<u8>, ArgumentParseError> {
+        let (value, base64) = match (obj.get("value"), obj.get("base64")) {
+            (Some(_), Some(_)) => {
+                return Err(ArgumentParseError::InvalidArgument(
+                    "Bytes take either a \"value\" or a \"base64\" field, not both".to_string(),
+                ))
+            }
+            (None, Some(encoded)) => (encoded, true),
+            (value, None) => (value.unwrap_or(&Value::Null), false),
+        };
+        let s = value
+            .as_str()
+            .ok_or_else(|| ArgumentParseError::TypeMismatch {
+                expected: if base64 {
+                    "bytes (base64 string)".to_string()
+                } else {
+                    "bytes (0x hex string)".to_string()
+                },
+                actual: format!("{}", value),
+            })?;
+
+        if base64 {
+            return base64::engine::general_purpose::STANDARD
+                .decode(s)
+                .map_err(|_| {
+                    ArgumentParseError::InvalidArgument(format!("Invalid base64 bytes: {}", s))
+                });
+        }
+        let hex = s.strip_prefix("0x").ok_or_else(|| {
+            ArgumentParseError::InvalidArgument(format!(
+                "Bytes must be a 0x hex string, or base64 in a \"base64\" field: {}",
+                s
+            ))
+        })?;
+        decode_hex(hex)
+            .ok_or_else(|| ArgumentParseError::InvalidArgument(format!("Invalid hex bytes: {}", s)))
+    }
+
+    /// Convert a JSON array to a Soroban Vec, applying the `element` type to each item
+    fn convert_vec(
+        &self,
+        value: &Value,
+        obj: &serde_json::Map<String, Value>,
+    ) -> Result<Val, ArgumentParseError> {
+        let arr = value
+            .as_array()
+            .ok_or_else(|| ArgumentParseError::TypeMismatch {
+                expected: "array for vec".to_string(),
+                actual: format!("{}", value),
+            })?;
+
+        let Some(element) = obj.get("element") else {
+            return self.array_to_soroban_vec(arr);
+        };
+
+        let mut soroban_vec = SorobanVec::<Val>::new(&self.env);
+        for (i, item) in arr.iter().enumerate() {
+            let val = self.convert_with_type(element, item).map_err(|e| {
+                ArgumentParseError::ConversionError(format!("Vec element {}: {}", i, e))
+            })?;
+            soroban_vec.push_back(val);
+        }
+
+        Ok(soroban_vec.into())
+    }
+
+    /// Convert a JSON object or array of `[key, value]` pairs to a Soroban Map
+    ///
+    /// Keys and values are converted with the optional `key_type` and
+    /// `value_type` fields, and as bare values otherwise.
+    fn convert_map(
+        &self,
+        value: &Value,
+        obj: &serde_json::Map<String, Value>,
+    ) -> Result<Val, ArgumentParseError> {
+        let key_type = obj.get("key_type");
+        let value_type = obj.get("value_type");
+
+        let entries: Vec<(Value, &Value)> = match value {
+            Value::Object(fields) => fields
+                .iter()
+                .map(|(k, v)| (object_key_to_json(k, key_type), v))
+                .collect(),
+            Value::Array(pairs) => pairs
+                .iter()
+                .map(|pair| match pair.as_array().map(Vec::as_slice) {
+                    Some([k, v]) => Ok((k.clone(), v)),
+                    _ => Err(ArgumentParseError::TypeMismatch {
+                        expected: "[key, value] pair".to_string(),
+                        actual: format!("{}", pair),
+                    }),
+                })
+                .collect::<Result<_, _>>()?,
+            _ => {
+                return Err(ArgumentParseError::TypeMismatch {
+                    expected: "object or array of [key, value] pairs for map".to_string(),
+                    actual: format!("{}", value),
+                })
+            }
+        };
+
+        let mut soroban_map = Map::<Val, Val>::new(&self.env);
+        for (key, val) in entries {
+            let key_val = match key_type {
+                Some(t) => self.convert_with_type(t, &key),
+                None => self.json_to_soroban_val(&key),
+            }
+            .map_err(|e| ArgumentParseError::ConversionError(format!("Map key {}: {}", key, e)))?;
+            let val_val = match value_type {
+                Some(t) => self.convert_with_type(t, val),
+                None => self.json_to_soroban_val(val),
+            }
+            .map_err(|e| {
+                ArgumentParseError::ConversionError(format!("Map value for key {}: {}", key, e))
+            })?;
+            soroban_map.set(key_val, val_val);
+        }
+
+        Ok(soroban_map.into())
+    }
+
+    /// Convert a JSON value with an element type given as a type name or a
+    /// value-less annotation. Values that carry their own annotation win.
+    fn convert_with_type(
+        &self,
+        type_spec: &Value,
+        value: &Value,
+    ) -> Result<Val, ArgumentParseError> {
+        if self.is_typed_annotation(value) {
+            return self.parse_typed_value(value);
+        }
+
+        let mut annotation = match type_spec {
+            Value::String(_) => {
+                serde_json::Map::from_iter([("type".to_string(), type_spec.clone())])
+            }
+            Value::Object(obj) if obj.get("type").is_some_and(Value::is_string) => obj.clone(),
+            other => {
+                return Err(ArgumentParseError::InvalidArgument(format!(
+                    "Element type must be a type name or annotation: {}",
+                    other
+                )))
+            }
+        };
+        annotation.insert("value".to_string(), value.clone());
+        self.parse_typed_value(&Value::Object(annotation))
+    }
+
     /// Convert a JSON value to an Option Val (None if null, Some(T) otherwise)
     fn convert_option(&self, value: &Value) -> Result<Val, ArgumentParseError> {
         if value.is_null() {
@@ -513,6 +880,89 @@ impl ArgumentParser {
     }
 }
 
+/// Turn a JSON object key into the JSON value converted with `key_type`
+///
+/// Object keys are always strings, so keys of numeric types are re-read as numbers.
+fn object_key_to_json(key: &str, key_type: Option<&Value>) -> Value {
+    let numeric = matches!(
+        key_type.and_then(Value::as_str),
+        Some(
+            "u32"
+                | "i32"
+                | "u64"
+                | "i64"
+                | "u128"
+                | "i128"
+                | "u256"
+                | "i256"
+                | "timepoint"
+                | "duration"
+        )
+    );
+    if numeric {
+        if let Ok(number @ Value::Number(_)) = serde_json::from_str(key) {
+            return number;
+        }
+    }
+    Value::String(key.to_string())
+}
+
+/// Parse up to 64 hex digits as a 256-bit integer, most significant word first
+fn parse_hex_256(hex: &str) -> Option<[u64; 4]> {
+    if hex.is_empty() || hex.len() > 64 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let padded = format!("{:0>64}", hex);
+    let mut words = [0u64; 4];
+    for (i, word) in words.iter_mut().enumerate() {
+        *word = u64::from_str_radix(&padded[i * 16..(i + 1) * 16], 16).ok()?;
+    }
+    Some(words)
+}
+
+/// Parse a decimal string as a 256-bit integer, most significant word first
+fn parse_decimal_256(decimal: &str) -> Option<[u64; 4]> {
+    if decimal.is_empty() {
+        return None;
+    }
+    let mut words = [0u64; 4];
+    for digit in decimal.chars() {
+        let mut carry = digit.to_digit(10)? as u128;
+        for word in words.iter_mut().rev() {
+            let next = *word as u128 * 10 + carry;
+            *word = next as u64;
+            carry = next >> 64;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(words)
+}
+
+/// Two's complement negation of a 256-bit integer
+fn negate_256(words: [u64; 4]) -> [u64; 4] {
+    let mut negated = words.map(|word| !word);
+    for word in negated.iter_mut().rev() {
+        let (sum, overflow) = word.overflowing_add(1);
+        *word = sum;
+        if !overflow {
+            break;
+        }
+    }
+    negated
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -833,6 +1283,103 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_typed_u256() {
+        let parser = create_parser();
+        let expected = ScVal::U256(UInt256Parts {
+            hi_hi: 0,
+            hi_lo: 0,
+            lo_hi: 1,
+            lo_lo: 0,
+        });
+        for json in [
+            r#"{"type": "u256", "value": "18446744073709551616"}"#,
+            r#"{"type": "u256", "value": "0x10000000000000000"}"#,
+        ] {
+            assert_eq!(parse_one(&parser, json).unwrap(), expected, "{}", json);
+        }
+        let max = format!(r#"{{"type": "u256", "value": "0x{}"}}"#, "f".repeat(64));
+        assert!(parse_one(&parser, &max).is_ok());
+        assert!(matches!(
+            parse_one(&parser, r#"{"type": "u256", "value": 7}"#),
+            Ok(ScVal::U256(UInt256Parts { lo_lo: 7, .. }))
+        ));
+
+        for invalid in [
+            r#"{"type": "u256", "value": -1}"#,
+            r#"{"type": "u256", "value": "-1"}"#,
+            r#"{"type": "u256", "value": "0x"}"#,
+            r#"{"type": "u256", "value": "12ab"}"#,
+        ] {
+            assert!(matches!(
+                parse_one(&parser, invalid),
+                Err(ArgumentParseError::TypeMismatch { .. })
+            ));
+        }
+        // 2^256 overflows, in decimal and in hex
+        let too_big = r#"{"type": "u256", "value": "115792089237316195423570985008687907853269984665640564039457584007913129639936"}"#;
+        assert!(parse_one(&parser, too_big).is_err());
+        let too_long = format!(r#"{{"type": "u256", "value": "0x1{}"}}"#, "0".repeat(64));
+        assert!(parse_one(&parser, &too_long).is_err());
+    }
+
+    #[test]
+    fn test_typed_i256() {
+        let parser = create_parser();
+        let minus_one = ScVal::I256(Int256Parts {
+            hi_hi: -1,
+            hi_lo: u64::MAX,
+            lo_hi: u64::MAX,
+            lo_lo: u64::MAX,
+        });
+        for json in [
+            r#"{"type": "i256", "value": -1}"#,
+            r#"{"type": "i256", "value": "-1"}"#,
+        ] {
+            assert_eq!(parse_one(&parser, json).unwrap(), minus_one, "{}", json);
+        }
+        let hex = format!(r#"{{"type": "i256", "value": "0x{}"}}"#, "f".repeat(64));
+        assert_eq!(parse_one(&parser, &hex).unwrap(), minus_one);
+
+        // The range is -2^255 ..= 2^255 - 1
+        let min = r#"{"type": "i256", "value": "-57896044618658097711785492504343953926634992332820282019728792003956564819968"}"#;
+        assert_eq!(
+            parse_one(&parser, min).unwrap(),
+            ScVal::I256(Int256Parts {
+                hi_hi: i64::MIN,
+                hi_lo: 0,
+                lo_hi: 0,
+                lo_lo: 0,
+            })
+        );
+        let above_max = r#"{"type": "i256", "value": "57896044618658097711785492504343953926634992332820282019728792003956564819968"}"#;
+        assert!(parse_one(&parser, above_max).is_err());
+    }
+
+    #[test]
+    fn test_typed_timepoint_and_duration() {
+        let parser = create_parser();
+        assert_eq!(
+            parse_one(&parser, r#"{"type": "timepoint", "value": 1700000000}"#).unwrap(),
+            ScVal::Timepoint(TimePoint(1700000000))
+        );
+        assert_eq!(
+            parse_one(&parser, r#"{"type": "duration", "value": 3600}"#).unwrap(),
+            ScVal::Duration(Duration(3600))
+        );
+        assert!(matches!(
+            parse_one(&parser, r#"{"type": "duration", "value": -1}"#),
+            Err(ArgumentParseError::TypeMismatch { .. })
+        ));
+
+        // Object keys are re-read as numbers
+        let json = r#"{"type": "map", "key_type": "timepoint", "value_type": "bool", "value": {"60": true}}"#;
+        match parse_one(&parser, json).unwrap() {
+            ScVal::Map(Some(map)) => assert_eq!(map[0].key, ScVal::Timepoint(TimePoint(60))),
+            other => panic!("Expected map, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_typed_bool() {
         let parser = create_parser();
@@ -871,7 +1418,7 @@ mod tests {
     #[test]
     fn test_typed_unsupported_type() {
         let parser = create_parser();
-        let result = parser.parse_args_string(r#"[{"type": "bytes", "value": "abc"}]"#);
+        let result = parser.parse_args_string(r#"[{"type": "f64", "value": 1.5}]"#);
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(
@@ -991,6 +1538,167 @@ mod tests {
         assert!(err.to_string().contains("arity mismatch"));
     }
 
+    // ── Addresses, bytes and typed collections ───────────────────────
+
+    const ACCOUNT: &str = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+    const CONTRACT: &str = "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABSC4";
+
+    fn parse_one(parser: &ArgumentParser, json: &str) -> Result<ScVal, ArgumentParseError> {
+        let val = parser.parse_single_value(&serde_json::from_str(json).unwrap())?;
+        Ok(ScVal::try_from_val(&parser.env, &val).unwrap())
+    }
+
+    #[test]
+    fn test_typed_address_account_and_contract() {
+        let parser = create_parser();
+        for strkey in [ACCOUNT, CONTRACT] {
+            let json = format!(r#"{{"type": "address", "value": "{}"}}"#, strkey);
+            match parse_one(&parser, &json).unwrap() {
+                ScVal::Address(addr) => assert_eq!(addr.to_string(), strkey),
+                other => panic!("Expected address, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_typed_address_invalid() {
+        let parser = create_parser();
+        let result = parse_one(&parser, r#"{"type": "address", "value": "GNOTANADDRESS"}"#);
+        assert!(matches!(
+            result,
+            Err(ArgumentParseError::InvalidArgument(_))
+        ));
+
+        let result = parse_one(&parser, r#"{"type": "address", "value": 42}"#);
+        assert!(matches!(
+            result,
+            Err(ArgumentParseError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_typed_bytes_hex_and_base64() {
+        let parser = create_parser();
+        for json in [
+            r#"{"type": "bytes", "value": "0xdeadbeef"}"#,
+            r#"{"type": "bytes", "base64": "3q2+7w=="}"#,
+            r#"{"type": "bytesn", "length": 4, "base64": "3q2+7w=="}"#,
+        ] {
+            match parse_one(&parser, json).unwrap() {
+                ScVal::Bytes(bytes) => assert_eq!(bytes.as_slice(), &[0xde, 0xad, 0xbe, 0xef]),
+                other => panic!("Expected bytes, got {:?}", other),
+            }
+        }
+
+        // Valid as both hex and base64, so neither is guessed
+        for json in [
+            r#"{"type": "bytes", "value": "abcd"}"#,
+            r#"{"type": "bytes", "value": "0xabc"}"#,
+            r#"{"type": "bytes", "base64": "not base64!"}"#,
+            r#"{"type": "bytes", "value": "0xab", "base64": "qw=="}"#,
+        ] {
+            assert!(
+                matches!(
+                    parse_one(&parser, json),
+                    Err(ArgumentParseError::InvalidArgument(_))
+                ),
+                "{} should be rejected",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn test_typed_bytesn_length() {
+        let parser = create_parser();
+        let hash = format!("0x{}", "00".repeat(32));
+        let json = format!(r#"{{"type": "bytesn", "length": 32, "value": "{}"}}"#, hash);
+        assert!(matches!(parse_one(&parser, &json), Ok(ScVal::Bytes(_))));
+
+        let json = format!(r#"{{"type": "bytesn", "length": 16, "value": "{}"}}"#, hash);
+        assert!(matches!(
+            parse_one(&parser, &json),
+            Err(ArgumentParseError::TypeMismatch { .. })
+        ));
+
+        let result = parse_one(&parser, r#"{"type": "bytesn", "value": "00"}"#);
+        assert!(matches!(
+            result,
+            Err(ArgumentParseError::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_typed_vec_elements() {
+        let parser = create_parser();
+        let json = format!(
+            r#"{{"type": "vec", "element": "address", "value": ["{}", "{}"]}}"#,
+            ACCOUNT, CONTRACT
+        );
+        match parse_one(&parser, &json).unwrap() {
+            ScVal::Vec(Some(items)) => {
+                assert_eq!(items.len(), 2);
+                assert!(items.iter().all(|v| matches!(v, ScVal::Address(_))));
+            }
+            other => panic!("Expected vec, got {:?}", other),
+        }
+
+        let json =
+            r#"{"type": "vec", "element": {"type": "bytesn", "length": 2}, "value": ["0xabcd"]}"#;
+        assert!(parse_one(&parser, json).is_ok());
+
+        let result = parse_one(
+            &parser,
+            r#"{"type": "vec", "element": "u32", "value": [1, -1]}"#,
+        );
+        assert!(matches!(
+            result,
+            Err(ArgumentParseError::ConversionError(_))
+        ));
+    }
+
+    #[test]
+    fn test_typed_map_object_and_pairs() {
+        let parser = create_parser();
+        let json = r#"{"type": "map", "key_type": "u32", "value_type": "bool", "value": {"1": true, "2": false}}"#;
+        match parse_one(&parser, json).unwrap() {
+            ScVal::Map(Some(map)) => {
+                assert_eq!(map.len(), 2);
+                assert_eq!(map[0].key, ScVal::U32(1));
+                assert_eq!(map[0].val, ScVal::Bool(true));
+            }
+            other => panic!("Expected map, got {:?}", other),
+        }
+
+        let json = format!(
+            r#"{{"type": "map", "key_type": "address", "value_type": "i128", "value": [["{}", 100]]}}"#,
+            ACCOUNT
+        );
+        match parse_one(&parser, &json).unwrap() {
+            ScVal::Map(Some(map)) => assert!(matches!(map[0].key, ScVal::Address(_))),
+            other => panic!("Expected map, got {:?}", other),
+        }
+
+        let result = parse_one(&parser, r#"{"type": "map", "value": [[1, 2, 3]]}"#);
+        assert!(matches!(
+            result,
+            Err(ArgumentParseError::TypeMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_scval_map_rendering_round_trips() {
+        use crate::utils::scval::scval_to_json;
+
+        let parser = create_parser();
+        let json = r#"{"type": "map", "value": [[{"type": "u32", "value": 1}, "one"]]}"#;
+        let val = parse_one(&parser, json).unwrap();
+        assert_eq!(
+            scval_to_json(&val),
+            serde_json::from_str::<Value>(json).unwrap()
+        );
+    }
+
     #[test]
     fn test_bare_null_is_void() {
         let parser = create_parser();
@@ -1,7 +1,10 @@
 pub mod arguments;
+pub mod scval;
 pub mod source_map;
+pub mod spec;
 pub mod wasm;
 
 pub use arguments::ArgumentParser;
 pub use source_map::{SourceLocation, SourceMap};
+pub use spec::{ContractErrorInfo, ContractSpec};
 pub use wasm::{get_module_info, parse_functions, ModuleInfo};
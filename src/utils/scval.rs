@@ -0,0 +1,299 @@
+//! Soroban value to JSON rendering
+//!
+//! This is the inverse of [`ArgumentParser`](super::arguments::ArgumentParser):
+//! host values are rendered in the same JSON format the parser accepts, so
+//! output (storage exports, results) can be fed back in as input. Errors and
+//! ledger-internal values, which contracts cannot take as arguments, are
+//! rendered for display only.
+//!
+//! - Symbols → bare strings
+//! - `i128` values that fit in 64 bits → bare numbers
+//! - Booleans → booleans, void → `null`
+//! - Vecs → arrays, Maps with symbol keys → objects
+//! - Everything else → type annotations such as `{"type": "u32", "value": 7}`
+
+use serde_json::{json, Value};
+use soroban_env_host::xdr::{ScMap, ScVal};
+
+/// Render a Soroban value as JSON in the `ArgumentParser` format.
+pub fn scval_to_json(val: &ScVal) -> Value {
+    match val {
+        ScVal::Bool(b) => Value::Bool(*b),
+        ScVal::Void => Value::Null,
+        ScVal::U32(n) => typed("u32", json!(n)),
+        ScVal::I32(n) => typed("i32", json!(n)),
+        ScVal::U64(n) => typed("u64", json!(n)),
+        ScVal::I64(n) => typed("i64", json!(n)),
+        ScVal::Timepoint(t) => typed("timepoint", json!(t.0)),
+        ScVal::Duration(d) => typed("duration", json!(d.0)),
+        ScVal::U128(parts) => {
+            let n = ((parts.hi as u128) << 64) | parts.lo as u128;
+            match u64::try_from(n) {
+                Ok(small) => typed("u128", json!(small)),
+                Err(_) => typed("u128", json!(n.to_string())),
+            }
+        }
+        ScVal::I128(parts) => {
+            let n = ((parts.hi as i128) << 64) | parts.lo as i128;
+            match i64::try_from(n) {
+                Ok(small) => json!(small),
+                Err(_) => typed("i128", json!(n.to_string())),
+            }
+        }
+        ScVal::U256(parts) => typed(
+            "u256",
+            json!(format!(
+                "0x{:016x}{:016x}{:016x}{:016x}",
+                parts.hi_hi, parts.hi_lo, parts.lo_hi, parts.lo_lo
+            )),
+        ),
+        ScVal::I256(parts) => typed(
+            "i256",
+            json!(format!(
+                "0x{:016x}{:016x}{:016x}{:016x}",
+                parts.hi_hi, parts.hi_lo, parts.lo_hi, parts.lo_lo
+            )),
+        ),
+        ScVal::Bytes(bytes) => typed("bytes", json!(to_hex(bytes.as_slice()))),
+        ScVal::String(s) => typed("string", json!(s.to_utf8_string_lossy())),
+        ScVal::Symbol(s) => Value::String(s.to_utf8_string_lossy()),
+        ScVal::Vec(items) => Value::Array(
+            items
+                .as_ref()
+                .map(|v| v.iter().map(scval_to_json).collect())
+                .unwrap_or_default(),
+        ),
+        ScVal::Map(map) => map.as_ref().map(map_to_json).unwrap_or(json!({})),
+        ScVal::Address(addr) => typed("address", json!(addr.to_string())),
+        ScVal::Error(e) => typed("error", json!(format!("{:?}", e))),
+        other => Value::String(format!("{:?}", other)),
+    }
+}
+
+/// Render a Soroban value as a single-line string.
+///
+/// Plain strings (symbols) are returned as-is; everything else is compact
+/// JSON text, which [`ArgumentParser`](super::arguments::ArgumentParser)
+/// callers such as initial storage decode back into the same value. Symbols
+/// that would read as JSON, such as `123` or `true`, are written as a
+/// `symbol` annotation instead.
+pub fn scval_to_string(val: &ScVal) -> String {
+    match scval_to_json(val) {
+        Value::String(s) if serde_json::from_str::<Value>(&s).is_ok() => {
+            typed("symbol", Value::String(s)).to_string()
+        }
+        Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+fn map_to_json(map: &ScMap) -> Value {
+    if map
+        .iter()
+        .all(|entry| matches!(entry.key, ScVal::Symbol(_)))
+    {
+        let obj = map
+            .iter()
+            .map(|entry| (scval_to_string(&entry.key), scval_to_json(&entry.val)))
+            .collect();
+        Value::Object(obj)
+    } else {
+        let pairs = map
+            .iter()
+            .map(|entry| json!([scval_to_json(&entry.key), scval_to_json(&entry.val)]))
+            .collect();
+        typed("map", Value::Array(pairs))
+    }
+}
+
+fn typed(type_name: &str, value: Value) -> Value {
+    json!({ "type": type_name, "value": value })
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("0x{}", digits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{Int128Parts, ScMapEntry, ScSymbol, ScVec};
+
+    fn symbol(s: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(s.try_into().unwrap()))
+    }
+
+    #[test]
+    fn test_symbol_renders_bare() {
+        assert_eq!(scval_to_json(&symbol("count")), json!("count"));
+        assert_eq!(scval_to_string(&symbol("count")), "count");
+    }
+
+    #[test]
+    fn test_integers_render_typed() {
+        assert_eq!(
+            scval_to_json(&ScVal::U32(7)),
+            json!({"type": "u32", "value": 7})
+        );
+        assert_eq!(
+            scval_to_json(&ScVal::I64(-3)),
+            json!({"type": "i64", "value": -3})
+        );
+    }
+
+    #[test]
+    fn test_i128_renders_bare_when_small() {
+        let small = ScVal::I128(Int128Parts { hi: 0, lo: 1000 });
+        assert_eq!(scval_to_json(&small), json!(1000));
+
+        let negative = ScVal::I128(Int128Parts {
+            hi: -1,
+            lo: u64::MAX,
+        });
+        assert_eq!(scval_to_json(&negative), json!(-1));
+
+        let large = ScVal::I128(Int128Parts { hi: 1, lo: 0 });
+        assert_eq!(
+            scval_to_json(&large),
+            json!({"type": "i128", "value": "18446744073709551616"})
+        );
+    }
+
+    #[test]
+    fn test_vec_and_symbol_map() {
+        let vec = ScVal::Vec(Some(ScVec(
+            vec![ScVal::Bool(true), ScVal::Void].try_into().unwrap(),
+        )));
+        assert_eq!(scval_to_json(&vec), json!([true, null]));
+
+        let map = ScVal::Map(Some(ScMap(
+            vec![ScMapEntry {
+                key: symbol("owner"),
+                val: ScVal::U32(1),
+            }]
+            .try_into()
+            .unwrap(),
+        )));
+        assert_eq!(
+            scval_to_json(&map),
+            json!({"owner": {"type": "u32", "value": 1}})
+        );
+    }
+
+    #[test]
+    fn test_non_symbol_map_keys_render_as_pairs() {
+        let map = ScVal::Map(Some(ScMap(
+            vec![ScMapEntry {
+                key: ScVal::U32(1),
+                val: symbol("one"),
+            }]
+            .try_into()
+            .unwrap(),
+        )));
+        assert_eq!(
+            scval_to_json(&map),
+            json!({"type": "map", "value": [[{"type": "u32", "value": 1}, "one"]]})
+        );
+    }
+
+    #[test]
+    fn test_rendered_values_parse_back() {
+        use crate::runtime::executor::decode_storage_json;
+        use crate::utils::ArgumentParser;
+        use soroban_env_host::xdr::{
+            Duration, Int256Parts, ScAddress, ScBytes, ScString, TimePoint, UInt128Parts,
+            UInt256Parts,
+        };
+        use soroban_sdk::{Env, TryFromVal};
+        use std::str::FromStr;
+
+        let env = Env::default();
+        let parser = ArgumentParser::new(env.clone());
+        let values = [
+            ScVal::Bool(false),
+            ScVal::Void,
+            ScVal::U32(7),
+            ScVal::I32(-7),
+            ScVal::U64(u64::MAX),
+            ScVal::I64(-3),
+            ScVal::Timepoint(TimePoint(1700000000)),
+            ScVal::Duration(Duration(3600)),
+            ScVal::U128(UInt128Parts { hi: 0, lo: 5 }),
+            ScVal::U128(UInt128Parts { hi: 1, lo: 5 }),
+            ScVal::I128(Int128Parts { hi: -1, lo: 0 }),
+            ScVal::U256(UInt256Parts {
+                hi_hi: 1,
+                hi_lo: 2,
+                lo_hi: 3,
+                lo_lo: 4,
+            }),
+            ScVal::I256(Int256Parts {
+                hi_hi: -1,
+                hi_lo: u64::MAX,
+                lo_hi: u64::MAX,
+                lo_lo: 0,
+            }),
+            ScVal::Bytes(ScBytes(vec![0xde, 0xad].try_into().unwrap())),
+            ScVal::String(ScString("balance:alice".try_into().unwrap())),
+            symbol("count"),
+            // Symbols that read as JSON
+            symbol("123"),
+            symbol("true"),
+            symbol("null"),
+            ScVal::Address(
+                ScAddress::from_str("CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABSC4")
+                    .unwrap(),
+            ),
+            ScVal::Vec(Some(ScVec(
+                vec![ScVal::U32(1), symbol("42")].try_into().unwrap(),
+            ))),
+            ScVal::Map(Some(ScMap(
+                vec![ScMapEntry {
+                    key: symbol("owner"),
+                    val: ScVal::U32(1),
+                }]
+                .try_into()
+                .unwrap(),
+            ))),
+            ScVal::Map(Some(ScMap(
+                vec![ScMapEntry {
+                    key: ScVal::U32(1),
+                    val: symbol("one"),
+                }]
+                .try_into()
+                .unwrap(),
+            ))),
+        ];
+
+        for value in values {
+            // As arguments
+            let json = scval_to_json(&value);
+            let parsed = parser.parse_single_value(&json).unwrap();
+            assert_eq!(
+                ScVal::try_from_val(&env, &parsed).unwrap(),
+                value,
+                "{}",
+                json
+            );
+
+            // As exported storage entries
+            let text = scval_to_string(&value);
+            let stored = decode_storage_json(&env, &Value::String(text.clone())).unwrap();
+            assert_eq!(
+                ScVal::try_from_val(&env, &stored).unwrap(),
+                value,
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_compact_string_for_typed_values() {
+        assert_eq!(
+            scval_to_string(&ScVal::U32(7)),
+            r#"{"type":"u32","value":7}"#
+        );
+    }
+}
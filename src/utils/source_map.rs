@@ -1,37 +1,321 @@
-/// Source map handling for mapping WASM to source code
-/// This will be implemented in Phase 3
+//! Mapping of WASM instructions to the Rust source they were compiled from
+//!
+//! Debug builds of a contract carry DWARF line tables in `.debug_*` custom
+//! sections. Their addresses count from the start of the code section's
+//! contents; [`SourceMap`] converts from the byte offsets into the whole
+//! binary that [`Instruction`](crate::runtime::Instruction) and the
+//! interpreter use.
+
+use gimli::{ColumnType, Dwarf, EndianSlice, LittleEndian, SectionId};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use wasmparser::{Parser, Payload};
+
+type Reader<'a> = EndianSlice<'a, LittleEndian>;
+
+/// Source locations of a contract's instructions, from its DWARF line
+/// tables
+#[derive(Debug, Clone, Default)]
 pub struct SourceMap {
-    // TODO: Implement source map parsing and lookup
+    /// Offset of the code section's contents in the binary
+    code_start: usize,
+    /// Line table rows by address. `None` ends a sequence, leaving the
+    /// addresses up to the next row unmapped.
+    rows: Vec<(u64, Option<SourceLocation>)>,
 }
 
 impl SourceMap {
+    /// An empty map, knowing no locations
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Read the line tables of a debug build. Returns `None` if the binary
+    /// has none, e.g. because it was built in release mode or stripped.
+    pub fn from_wasm(wasm: &[u8]) -> Option<Self> {
+        let mut code_start = None;
+        let mut sections = HashMap::new();
+        for payload in Parser::new(0).parse_all(wasm) {
+            match payload.ok()? {
+                Payload::CodeSectionStart { range, .. } => code_start = Some(range.start),
+                Payload::CustomSection(reader) if reader.name().starts_with(".debug_") => {
+                    sections.insert(reader.name(), reader.data());
+                }
+                _ => {}
+            }
+        }
+        if !sections.contains_key(".debug_line") {
+            return None;
+        }
+
+        match read_rows(&sections) {
+            Ok(mut rows) if !rows.is_empty() => {
+                // Where one sequence ends at the address another starts,
+                // the start wins
+                rows.sort_by_key(|(address, location)| (*address, location.is_some()));
+                Some(Self {
+                    code_start: code_start?,
+                    rows,
+                })
+            }
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!("Ignoring malformed DWARF debug info: {}", e);
+                None
+            }
+        }
     }
 
-    /// Load source map from WASM custom section
-    pub fn from_wasm(_wasm: &[u8]) -> Option<Self> {
-        // TODO: Parse source map custom section
-        None
+    /// Source location of the instruction at `offset` in the binary, if the
+    /// line tables cover it
+    pub fn get_location(&self, offset: usize) -> Option<SourceLocation> {
+        self.row(offset).cloned()
     }
 
-    /// Get source location for a WASM instruction
-    pub fn get_location(&self, _offset: usize) -> Option<SourceLocation> {
-        // TODO: Implement
-        None
+    /// File and line of the instruction at `offset`, without copying them.
+    /// Line stepping compares these on every instruction.
+    pub fn get_line(&self, offset: usize) -> Option<(&str, usize)> {
+        self.row(offset)
+            .map(|location| (location.file.as_str(), location.line))
     }
+
+    /// Offsets in the binary of the instructions where `line` of `file`
+    /// starts, for line breakpoints, with the line they are on. A line
+    /// without code resolves to the next line of the file that has some.
+    /// `file` matches compiled files with the same path or one ending in it.
+    pub fn line_offsets(&self, file: &str, line: usize) -> Option<(usize, Vec<usize>)> {
+        let wanted = Path::new(file);
+        let in_file = |location: &SourceLocation| {
+            let path = Path::new(&location.file);
+            path.ends_with(wanted) || wanted.ends_with(path)
+        };
+        let line = self
+            .rows
+            .iter()
+            .filter_map(|(_, location)| location.as_ref())
+            .filter(|location| location.line >= line && in_file(location))
+            .map(|location| location.line)
+            .min()?;
+
+        // A line starts wherever a row for it follows a row for another one
+        let mut offsets = Vec::new();
+        let mut previous: Option<&SourceLocation> = None;
+        for (address, location) in &self.rows {
+            if let Some(location) = location {
+                let continues = previous.is_some_and(|previous| {
+                    previous.line == location.line && previous.file == location.file
+                });
+                if location.line == line && in_file(location) && !continues {
+                    offsets.push(self.code_start + *address as usize);
+                }
+            }
+            previous = location.as_ref();
+        }
+        offsets.dedup();
+        Some((line, offsets))
+    }
+
+    fn row(&self, offset: usize) -> Option<&SourceLocation> {
+        let address = offset.checked_sub(self.code_start)? as u64;
+        let next = self.rows.partition_point(|(row, _)| *row <= address);
+        self.rows.get(next.checked_sub(1)?)?.1.as_ref()
+    }
+}
+
+/// Rows of every line program in the binary, unsorted
+fn read_rows(sections: &HashMap<&str, &[u8]>) -> gimli::Result<Vec<(u64, Option<SourceLocation>)>> {
+    let dwarf = Dwarf::load(|id: SectionId| -> gimli::Result<Reader> {
+        let data = sections.get(id.name()).copied().unwrap_or_default();
+        Ok(EndianSlice::new(data, LittleEndian))
+    })?;
+
+    let mut rows = Vec::new();
+    let mut units = dwarf.units();
+    while let Some(header) = units.next()? {
+        let unit = dwarf.unit(header)?;
+        let Some(program) = unit.line_program.clone() else {
+            continue;
+        };
+        let mut files: HashMap<u64, String> = HashMap::new();
+        let mut program_rows = program.rows();
+        while let Some((header, row)) = program_rows.next_row()? {
+            if row.end_sequence() {
+                rows.push((row.address(), None));
+                continue;
+            }
+            // Line 0 marks code with no source line, e.g. compiler glue
+            let location = match (row.line(), row.file(header)) {
+                (Some(line), Some(file)) => {
+                    let path = match files.get(&row.file_index()) {
+                        Some(path) => path.clone(),
+                        None => {
+                            let path = file_path(&dwarf, &unit, header, file)?;
+                            files.insert(row.file_index(), path.clone());
+                            path
+                        }
+                    };
+                    Some(SourceLocation {
+                        file: path,
+                        line: line.get() as usize,
+                        column: match row.column() {
+                            ColumnType::LeftEdge => 0,
+                            ColumnType::Column(column) => column.get() as usize,
+                        },
+                    })
+                }
+                _ => None,
+            };
+            rows.push((row.address(), location));
+        }
+    }
+    Ok(rows)
 }
 
-impl Default for SourceMap {
-    fn default() -> Self {
-        Self::new()
+/// Path of a line table file, joined to its directory and to the unit's
+/// compilation directory when those are relative
+fn file_path(
+    dwarf: &Dwarf<Reader>,
+    unit: &gimli::Unit<Reader>,
+    header: &gimli::LineProgramHeader<Reader>,
+    file: &gimli::FileEntry<Reader>,
+) -> gimli::Result<String> {
+    let attr_string = |attr| -> gimli::Result<String> {
+        Ok(dwarf
+            .attr_string(unit, attr)?
+            .to_string_lossy()
+            .into_owned())
+    };
+    let mut path = unit
+        .comp_dir
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if let Some(directory) = file.directory(header) {
+        path = join(&path, &attr_string(directory)?);
+    }
+    Ok(join(&path, &attr_string(file.path_name())?))
+}
+
+fn join(base: &str, path: &str) -> String {
+    if base.is_empty() || Path::new(path).is_absolute() {
+        path.to_string()
+    } else {
+        Path::new(base).join(path).to_string_lossy().into_owned()
     }
 }
 
 /// A location in source code
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct SourceLocation {
     pub file: String,
     pub line: usize,
+    /// 0 when the line table gives no column
     pub column: usize,
 }
+
+impl SourceLocation {
+    /// Text of the line, if the source file is readable from here
+    pub fn source_line(&self) -> Option<String> {
+        let text = fs::read_to_string(&self.file).ok()?;
+        let line = text.lines().nth(self.line.checked_sub(1)?)?;
+        Some(line.trim().to_string())
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)?;
+        if self.column > 0 {
+            write!(f, ":{}", self.column)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(line: usize) -> Option<SourceLocation> {
+        Some(SourceLocation {
+            file: "src/lib.rs".to_string(),
+            line,
+            column: 0,
+        })
+    }
+
+    #[test]
+    fn test_looks_up_rows_by_address() {
+        let map = SourceMap {
+            code_start: 0x100,
+            rows: vec![
+                (0, location(3)),
+                (4, location(5)),
+                (8, None),
+                (12, location(9)),
+            ],
+        };
+        assert_eq!(map.get_location(0xff), None);
+        assert_eq!(map.get_location(0x100), location(3));
+        assert_eq!(map.get_location(0x107), location(5));
+        // Between the end of a sequence and the next one
+        assert_eq!(map.get_location(0x10a), None);
+        assert_eq!(map.get_location(0x200), location(9));
+        assert_eq!(map.get_line(0x105), Some(("src/lib.rs", 5)));
+        assert_eq!(map.get_line(0x10a), None);
+    }
+
+    #[test]
+    fn test_finds_where_lines_start() {
+        let map = SourceMap {
+            code_start: 0x100,
+            rows: vec![
+                (0, location(3)),
+                (2, location(3)),
+                (4, location(5)),
+                (8, location(3)),
+                (10, None),
+                (12, location(9)),
+            ],
+        };
+        assert_eq!(
+            map.line_offsets("src/lib.rs", 3),
+            Some((3, vec![0x100, 0x108]))
+        );
+        assert_eq!(
+            map.line_offsets("/work/counter/src/lib.rs", 5),
+            Some((5, vec![0x104]))
+        );
+        // Lines without code move to the next one that has some
+        assert_eq!(map.line_offsets("src/lib.rs", 6), Some((9, vec![0x10c])));
+        assert_eq!(map.line_offsets("src/lib.rs", 10), None);
+        assert_eq!(map.line_offsets("src/other.rs", 3), None);
+    }
+
+    #[test]
+    fn test_needs_line_tables() {
+        let module = walrus::Module::default().emit_wasm();
+        assert!(SourceMap::from_wasm(&module).is_none());
+        assert!(SourceMap::from_wasm(b"not wasm").is_none());
+    }
+
+    #[test]
+    fn test_joins_relative_paths() {
+        assert_eq!(join("", "src/lib.rs"), "src/lib.rs");
+        assert_eq!(join("/work", "/abs/lib.rs"), "/abs/lib.rs");
+        assert_eq!(
+            join("/work/counter", "src/lib.rs"),
+            Path::new("/work/counter/src/lib.rs").to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_displays_columns_when_known() {
+        let mut location = location(12).unwrap();
+        assert_eq!(location.to_string(), "src/lib.rs:12");
+        location.column = 5;
+        assert_eq!(location.to_string(), "src/lib.rs:12:5");
+    }
+}
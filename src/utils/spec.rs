@@ -0,0 +1,1215 @@
+//! Contract spec driven argument coercion
+//!
+//! Contracts embed their interface in the `contractspecv0` custom section.
+//! [`ContractSpec`] looks up the declared parameter types of a function and
+//! turns bare JSON arguments into the type annotations understood by
+//! [`ArgumentParser`], so `5` passed to a `u32` parameter becomes a `u32`
+//! rather than the default `i128`. Explicit annotations are kept as given.
+//!
+//! User-defined types are encoded the way the SDK encodes them:
+//! - structs → maps keyed by field name (tuple structs → vecs), written as
+//!   JSON objects (arrays for tuple structs)
+//! - unions → `[case, ...values]` vecs, written as `"Case"`,
+//!   `{"Case": value}` or `["Case", value, ...]`
+//! - integer enums → `u32`, written as the number or the case name
+//!
+//! [`ContractSpec::value_to_json`] renders values the other way round, in
+//! the same bare format, so a returned struct can be passed straight back
+//! to a function taking that struct.
+//!
+//! Contract error codes are named after the `contracterror` enums in the
+//! spec by [`ContractSpec::describe_error`].
+
+use super::arguments::{ArgumentParseError, ArgumentParser};
+use super::scval::{scval_to_json, scval_to_string};
+use super::wasm::{parse_contract_spec, spec_type_to_string};
+use crate::Result;
+use serde::Serialize;
+use serde_json::{json, Value};
+use soroban_sdk::Val;
+use std::fmt;
+use stellar_xdr::curr::{
+    ScError, ScSpecEntry, ScSpecFunctionV0, ScSpecTypeDef, ScSpecUdtEnumV0, ScSpecUdtErrorEnumV0,
+    ScSpecUdtStructV0, ScSpecUdtUnionCaseV0, ScSpecUdtUnionV0, ScVal,
+};
+use tracing::warn;
+
+/// A contract error code, named after its spec error enum case when known
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContractErrorInfo {
+    pub code: u32,
+    /// `Enum::Case`, e.g. `Error::InsufficientBalance`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Doc comment of the enum case
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+}
+
+impl ContractErrorInfo {
+    /// Whether `expected` names this error: `Enum::Case`, `Case` or the code
+    pub fn matches(&self, expected: &str) -> bool {
+        let expected = expected.trim();
+        if expected == self.code.to_string() {
+            return true;
+        }
+        match &self.name {
+            Some(name) => name == expected || name.rsplit("::").next() == Some(expected),
+            None => false,
+        }
+    }
+}
+
+impl fmt::Display for ContractErrorInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{} ({})", name, self.code)?,
+            None => write!(f, "Contract error code: {}", self.code)?,
+        }
+        if let Some(doc) = &self.doc {
+            write!(f, ": {}", doc)?;
+        }
+        Ok(())
+    }
+}
+
+/// Interface of a contract, read from its `contractspecv0` section
+#[derive(Debug, Clone, Default)]
+pub struct ContractSpec {
+    entries: Vec<ScSpecEntry>,
+}
+
+impl ContractSpec {
+    /// Read the spec embedded in a contract WASM
+    pub fn from_wasm(wasm_bytes: &[u8]) -> Result<Self> {
+        Ok(Self::from_entries(parse_contract_spec(wasm_bytes)?))
+    }
+
+    /// Build a spec from already decoded entries
+    pub fn from_entries(entries: Vec<ScSpecEntry>) -> Self {
+        Self { entries }
+    }
+
+    /// All spec entries
+    pub fn entries(&self) -> &[ScSpecEntry] {
+        &self.entries
+    }
+
+    /// Find the declaration of a function
+    pub fn function(&self, name: &str) -> Option<&ScSpecFunctionV0> {
+        self.entries.iter().find_map(|entry| match entry {
+            ScSpecEntry::FunctionV0(func) if func.name.0.as_slice() == name.as_bytes() => {
+                Some(func)
+            }
+            _ => None,
+        })
+    }
+
+    /// Declared return type of a function, if the spec describes it
+    pub fn return_type(&self, function: &str) -> Option<&ScSpecTypeDef> {
+        self.function(function)?.outputs.first()
+    }
+
+    /// Pair the arguments of a call with the parameter names of `function`,
+    /// rendering each against its declared type. Arguments beyond the spec,
+    /// or of an undeclared function, are named `arg0`, `arg1`, ...
+    pub fn named_args(&self, function: &str, args: &[ScVal]) -> Vec<(String, Value)> {
+        let inputs = self.function(function).map(|func| func.inputs.as_slice());
+        args.iter()
+            .enumerate()
+            .map(|(i, arg)| match inputs.and_then(|inputs| inputs.get(i)) {
+                Some(input) => (
+                    input.name.to_utf8_string_lossy(),
+                    self.value_to_json(arg, &input.type_),
+                ),
+                None => (format!("arg{}", i), scval_to_json(arg)),
+            })
+            .collect()
+    }
+
+    /// Error enums (`#[contracterror]`) declared by the contract
+    pub fn error_enums(&self) -> impl Iterator<Item = &ScSpecUdtErrorEnumV0> {
+        self.entries.iter().filter_map(|entry| match entry {
+            ScSpecEntry::UdtErrorEnumV0(e) => Some(e),
+            _ => None,
+        })
+    }
+
+    /// Name the error `code` returned by `function`
+    ///
+    /// The error enum of the function's `Result` return type is preferred;
+    /// otherwise the first error enum with a matching case is used.
+    pub fn describe_error(&self, function: Option<&str>, code: u32) -> ContractErrorInfo {
+        let declared = function
+            .and_then(|f| self.return_type(f))
+            .and_then(|ty| match ty {
+                ScSpecTypeDef::Result(r) => match r.error_type.as_ref() {
+                    ScSpecTypeDef::Udt(u) => Some(u.name.to_utf8_string_lossy()),
+                    _ => None,
+                },
+                _ => None,
+            });
+
+        let mut enums: Vec<&ScSpecUdtErrorEnumV0> = self.error_enums().collect();
+        if let Some(declared) = declared {
+            enums.sort_by_key(|e| e.name.to_utf8_string_lossy() != declared);
+        }
+
+        enums
+            .iter()
+            .find_map(|e| {
+                let case = e.cases.iter().find(|c| c.value == code)?;
+                let doc = case.doc.to_utf8_string_lossy();
+                Some(ContractErrorInfo {
+                    code,
+                    name: Some(format!(
+                        "{}::{}",
+                        e.name.to_utf8_string_lossy(),
+                        case.name.to_utf8_string_lossy()
+                    )),
+                    doc: Some(doc.trim().to_string()).filter(|d| !d.is_empty()),
+                })
+            })
+            .unwrap_or(ContractErrorInfo {
+                code,
+                name: None,
+                doc: None,
+            })
+    }
+
+    /// Find a user-defined type by name
+    fn udt(&self, name: &str) -> Option<&ScSpecEntry> {
+        self.entries.iter().find(|entry| {
+            let udt_name = match entry {
+                ScSpecEntry::UdtStructV0(s) => s.name.as_slice(),
+                ScSpecEntry::UdtUnionV0(u) => u.name.as_slice(),
+                ScSpecEntry::UdtEnumV0(e) => e.name.as_slice(),
+                ScSpecEntry::UdtErrorEnumV0(e) => e.name.as_slice(),
+                ScSpecEntry::FunctionV0(_) => return false,
+            };
+            udt_name == name.as_bytes()
+        })
+    }
+
+    /// Parse JSON arguments for `func`, coercing each against its declared type
+    ///
+    /// A JSON array holds one element per parameter and any other value is a
+    /// single argument. For functions with one parameter an array of a
+    /// different length is taken as that parameter's value. So is any array
+    /// for a single `Vec` or tuple parameter, unless it wraps the value in an
+    /// outer array (`[[7]]`) or holds one type annotation.
+    pub fn parse_args(
+        &self,
+        parser: &ArgumentParser,
+        func: &ScSpecFunctionV0,
+        json_str: &str,
+    ) -> std::result::Result<Vec<Val>, ArgumentParseError> {
+        if json_str.trim().is_empty() {
+            return Err(ArgumentParseError::EmptyArguments);
+        }
+
+        let value: Value = serde_json::from_str(json_str)?;
+        let single_sequence = match func.inputs.as_slice() {
+            [input] => matches!(input.type_, ScSpecTypeDef::Vec(_) | ScSpecTypeDef::Tuple(_)),
+            _ => false,
+        };
+        let values = match value {
+            Value::Array(items) if func.inputs.len() == 1 && items.len() != 1 => {
+                vec![Value::Array(items)]
+            }
+            Value::Array(items)
+                if single_sequence
+                    && !(items[0].is_array() || parser.is_typed_annotation(&items[0])) =>
+            {
+                vec![Value::Array(items)]
+            }
+            Value::Array(items) => items,
+            other => vec![other],
+        };
+
+        if values.len() != func.inputs.len() {
+            let params: Vec<String> = func
+                .inputs
+                .iter()
+                .map(|input| {
+                    format!(
+                        "{}: {}",
+                        input.name.to_utf8_string_lossy(),
+                        spec_type_to_string(&input.type_)
+                    )
+                })
+                .collect();
+            return Err(ArgumentParseError::InvalidArgument(format!(
+                "Function '{}' expects {} argument(s) ({}), got {}",
+                func.name.0.to_utf8_string_lossy(),
+                func.inputs.len(),
+                params.join(", "),
+                values.len()
+            )));
+        }
+
+        func.inputs
+            .iter()
+            .zip(&values)
+            .map(|(input, value)| {
+                self.annotate(parser, value, &input.type_)
+                    .and_then(|annotated| parser.parse_single_value(&annotated))
+                    .map_err(|e| ArgumentParseError::InvalidParameter {
+                        name: input.name.to_utf8_string_lossy(),
+                        type_name: spec_type_to_string(&input.type_),
+                        reason: e.to_string(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Rewrite a bare JSON value as the type annotation for `ty`
+    fn annotate(
+        &self,
+        parser: &ArgumentParser,
+        value: &Value,
+        ty: &ScSpecTypeDef,
+    ) -> std::result::Result<Value, ArgumentParseError> {
+        if parser.is_typed_annotation(value) {
+            return Ok(value.clone());
+        }
+
+        let typed = |type_name: &str| json!({ "type": type_name, "value": value });
+        let annotated = match ty {
+            ScSpecTypeDef::Val => value.clone(),
+            ScSpecTypeDef::Bool => typed("bool"),
+            ScSpecTypeDef::U32 => typed("u32"),
+            ScSpecTypeDef::I32 => typed("i32"),
+            ScSpecTypeDef::U64 => typed("u64"),
+            ScSpecTypeDef::I64 => typed("i64"),
+            ScSpecTypeDef::U128 => typed("u128"),
+            ScSpecTypeDef::I128 => typed("i128"),
+            ScSpecTypeDef::U256 => typed("u256"),
+            ScSpecTypeDef::I256 => typed("i256"),
+            ScSpecTypeDef::Timepoint => typed("timepoint"),
+            ScSpecTypeDef::Duration => typed("duration"),
+            ScSpecTypeDef::String => typed("string"),
+            ScSpecTypeDef::Symbol => typed("symbol"),
+            ScSpecTypeDef::Address => typed("address"),
+            ScSpecTypeDef::Bytes => typed("bytes"),
+            ScSpecTypeDef::BytesN(b) => json!({ "type": "bytesn", "length": b.n, "value": value }),
+            ScSpecTypeDef::Void => {
+                if !value.is_null() {
+                    return Err(mismatch(ty, value));
+                }
+                Value::Null
+            }
+            ScSpecTypeDef::Option(o) => {
+                if value.is_null() {
+                    Value::Null
+                } else {
+                    self.annotate(parser, value, &o.value_type)?
+                }
+            }
+            ScSpecTypeDef::Vec(v) => {
+                let items = value.as_array().ok_or_else(|| mismatch(ty, value))?;
+                let items = items
+                    .iter()
+                    .map(|item| self.annotate(parser, item, &v.element_type))
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+                json!({ "type": "vec", "value": items })
+            }
+            ScSpecTypeDef::Map(m) => {
+                let pairs: Vec<(Value, &Value)> = match value {
+                    Value::Object(fields) => fields
+                        .iter()
+                        .map(|(k, v)| (object_key(k, &m.key_type), v))
+                        .collect(),
+                    Value::Array(pairs) => pairs
+                        .iter()
+                        .map(|pair| match pair.as_array().map(Vec::as_slice) {
+                            Some([k, v]) => Ok((k.clone(), v)),
+                            _ => Err(mismatch(ty, pair)),
+                        })
+                        .collect::<std::result::Result<_, _>>()?,
+                    _ => return Err(mismatch(ty, value)),
+                };
+                let pairs = pairs
+                    .iter()
+                    .map(|(k, v)| {
+                        Ok(json!([
+                            self.annotate(parser, k, &m.key_type)?,
+                            self.annotate(parser, v, &m.value_type)?
+                        ]))
+                    })
+                    .collect::<std::result::Result<Vec<_>, ArgumentParseError>>()?;
+                json!({ "type": "map", "value": pairs })
+            }
+            ScSpecTypeDef::Tuple(t) => {
+                let items = value
+                    .as_array()
+                    .filter(|items| items.len() == t.value_types.len())
+                    .ok_or_else(|| mismatch(ty, value))?;
+                self.annotate_items(parser, items, t.value_types.as_slice())?
+            }
+            ScSpecTypeDef::Udt(u) => {
+                let name = u.name.to_utf8_string_lossy();
+                match self.udt(&name) {
+                    Some(ScSpecEntry::UdtStructV0(s)) => self.annotate_struct(parser, value, s)?,
+                    Some(ScSpecEntry::UdtUnionV0(un)) => self.annotate_union(parser, value, un)?,
+                    Some(ScSpecEntry::UdtEnumV0(e)) => annotate_enum(value, e)?,
+                    Some(_) => {
+                        return Err(ArgumentParseError::UnsupportedType(format!(
+                            "{} cannot be passed as an argument",
+                            name
+                        )))
+                    }
+                    None => {
+                        return Err(ArgumentParseError::InvalidArgument(format!(
+                            "Type {} is not defined in the contract spec",
+                            name
+                        )))
+                    }
+                }
+            }
+            ScSpecTypeDef::Error | ScSpecTypeDef::Result(_) => {
+                return Err(ArgumentParseError::UnsupportedType(format!(
+                    "{} cannot be passed as an argument",
+                    spec_type_to_string(ty)
+                )))
+            }
+        };
+
+        Ok(annotated)
+    }
+
+    /// Render a value of type `ty` as bare JSON
+    ///
+    /// Numbers, strings, addresses (strkeys) and bytes (`0x` hex) are written
+    /// without annotations, structs as objects, unions as `"Case"` or
+    /// `{"Case": value}` and enums by case name. Values that do not match
+    /// `ty` are logged as a mismatch and keep the annotated
+    /// [`scval_to_json`] format, so their actual type shows.
+    pub fn value_to_json(&self, val: &ScVal, ty: &ScSpecTypeDef) -> Value {
+        self.try_value_to_json(val, ty).unwrap_or_else(|| {
+            if *ty != ScSpecTypeDef::Val {
+                warn!(
+                    "Value {} does not match its declared type {}",
+                    scval_to_string(val),
+                    spec_type_to_string(ty)
+                );
+            }
+            scval_to_json(val)
+        })
+    }
+
+    fn try_value_to_json(&self, val: &ScVal, ty: &ScSpecTypeDef) -> Option<Value> {
+        let json = match (ty, val) {
+            (ScSpecTypeDef::Option(_), ScVal::Void) => Value::Null,
+            (ScSpecTypeDef::Option(o), _) => self.try_value_to_json(val, &o.value_type)?,
+            (ScSpecTypeDef::Result(r), _) => self.try_value_to_json(val, &r.ok_type)?,
+            (ScSpecTypeDef::Vec(v), ScVal::Vec(Some(items))) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.value_to_json(item, &v.element_type))
+                    .collect(),
+            ),
+            (ScSpecTypeDef::Map(m), ScVal::Map(Some(map))) => {
+                let pairs: Vec<(Value, Value)> = map
+                    .iter()
+                    .map(|entry| {
+                        (
+                            self.value_to_json(&entry.key, &m.key_type),
+                            self.value_to_json(&entry.val, &m.value_type),
+                        )
+                    })
+                    .collect();
+                let keys_are_plain = pairs
+                    .iter()
+                    .all(|(k, _)| matches!(k, Value::String(_) | Value::Number(_)));
+                if keys_are_plain {
+                    Value::Object(
+                        pairs
+                            .into_iter()
+                            .map(|(k, v)| match k {
+                                Value::String(k) => (k, v),
+                                other => (other.to_string(), v),
+                            })
+                            .collect(),
+                    )
+                } else {
+                    Value::Array(pairs.into_iter().map(|(k, v)| json!([k, v])).collect())
+                }
+            }
+            (ScSpecTypeDef::Tuple(t), ScVal::Vec(Some(items)))
+                if items.len() == t.value_types.len() =>
+            {
+                Value::Array(
+                    items
+                        .iter()
+                        .zip(t.value_types.iter())
+                        .map(|(item, ty)| self.value_to_json(item, ty))
+                        .collect(),
+                )
+            }
+            (ScSpecTypeDef::Udt(u), _) => match self.udt(&u.name.to_utf8_string_lossy())? {
+                ScSpecEntry::UdtStructV0(s) => self.struct_to_json(val, s)?,
+                ScSpecEntry::UdtUnionV0(un) => self.union_to_json(val, un)?,
+                ScSpecEntry::UdtEnumV0(e) => match val {
+                    ScVal::U32(n) => e
+                        .cases
+                        .iter()
+                        .find(|c| c.value == *n)
+                        .map(|c| Value::String(c.name.to_utf8_string_lossy()))?,
+                    _ => return None,
+                },
+                ScSpecEntry::UdtErrorEnumV0(e) => match val {
+                    ScVal::Error(ScError::Contract(code)) => e
+                        .cases
+                        .iter()
+                        .find(|c| c.value == *code)
+                        .map(|c| Value::String(c.name.to_utf8_string_lossy()))?,
+                    _ => return None,
+                },
+                ScSpecEntry::FunctionV0(_) => return None,
+            },
+            (ty, _) if !is_plain_value_of(ty, val) => return None,
+            (_, ScVal::Bool(b)) => Value::Bool(*b),
+            (_, ScVal::Void) => Value::Null,
+            (_, ScVal::U32(n)) => json!(n),
+            (_, ScVal::I32(n)) => json!(n),
+            (_, ScVal::U64(n)) => json!(n),
+            (_, ScVal::I64(n)) => json!(n),
+            (_, ScVal::Timepoint(t)) => json!(t.0),
+            (_, ScVal::Duration(d)) => json!(d.0),
+            (_, ScVal::U128(parts)) => {
+                let n = ((parts.hi as u128) << 64) | parts.lo as u128;
+                u64::try_from(n)
+                    .map(|small| json!(small))
+                    .unwrap_or_else(|_| json!(n.to_string()))
+            }
+            (_, ScVal::I128(parts)) => {
+                let n = ((parts.hi as i128) << 64) | parts.lo as i128;
+                i64::try_from(n)
+                    .map(|small| json!(small))
+                    .unwrap_or_else(|_| json!(n.to_string()))
+            }
+            (_, ScVal::String(s)) => Value::String(s.to_utf8_string_lossy()),
+            (_, ScVal::Symbol(s)) => Value::String(s.to_utf8_string_lossy()),
+            (_, ScVal::Address(addr)) => Value::String(addr.to_string()),
+            (_, ScVal::Bytes(bytes)) => Value::String(format!(
+                "0x{}",
+                bytes
+                    .as_slice()
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<String>()
+            )),
+            _ => return None,
+        };
+        Some(json)
+    }
+
+    fn struct_to_json(&self, val: &ScVal, s: &ScSpecUdtStructV0) -> Option<Value> {
+        match val {
+            // Tuple structs
+            ScVal::Vec(Some(items)) if items.len() == s.fields.len() => Some(Value::Array(
+                items
+                    .iter()
+                    .zip(s.fields.iter())
+                    .map(|(item, field)| self.value_to_json(item, &field.type_))
+                    .collect(),
+            )),
+            ScVal::Map(Some(map)) => {
+                let mut fields = serde_json::Map::new();
+                for entry in map.iter() {
+                    let ScVal::Symbol(key) = &entry.key else {
+                        return None;
+                    };
+                    let field = s
+                        .fields
+                        .iter()
+                        .find(|f| f.name.as_slice() == key.as_slice())?;
+                    fields.insert(
+                        key.to_utf8_string_lossy(),
+                        self.value_to_json(&entry.val, &field.type_),
+                    );
+                }
+                Some(Value::Object(fields))
+            }
+            _ => None,
+        }
+    }
+
+    fn union_to_json(&self, val: &ScVal, union: &ScSpecUdtUnionV0) -> Option<Value> {
+        let ScVal::Vec(Some(items)) = val else {
+            return None;
+        };
+        let (ScVal::Symbol(tag), values) = items.split_first()? else {
+            return None;
+        };
+        let case = union
+            .cases
+            .iter()
+            .find(|case| union_case_name(case) == tag.as_slice())?;
+        let name = tag.to_utf8_string_lossy();
+
+        match case {
+            ScSpecUdtUnionCaseV0::VoidV0(_) if values.is_empty() => Some(Value::String(name)),
+            ScSpecUdtUnionCaseV0::TupleV0(tuple) if values.len() == tuple.type_.len() => {
+                let mut rendered: Vec<Value> = values
+                    .iter()
+                    .zip(tuple.type_.iter())
+                    .map(|(item, ty)| self.value_to_json(item, ty))
+                    .collect();
+                let payload = if rendered.len() == 1 {
+                    rendered.remove(0)
+                } else {
+                    Value::Array(rendered)
+                };
+                Some(json!({ name: payload }))
+            }
+            _ => None,
+        }
+    }
+
+    /// Annotate a fixed list of values as a vec
+    fn annotate_items(
+        &self,
+        parser: &ArgumentParser,
+        items: &[Value],
+        types: &[ScSpecTypeDef],
+    ) -> std::result::Result<Value, ArgumentParseError> {
+        let items = items
+            .iter()
+            .zip(types)
+            .map(|(item, ty)| self.annotate(parser, item, ty))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(json!({ "type": "vec", "value": items }))
+    }
+
+    /// Structs are maps keyed by field name; tuple structs (`0`, `1`, ...) are vecs
+    fn annotate_struct(
+        &self,
+        parser: &ArgumentParser,
+        value: &Value,
+        s: &ScSpecUdtStructV0,
+    ) -> std::result::Result<Value, ArgumentParseError> {
+        let struct_name = s.name.to_utf8_string_lossy();
+        let is_tuple = s
+            .fields
+            .iter()
+            .all(|field| field.name.as_slice().iter().all(u8::is_ascii_digit));
+
+        if is_tuple {
+            let types: Vec<ScSpecTypeDef> = s.fields.iter().map(|f| f.type_.clone()).collect();
+            let items = value
+                .as_array()
+                .filter(|items| items.len() == types.len())
+                .ok_or_else(|| ArgumentParseError::TypeMismatch {
+                    expected: format!("{} (array of {} fields)", struct_name, types.len()),
+                    actual: value.to_string(),
+                })?;
+            return self.annotate_items(parser, items, &types);
+        }
+
+        let fields = value
+            .as_object()
+            .ok_or_else(|| ArgumentParseError::TypeMismatch {
+                expected: format!("{} (object)", struct_name),
+                actual: value.to_string(),
+            })?;
+
+        if let Some(unknown) = fields
+            .keys()
+            .find(|key| !s.fields.iter().any(|f| f.name.as_slice() == key.as_bytes()))
+        {
+            return Err(ArgumentParseError::InvalidArgument(format!(
+                "{} has no field '{}'",
+                struct_name, unknown
+            )));
+        }
+
+        let pairs = s
+            .fields
+            .iter()
+            .map(|field| {
+                let field_name = field.name.to_utf8_string_lossy();
+                let field_value = fields.get(&field_name).ok_or_else(|| {
+                    ArgumentParseError::InvalidArgument(format!(
+                        "{} is missing field '{}'",
+                        struct_name, field_name
+                    ))
+                })?;
+                let annotated = self
+                    .annotate(parser, field_value, &field.type_)
+                    .map_err(|e| {
+                        ArgumentParseError::ConversionError(format!(
+                            "{}.{}: {}",
+                            struct_name, field_name, e
+                        ))
+                    })?;
+                Ok(json!([{ "type": "symbol", "value": field_name }, annotated]))
+            })
+            .collect::<std::result::Result<Vec<_>, ArgumentParseError>>()?;
+
+        Ok(json!({ "type": "map", "value": pairs }))
+    }
+
+    /// Unions are `[case, ...values]` vecs
+    fn annotate_union(
+        &self,
+        parser: &ArgumentParser,
+        value: &Value,
+        union: &ScSpecUdtUnionV0,
+    ) -> std::result::Result<Value, ArgumentParseError> {
+        let union_name = union.name.to_utf8_string_lossy();
+        let expected = || ArgumentParseError::TypeMismatch {
+            expected: format!(
+                "{} (\"Case\", {{\"Case\": value}} or [\"Case\", ...values])",
+                union_name
+            ),
+            actual: value.to_string(),
+        };
+
+        let (case_name, payload): (&str, Option<&Value>) = match value {
+            Value::String(case) => (case, None),
+            Value::Object(obj) if obj.len() == 1 => {
+                let (case, payload) = obj.iter().next().ok_or_else(expected)?;
+                (case, Some(payload))
+            }
+            Value::Array(items) => match items.split_first() {
+                Some((Value::String(case), rest)) => {
+                    (case, Some(value).filter(|_| !rest.is_empty()))
+                }
+                _ => return Err(expected()),
+            },
+            _ => return Err(expected()),
+        };
+
+        let case = union
+            .cases
+            .iter()
+            .find(|case| union_case_name(case) == case_name.as_bytes())
+            .ok_or_else(|| {
+                ArgumentParseError::InvalidArgument(format!(
+                    "{} has no case '{}'",
+                    union_name, case_name
+                ))
+            })?;
+
+        let tag = json!({ "type": "symbol", "value": case_name });
+        match case {
+            ScSpecUdtUnionCaseV0::VoidV0(_) => {
+                if payload.is_some() {
+                    return Err(ArgumentParseError::InvalidArgument(format!(
+                        "{}::{} takes no values",
+                        union_name, case_name
+                    )));
+                }
+                Ok(json!({ "type": "vec", "value": [tag] }))
+            }
+            ScSpecUdtUnionCaseV0::TupleV0(tuple) => {
+                let types = tuple.type_.as_slice();
+                let values: Vec<Value> = match (value, payload) {
+                    // ["Case", a, b]
+                    (Value::Array(items), Some(_)) => items[1..].to_vec(),
+                    // {"Case": [a, b]}
+                    (_, Some(Value::Array(items))) if types.len() != 1 => items.clone(),
+                    // {"Case": a}
+                    (_, Some(single)) => vec![single.clone()],
+                    _ => Vec::new(),
+                };
+                if values.len() != types.len() {
+                    return Err(ArgumentParseError::InvalidArgument(format!(
+                        "{}::{} takes {} value(s), got {}",
+                        union_name,
+                        case_name,
+                        types.len(),
+                        values.len()
+                    )));
+                }
+                let mut items = vec![tag];
+                for (item, ty) in values.iter().zip(types) {
+                    items.push(self.annotate(parser, item, ty)?);
+                }
+                Ok(json!({ "type": "vec", "value": items }))
+            }
+        }
+    }
+}
+
+/// Integer enums are `u32`, given as the number or the case name
+fn annotate_enum(
+    value: &Value,
+    e: &ScSpecUdtEnumV0,
+) -> std::result::Result<Value, ArgumentParseError> {
+    let case = match value {
+        Value::String(name) => e
+            .cases
+            .iter()
+            .find(|c| c.name.as_slice() == name.as_bytes()),
+        Value::Number(n) => e
+            .cases
+            .iter()
+            .find(|c| n.as_u64() == Some(u64::from(c.value))),
+        _ => None,
+    };
+
+    let case = case.ok_or_else(|| {
+        let cases: Vec<String> = e
+            .cases
+            .iter()
+            .map(|c| format!("{} = {}", c.name.to_utf8_string_lossy(), c.value))
+            .collect();
+        ArgumentParseError::TypeMismatch {
+            expected: format!("{} ({})", e.name.to_utf8_string_lossy(), cases.join(", ")),
+            actual: value.to_string(),
+        }
+    })?;
+
+    Ok(json!({ "type": "u32", "value": case.value }))
+}
+
+fn union_case_name(case: &ScSpecUdtUnionCaseV0) -> &[u8] {
+    match case {
+        ScSpecUdtUnionCaseV0::VoidV0(v) => v.name.as_slice(),
+        ScSpecUdtUnionCaseV0::TupleV0(t) => t.name.as_slice(),
+    }
+}
+
+/// JSON object keys are strings; re-read them as numbers for integer key types
+fn object_key(key: &str, key_type: &ScSpecTypeDef) -> Value {
+    let numeric = matches!(
+        key_type,
+        ScSpecTypeDef::U32
+            | ScSpecTypeDef::I32
+            | ScSpecTypeDef::U64
+            | ScSpecTypeDef::I64
+            | ScSpecTypeDef::U128
+            | ScSpecTypeDef::I128
+    );
+    if numeric {
+        if let Ok(number @ Value::Number(_)) = serde_json::from_str(key) {
+            return number;
+        }
+    }
+    Value::String(key.to_string())
+}
+
+/// Whether `val` is a plain value of the declared type `ty`
+fn is_plain_value_of(ty: &ScSpecTypeDef, val: &ScVal) -> bool {
+    matches!(
+        (ty, val),
+        (ScSpecTypeDef::Val, _)
+            | (ScSpecTypeDef::Bool, ScVal::Bool(_))
+            | (ScSpecTypeDef::Void, ScVal::Void)
+            | (ScSpecTypeDef::U32, ScVal::U32(_))
+            | (ScSpecTypeDef::I32, ScVal::I32(_))
+            | (ScSpecTypeDef::U64, ScVal::U64(_))
+            | (ScSpecTypeDef::I64, ScVal::I64(_))
+            | (ScSpecTypeDef::Timepoint, ScVal::Timepoint(_))
+            | (ScSpecTypeDef::Duration, ScVal::Duration(_))
+            | (ScSpecTypeDef::U128, ScVal::U128(_))
+            | (ScSpecTypeDef::I128, ScVal::I128(_))
+            | (ScSpecTypeDef::String, ScVal::String(_))
+            | (ScSpecTypeDef::Symbol, ScVal::Symbol(_))
+            | (ScSpecTypeDef::Address, ScVal::Address(_))
+            | (
+                ScSpecTypeDef::Bytes | ScSpecTypeDef::BytesN(_),
+                ScVal::Bytes(_)
+            )
+    )
+}
+
+fn mismatch(ty: &ScSpecTypeDef, value: &Value) -> ArgumentParseError {
+    ArgumentParseError::TypeMismatch {
+        expected: spec_type_to_string(ty),
+        actual: value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{Env, TryFromVal};
+    use stellar_xdr::curr::{
+        ScSpecFunctionInputV0, ScSpecTypeOption, ScSpecTypeResult, ScSpecTypeUdt, ScSpecTypeVec,
+        ScSpecUdtEnumCaseV0, ScSpecUdtErrorEnumCaseV0, ScSpecUdtStructFieldV0,
+        ScSpecUdtUnionCaseTupleV0, ScSpecUdtUnionCaseVoidV0, ScSymbol, ScVal,
+    };
+
+    fn udt(name: &str) -> ScSpecTypeDef {
+        ScSpecTypeDef::Udt(ScSpecTypeUdt {
+            name: name.try_into().unwrap(),
+        })
+    }
+
+    fn function(name: &str, inputs: &[(&str, ScSpecTypeDef)]) -> ScSpecEntry {
+        ScSpecEntry::FunctionV0(ScSpecFunctionV0 {
+            doc: Default::default(),
+            name: ScSymbol(name.try_into().unwrap()),
+            inputs: inputs
+                .iter()
+                .map(|(name, ty)| ScSpecFunctionInputV0 {
+                    doc: Default::default(),
+                    name: (*name).try_into().unwrap(),
+                    type_: ty.clone(),
+                })
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+            outputs: Default::default(),
+        })
+    }
+
+    fn test_spec() -> ContractSpec {
+        let transfer = ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+            doc: Default::default(),
+            lib: Default::default(),
+            name: "Transfer".try_into().unwrap(),
+            fields: vec![
+                ScSpecUdtStructFieldV0 {
+                    doc: Default::default(),
+                    name: "amount".try_into().unwrap(),
+                    type_: ScSpecTypeDef::I128,
+                },
+                ScSpecUdtStructFieldV0 {
+                    doc: Default::default(),
+                    name: "memo".try_into().unwrap(),
+                    type_: ScSpecTypeDef::Option(Box::new(ScSpecTypeOption {
+                        value_type: Box::new(ScSpecTypeDef::Symbol),
+                    })),
+                },
+            ]
+            .try_into()
+            .unwrap(),
+        });
+        let action = ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
+            doc: Default::default(),
+            lib: Default::default(),
+            name: "Action".try_into().unwrap(),
+            cases: vec![
+                ScSpecUdtUnionCaseV0::VoidV0(ScSpecUdtUnionCaseVoidV0 {
+                    doc: Default::default(),
+                    name: "Stop".try_into().unwrap(),
+                }),
+                ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                    doc: Default::default(),
+                    name: "Move".try_into().unwrap(),
+                    type_: vec![ScSpecTypeDef::U32].try_into().unwrap(),
+                }),
+            ]
+            .try_into()
+            .unwrap(),
+        });
+        let level = ScSpecEntry::UdtEnumV0(ScSpecUdtEnumV0 {
+            doc: Default::default(),
+            lib: Default::default(),
+            name: "Level".try_into().unwrap(),
+            cases: vec![
+                ScSpecUdtEnumCaseV0 {
+                    doc: Default::default(),
+                    name: "Low".try_into().unwrap(),
+                    value: 1,
+                },
+                ScSpecUdtEnumCaseV0 {
+                    doc: Default::default(),
+                    name: "High".try_into().unwrap(),
+                    value: 2,
+                },
+            ]
+            .try_into()
+            .unwrap(),
+        });
+
+        let errors = |name: &str, cases: &[(&str, u32, &str)]| {
+            ScSpecEntry::UdtErrorEnumV0(ScSpecUdtErrorEnumV0 {
+                doc: Default::default(),
+                lib: Default::default(),
+                name: name.try_into().unwrap(),
+                cases: cases
+                    .iter()
+                    .map(|(name, value, doc)| ScSpecUdtErrorEnumCaseV0 {
+                        doc: (*doc).try_into().unwrap(),
+                        name: (*name).try_into().unwrap(),
+                        value: *value,
+                    })
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap(),
+            })
+        };
+        let mut withdraw = function("withdraw", &[("amount", ScSpecTypeDef::I128)]);
+        if let ScSpecEntry::FunctionV0(func) = &mut withdraw {
+            func.outputs = vec![ScSpecTypeDef::Result(Box::new(ScSpecTypeResult {
+                ok_type: Box::new(ScSpecTypeDef::I128),
+                error_type: Box::new(udt("TokenError")),
+            }))]
+            .try_into()
+            .unwrap();
+        }
+
+        ContractSpec::from_entries(vec![
+            errors(
+                "Error",
+                &[
+                    ("NotInitialized", 1, ""),
+                    ("Overflow", 3, "Counter overflowed"),
+                ],
+            ),
+            errors(
+                "TokenError",
+                &[("InsufficientBalance", 3, "Balance is too low")],
+            ),
+            withdraw,
+            transfer,
+            action,
+            level,
+            function("set", &[("count", ScSpecTypeDef::U32)]),
+            function(
+                "pay",
+                &[("to", ScSpecTypeDef::Address), ("t", udt("Transfer"))],
+            ),
+            function("act", &[("action", udt("Action"))]),
+            function("level", &[("level", udt("Level"))]),
+            function(
+                "sum",
+                &[(
+                    "values",
+                    ScSpecTypeDef::Vec(Box::new(ScSpecTypeVec {
+                        element_type: Box::new(ScSpecTypeDef::U64),
+                    })),
+                )],
+            ),
+        ])
+    }
+
+    fn parse(function: &str, json: &str) -> std::result::Result<Vec<ScVal>, ArgumentParseError> {
+        let env = Env::default();
+        let parser = ArgumentParser::new(env.clone());
+        let spec = test_spec();
+        let vals = spec.parse_args(&parser, spec.function(function).unwrap(), json)?;
+        Ok(vals
+            .iter()
+            .map(|v| ScVal::try_from_val(&env, v).unwrap())
+            .collect())
+    }
+
+    #[test]
+    fn test_bare_number_coerced_to_declared_type() {
+        assert_eq!(parse("set", "[5]").unwrap(), vec![ScVal::U32(5)]);
+        assert_eq!(parse("set", "5").unwrap(), vec![ScVal::U32(5)]);
+    }
+
+    #[test]
+    fn test_explicit_annotation_is_kept() {
+        assert_eq!(
+            parse("set", r#"[{"type": "u64", "value": 5}]"#).unwrap(),
+            vec![ScVal::U64(5)]
+        );
+    }
+
+    #[test]
+    fn test_single_vec_parameter_takes_whole_array() {
+        match &parse("sum", "[1, 2, 3]").unwrap()[..] {
+            [ScVal::Vec(Some(items))] => {
+                assert_eq!(
+                    items.to_vec(),
+                    vec![ScVal::U64(1), ScVal::U64(2), ScVal::U64(3)]
+                )
+            }
+            other => panic!("Expected a single vec, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_single_vec_parameter_takes_one_element_array() {
+        let one = vec![ScVal::U64(7)];
+        for json in [
+            "[7]",
+            "[[7]]",
+            r#"[{"type": "vec", "value": [{"type": "u64", "value": 7}]}]"#,
+        ] {
+            match &parse("sum", json).unwrap()[..] {
+                [ScVal::Vec(Some(items))] => assert_eq!(items.to_vec(), one, "{}", json),
+                other => panic!("Expected a single vec for {}, got {:?}", json, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_struct_becomes_symbol_keyed_map() {
+        let vals = parse(
+            "pay",
+            r#"["GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF", {"amount": 10, "memo": null}]"#,
+        )
+        .unwrap();
+        assert!(matches!(vals[0], ScVal::Address(_)));
+        match &vals[1] {
+            ScVal::Map(Some(map)) => {
+                assert_eq!(map.len(), 2);
+                assert!(matches!(map[0].val, ScVal::I128(_)));
+                assert_eq!(map[1].val, ScVal::Void);
+            }
+            other => panic!("Expected map, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_struct_missing_and_unknown_fields() {
+        let err = parse(
+            "pay",
+            r#"["GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF", {"amount": 1}]"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("missing field 'memo'"), "{}", err);
+
+        let err = parse(
+            "pay",
+            r#"["GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF", {"amount": 1, "memo": null, "fee": 2}]"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("no field 'fee'"), "{}", err);
+    }
+
+    #[test]
+    fn test_union_cases() {
+        let stop = ScVal::Symbol(ScSymbol("Stop".try_into().unwrap()));
+        match &parse("act", r#""Stop""#).unwrap()[..] {
+            [ScVal::Vec(Some(items))] => assert_eq!(items.to_vec(), vec![stop]),
+            other => panic!("Expected vec, got {:?}", other),
+        }
+
+        for json in [r#"{"Move": 3}"#, r#"[["Move", 3]]"#] {
+            match &parse("act", json).unwrap()[..] {
+                [ScVal::Vec(Some(items))] => assert_eq!(items[1], ScVal::U32(3)),
+                other => panic!("Expected vec, got {:?}", other),
+            }
+        }
+
+        let err = parse("act", r#""Jump""#).unwrap_err();
+        assert!(err.to_string().contains("no case 'Jump'"), "{}", err);
+    }
+
+    #[test]
+    fn test_enum_by_name_or_value() {
+        assert_eq!(parse("level", r#""High""#).unwrap(), vec![ScVal::U32(2)]);
+        assert_eq!(parse("level", "1").unwrap(), vec![ScVal::U32(1)]);
+        assert!(parse("level", "7").is_err());
+    }
+
+    #[test]
+    fn test_errors_name_the_parameter() {
+        let err = parse("set", "[-1]").unwrap_err();
+        assert!(
+            matches!(&err, ArgumentParseError::InvalidParameter { name, .. } if name == "count"),
+            "{:?}",
+            err
+        );
+        assert!(
+            err.to_string().starts_with("Parameter 'count' (U32)"),
+            "{}",
+            err
+        );
+    }
+
+    fn symbol(s: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(s.try_into().unwrap()))
+    }
+
+    #[test]
+    fn test_value_to_json_struct_round_trips() {
+        let spec = test_spec();
+        let args = r#"["GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF", {"amount": 10, "memo": "gift"}]"#;
+        let vals = parse("pay", args).unwrap();
+
+        assert_eq!(
+            spec.value_to_json(&vals[0], &ScSpecTypeDef::Address),
+            json!("GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF")
+        );
+        assert_eq!(
+            spec.value_to_json(&vals[1], &udt("Transfer")),
+            json!({"amount": 10, "memo": "gift"})
+        );
+    }
+
+    #[test]
+    fn test_value_to_json_unions_and_enums() {
+        let spec = test_spec();
+        let stop = ScVal::Vec(Some(vec![symbol("Stop")].try_into().unwrap()));
+        let mv = ScVal::Vec(Some(
+            vec![symbol("Move"), ScVal::U32(3)].try_into().unwrap(),
+        ));
+
+        assert_eq!(spec.value_to_json(&stop, &udt("Action")), json!("Stop"));
+        assert_eq!(spec.value_to_json(&mv, &udt("Action")), json!({"Move": 3}));
+        assert_eq!(
+            spec.value_to_json(&ScVal::U32(2), &udt("Level")),
+            json!("High")
+        );
+    }
+
+    #[test]
+    fn test_value_to_json_falls_back_to_annotations() {
+        let spec = test_spec();
+        // Not a valid `Level` case
+        assert_eq!(
+            spec.value_to_json(&ScVal::U32(9), &udt("Level")),
+            json!({"type": "u32", "value": 9})
+        );
+        assert_eq!(
+            spec.value_to_json(&ScVal::Bool(true), &ScSpecTypeDef::Val),
+            json!(true)
+        );
+        // A plain value of another type keeps its own type
+        assert_eq!(
+            spec.value_to_json(&ScVal::U32(5), &ScSpecTypeDef::I128),
+            json!({"type": "u32", "value": 5})
+        );
+        assert_eq!(
+            spec.value_to_json(&ScVal::U32(5), &ScSpecTypeDef::U32),
+            json!(5)
+        );
+    }
+
+    #[test]
+    fn test_describe_error_names_codes() {
+        let spec = test_spec();
+
+        let error = spec.describe_error(Some("set"), 1);
+        assert_eq!(error.name.as_deref(), Some("Error::NotInitialized"));
+        assert_eq!(error.doc, None);
+        assert_eq!(error.to_string(), "Error::NotInitialized (1)");
+
+        // The function's declared error type wins over other enums
+        let error = spec.describe_error(Some("withdraw"), 3);
+        assert_eq!(
+            error.to_string(),
+            "TokenError::InsufficientBalance (3): Balance is too low"
+        );
+        assert_eq!(
+            spec.describe_error(None, 3).name.as_deref(),
+            Some("Error::Overflow")
+        );
+
+        let unknown = spec.describe_error(Some("set"), 42);
+        assert_eq!(unknown.name, None);
+        assert_eq!(unknown.to_string(), "Contract error code: 42");
+    }
+
+    #[test]
+    fn test_contract_error_matches_names_and_code() {
+        let error = test_spec().describe_error(Some("withdraw"), 3);
+        assert!(error.matches("TokenError::InsufficientBalance"));
+        assert!(error.matches("InsufficientBalance"));
+        assert!(error.matches("3"));
+        assert!(!error.matches("Overflow"));
+    }
+
+    #[test]
+    fn test_argument_count_mismatch() {
+        let err = parse("pay", "[1]").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("expects 2 argument(s) (to: Address, t: Transfer), got 1"),
+            "{}",
+            err
+        );
+    }
+}
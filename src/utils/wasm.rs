@@ -1,6 +1,7 @@
 use crate::Result;
 use serde::Deserialize;
-use wasmparser::{Parser, Payload};
+use std::collections::HashMap;
+use wasmparser::{Name, NameSectionReader, Parser, Payload};
 
 // ─── existing public API (unchanged) ─────────────────────────────────────────
 
@@ -23,6 +24,47 @@ pub fn parse_functions(wasm_bytes: &[u8]) -> Result<Vec<String>> {
     Ok(functions)
 }
 
+/// Names of the functions in a WASM module, by index in the function index
+/// space. Exported names take precedence over names from the `name` section.
+pub fn parse_function_names(wasm_bytes: &[u8]) -> Result<HashMap<u32, String>> {
+    let mut names = HashMap::new();
+    let mut exports = Vec::new();
+
+    for payload in Parser::new(0).parse_all(wasm_bytes) {
+        match payload? {
+            Payload::ExportSection(reader) => {
+                for export in reader {
+                    let export = export?;
+                    if matches!(export.kind, wasmparser::ExternalKind::Func) {
+                        exports.push((export.index, export.name.to_string()));
+                    }
+                }
+            }
+            Payload::CustomSection(reader) if reader.name() == "name" => {
+                // Debug names are optional; a malformed section is ignored
+                let section = NameSectionReader::new(reader.data(), reader.data_offset());
+                for name in section.into_iter().flatten() {
+                    if let Name::Function(map) = name {
+                        for naming in map.into_iter().flatten() {
+                            names.insert(naming.index, demangle_name(naming.name));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    names.extend(exports);
+    Ok(names)
+}
+
+/// Readable form of a `name` section entry. Rust symbols are demangled
+/// without their hash suffix; other names are returned as they are.
+pub fn demangle_name(name: &str) -> String {
+    format!("{:#}", rustc_demangle::demangle(name))
+}
+
 /// Get high-level module statistics from a WASM binary.
 pub fn get_module_info(wasm_bytes: &[u8]) -> Result<ModuleInfo> {
     let mut info = ModuleInfo::default();
@@ -244,7 +286,7 @@ pub struct FunctionSignature {
 }
 
 /// Convert an XDR `ScSpecTypeDef` into a human-readable type string.
-fn spec_type_to_string(ty: &stellar_xdr::curr::ScSpecTypeDef) -> String {
+pub fn spec_type_to_string(ty: &stellar_xdr::curr::ScSpecTypeDef) -> String {
     use stellar_xdr::curr::ScSpecTypeDef as T;
     match ty {
         T::Val => "Val".into(),
@@ -295,15 +337,13 @@ fn stringm_to_string(bytes: &[u8]) -> String {
         .to_string()
 }
 
-/// Parse full function signatures from the WASM `contractspecv0` custom section.
+/// Parse every entry of the WASM `contractspecv0` custom section.
 ///
-/// Returns an empty `Vec` (not an error) when no spec section is present —
-/// this keeps callers simple and backward-compatible with contracts that
-/// pre-date the spec section.
-pub fn parse_function_signatures(wasm_bytes: &[u8]) -> Result<Vec<FunctionSignature>> {
+/// Returns an empty `Vec` when no spec section is present.
+pub fn parse_contract_spec(wasm_bytes: &[u8]) -> Result<Vec<stellar_xdr::curr::ScSpecEntry>> {
     use stellar_xdr::curr::{Limited, Limits, ReadXdr, ScSpecEntry};
 
-    let mut signatures = Vec::new();
+    let mut entries = Vec::new();
     let parser = Parser::new(0);
 
     for payload in parser.parse_all(wasm_bytes) {
@@ -320,38 +360,49 @@ pub fn parse_function_signatures(wasm_bytes: &[u8]) -> Result<Vec<FunctionSignat
         let mut limited = Limited::new(cursor, Limits::none());
 
         // The section is a packed sequence of XDR-encoded ScSpecEntry values.
-        loop {
-            match ScSpecEntry::read_xdr(&mut limited) {
-                Ok(ScSpecEntry::FunctionV0(func)) => {
-                    let name = stringm_to_string(func.name.0.as_slice());
-
-                    let params = func
-                        .inputs
-                        .iter()
-                        .map(|input| FunctionParam {
-                            name: stringm_to_string(input.name.as_slice()),
-                            type_name: spec_type_to_string(&input.type_),
-                        })
-                        .collect();
-
-                    let return_type = func.outputs.first().map(spec_type_to_string);
-
-                    signatures.push(FunctionSignature {
-                        name,
-                        params,
-                        return_type,
-                    });
-                }
-                Ok(_) => {
-                    // UDT definitions, events, etc. — skip
-                }
-                Err(_) => break, // end of section or corrupt data
-            }
+        while let Ok(entry) = ScSpecEntry::read_xdr(&mut limited) {
+            entries.push(entry);
         }
 
         break; // only one contractspecv0 section exists per contract
     }
 
+    Ok(entries)
+}
+
+/// Parse full function signatures from the WASM `contractspecv0` custom section.
+///
+/// Returns an empty `Vec` (not an error) when no spec section is present —
+/// this keeps callers simple and backward-compatible with contracts that
+/// pre-date the spec section.
+pub fn parse_function_signatures(wasm_bytes: &[u8]) -> Result<Vec<FunctionSignature>> {
+    use stellar_xdr::curr::ScSpecEntry;
+
+    let signatures = parse_contract_spec(wasm_bytes)?
+        .into_iter()
+        .filter_map(|entry| match entry {
+            ScSpecEntry::FunctionV0(func) => Some(func),
+            // UDT definitions, events, etc. — skip
+            _ => None,
+        })
+        .map(|func| {
+            let params = func
+                .inputs
+                .iter()
+                .map(|input| FunctionParam {
+                    name: stringm_to_string(input.name.as_slice()),
+                    type_name: spec_type_to_string(&input.type_),
+                })
+                .collect();
+
+            FunctionSignature {
+                name: stringm_to_string(func.name.0.as_slice()),
+                params,
+                return_type: func.outputs.first().map(spec_type_to_string),
+            }
+        })
+        .collect();
+
     Ok(signatures)
 }
 
@@ -509,4 +560,16 @@ implementation_notes=Line-based format
         };
         assert!(!meta.is_empty());
     }
+
+    // ── demangle_name ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn demangle_name_strips_rust_mangling() {
+        let mangled = "_ZN80_$LT$soroban_env_guest..guest..Guest$u20$as$u20$soroban_env_common..env..Env$GT$17put_contract_data17h7f6ff6954112b083E";
+        assert_eq!(
+            demangle_name(mangled),
+            "<soroban_env_guest::guest::Guest as soroban_env_common::env::Env>::put_contract_data"
+        );
+        assert_eq!(demangle_name("increment"), "increment");
+    }
 }
@@ -61,6 +61,7 @@ fn test_batch_summary_calculation() {
             result: "ok".to_string(),
             success: true,
             error: None,
+            contract_error: None,
             expected: Some("ok".to_string()),
             passed: true,
             duration_ms: 10,
@@ -72,6 +73,7 @@ fn test_batch_summary_calculation() {
             result: "fail".to_string(),
             success: true,
             error: None,
+            contract_error: None,
             expected: Some("ok".to_string()),
             passed: false,
             duration_ms: 15,
@@ -83,6 +85,7 @@ fn test_batch_summary_calculation() {
             result: String::new(),
             success: false,
             error: Some("execution error".to_string()),
+            contract_error: None,
             expected: None,
             passed: false,
             duration_ms: 5,
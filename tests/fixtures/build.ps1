@@ -4,7 +4,7 @@
 #
 # Prerequisites:
 #   - Rust toolchain installed
-#   - wasm32-unknown-unknown target: rustup target add wasm32-unknown-unknown
+#   - wasm32v1-none target: rustup target add wasm32v1-none
 #
 # This script builds all contracts in tests/fixtures/contracts/ and
 # places the compiled WASM files in tests/fixtures/wasm/
@@ -14,12 +14,15 @@ $ErrorActionPreference = "Stop"
 $ScriptDir = Split-Path -Parent $MyInvocation.MyCommand.Path
 $ContractsDir = Join-Path $ScriptDir "contracts"
 $WasmDir = Join-Path $ScriptDir "wasm"
+# The target Soroban recommends; wasm32-unknown-unknown on Rust 1.82 and
+# later emits WASM features the host rejects
+$Target = "wasm32v1-none"
 
 # Check if wasm32 target is installed
 $InstalledTargets = rustup target list --installed
-if ($InstalledTargets -notcontains "wasm32-unknown-unknown") {
-    Write-Host "Error: wasm32-unknown-unknown target not installed." -ForegroundColor Red
-    Write-Host "Install it with: rustup target add wasm32-unknown-unknown" -ForegroundColor Yellow
+if ($InstalledTargets -notcontains $Target) {
+    Write-Host "Error: $Target target not installed." -ForegroundColor Red
+    Write-Host "Install it with: rustup target add $Target" -ForegroundColor Yellow
     exit 1
 }
 
@@ -39,11 +42,12 @@ Get-ChildItem -Path $ContractsDir -Directory | ForEach-Object {
         
         Push-Location $ContractDir
         try {
-            cargo build --release --target wasm32-unknown-unknown
+            cargo build --release --target $Target
             
-            # Find the generated WASM file
-            $WasmFileName = $ContractName -replace "-", "_"
-            $WasmFile = Join-Path $ContractDir "target\wasm32-unknown-unknown\release\${WasmFileName}.wasm"
+            # Find the generated WASM file, named after the package
+            # (`<contract>-fixture`) in the workspace's target directory
+            $WasmFileName = ($ContractName -replace "-", "_") + "_fixture"
+            $WasmFile = Join-Path $ContractsDir "target\$Target\release\${WasmFileName}.wasm"
             
             if (Test-Path $WasmFile) {
                 Copy-Item $WasmFile (Join-Path $WasmDir "${ContractName}.wasm")
@@ -58,6 +62,31 @@ Get-ChildItem -Path $ContractsDir -Directory | ForEach-Object {
     }
 }
 
+# Build counter again with DWARF line tables for the source map tests.
+# Paths are remapped relative to the repository root so that the sources
+# resolve from there. The workspace ignores the contract's own release
+# profile, so it is given here to keep the binary small.
+Write-Host "  Building counter_debug..." -ForegroundColor Yellow
+$RepoRoot = (Resolve-Path (Join-Path $ScriptDir "..\..")).Path
+Push-Location (Join-Path $ContractsDir "counter")
+try {
+    $env:RUSTFLAGS = "--remap-path-prefix=$RepoRoot\="
+    $env:CARGO_PROFILE_RELEASE_DEBUG = "line-tables-only"
+    $env:CARGO_PROFILE_RELEASE_STRIP = "none"
+    $env:CARGO_PROFILE_RELEASE_OPT_LEVEL = "z"
+    $env:CARGO_PROFILE_RELEASE_LTO = "true"
+    $env:CARGO_PROFILE_RELEASE_CODEGEN_UNITS = "1"
+    $env:CARGO_PROFILE_RELEASE_PANIC = "abort"
+    cargo build --release --target $Target --target-dir target\debug-info
+    Copy-Item "target\debug-info\$Target\release\counter_fixture.wasm" (Join-Path $WasmDir "counter_debug.wasm")
+    Write-Host "    ✓ Built counter_debug.wasm" -ForegroundColor Green
+} finally {
+    Remove-Item Env:RUSTFLAGS, Env:CARGO_PROFILE_RELEASE_DEBUG, Env:CARGO_PROFILE_RELEASE_STRIP,
+        Env:CARGO_PROFILE_RELEASE_OPT_LEVEL, Env:CARGO_PROFILE_RELEASE_LTO,
+        Env:CARGO_PROFILE_RELEASE_CODEGEN_UNITS, Env:CARGO_PROFILE_RELEASE_PANIC
+    Pop-Location
+}
+
 Write-Host ""
 Write-Host "All contracts built successfully!" -ForegroundColor Green
 Write-Host "WASM files are in: $WasmDir" -ForegroundColor Cyan
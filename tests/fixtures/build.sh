@@ -5,7 +5,7 @@
 #
 # Prerequisites:
 #   - Rust toolchain installed
-#   - wasm32-unknown-unknown target: rustup target add wasm32-unknown-unknown
+#   - wasm32v1-none target: rustup target add wasm32v1-none
 #
 # This script builds all contracts in tests/fixtures/contracts/ and
 # places the compiled WASM files in tests/fixtures/wasm/
@@ -13,13 +13,16 @@
 set -e
 
 SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+# The target Soroban recommends; wasm32-unknown-unknown on Rust 1.82 and
+# later emits WASM features the host rejects
+TARGET="wasm32v1-none"
 CONTRACTS_DIR="${SCRIPT_DIR}/contracts"
 WASM_DIR="${SCRIPT_DIR}/wasm"
 
 # Check if wasm32 target is installed
-if ! rustup target list --installed | grep -q "wasm32-unknown-unknown"; then
-    echo "Error: wasm32-unknown-unknown target not installed."
-    echo "Install it with: rustup target add wasm32-unknown-unknown"
+if ! rustup target list --installed | grep -q "${TARGET}"; then
+    echo "Error: ${TARGET} target not installed."
+    echo "Install it with: rustup target add ${TARGET}"
     exit 1
 fi
 
@@ -36,10 +39,11 @@ for contract_dir in "${CONTRACTS_DIR}"/*/; do
         
         (
             cd "${contract_dir}"
-            cargo build --release --target wasm32-unknown-unknown
+            cargo build --release --target "${TARGET}"
             
-            # Find the generated WASM file
-            wasm_file="target/wasm32-unknown-unknown/release/${contract_name//-/_}.wasm"
+            # Find the generated WASM file, named after the package
+            # (`<contract>-fixture`) in the workspace's target directory
+            wasm_file="${CONTRACTS_DIR}/target/${TARGET}/release/${contract_name//-/_}_fixture.wasm"
             
             if [ -f "${wasm_file}" ]; then
                 cp "${wasm_file}" "${WASM_DIR}/${contract_name}.wasm"
@@ -52,6 +56,27 @@ for contract_dir in "${CONTRACTS_DIR}"/*/; do
     fi
 done
 
+# Build counter again with DWARF line tables for the source map tests.
+# Paths are remapped relative to the repository root so that the sources
+# resolve from there. The workspace ignores the contract's own release
+# profile, so it is given here to keep the binary small.
+echo "  Building counter_debug..."
+(
+    cd "${CONTRACTS_DIR}/counter"
+    REPO_ROOT="$(cd "${SCRIPT_DIR}/../.." && pwd)"
+    RUSTFLAGS="--remap-path-prefix=${REPO_ROOT}/=" \
+        CARGO_PROFILE_RELEASE_DEBUG=line-tables-only \
+        CARGO_PROFILE_RELEASE_STRIP=none \
+        CARGO_PROFILE_RELEASE_OPT_LEVEL=z \
+        CARGO_PROFILE_RELEASE_LTO=true \
+        CARGO_PROFILE_RELEASE_CODEGEN_UNITS=1 \
+        CARGO_PROFILE_RELEASE_PANIC=abort \
+        cargo build --release --target "${TARGET}" --target-dir target/debug-info
+    cp "target/debug-info/${TARGET}/release/counter_fixture.wasm" \
+        "${WASM_DIR}/counter_debug.wasm"
+    echo "    ✓ Built counter_debug.wasm"
+)
+
 echo ""
 echo "All contracts built successfully!"
 echo "WASM files are in: ${WASM_DIR}"
@@ -1,6 +1,6 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, Val};
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, Val, Vec};
 
 #[contract]
 pub struct CrossContractCaller;
@@ -19,13 +19,13 @@ impl CrossContractCaller {
         function: soroban_sdk::Symbol,
         args: Vec<Val>,
     ) -> Val {
-        env.invoke_contract(&callee, &function, &args)
+        env.invoke_contract(&callee, &function, args)
     }
 
     /// Call another contract's echo function with a value
     pub fn call_echo(env: Env, callee: Address, value: Val) -> Val {
         let args = soroban_sdk::vec![&env, value];
-        env.invoke_contract(&callee, &symbol_short!("echo"), &args)
+        env.invoke_contract(&callee, &symbol_short!("echo"), args)
     }
 
     /// Chain call: call a contract that calls another contract
@@ -39,14 +39,14 @@ impl CrossContractCaller {
         let first_result = env.invoke_contract(
             &first,
             &symbol_short!("echo"),
-            &soroban_sdk::vec![&env, value],
+            soroban_sdk::vec![&env, value],
         );
         
         // Call second contract with first result
         env.invoke_contract(
             &second,
             &symbol_short!("echo"),
-            &soroban_sdk::vec![&env, first_result],
+            soroban_sdk::vec![&env, first_result],
         )
     }
 }
@@ -0,0 +1,14 @@
+#![no_std]
+
+use soroban_sdk::{contract, contractimpl, symbol_short, Env, Symbol};
+
+#[contract]
+pub struct Events;
+
+#[contractimpl]
+impl Events {
+    /// Publish a `("transfer", to)` event with the amount as data
+    pub fn transfer(env: Env, to: Symbol, amount: i64) {
+        env.events().publish((symbol_short!("transfer"), to), amount);
+    }
+}
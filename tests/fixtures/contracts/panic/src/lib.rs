@@ -14,7 +14,7 @@ impl PanicContract {
 
     /// Panics with a custom message
     pub fn panic_with_message(_env: Env, message: soroban_sdk::String) {
-        panic!("{}", message);
+        panic!("{:?}", message);
     }
 
     /// Panics when called with a specific value
@@ -112,7 +112,7 @@ fn test_instruction_context_formatting() {
         (2, instruction.clone(), false),
     ];
 
-    let formatted = Formatter::format_instruction_context(&context, 3);
+    let formatted = Formatter::format_instruction_context(&context, 3, None);
     assert!(formatted.contains("Instruction Context"));
     assert!(formatted.contains("i32.const"));
     assert!(formatted.contains("►")); // Current instruction marker
@@ -215,12 +215,12 @@ fn test_instruction_formatting() {
     );
 
     // Test current instruction formatting
-    let formatted_current = Formatter::format_instruction(&instruction, true);
+    let formatted_current = Formatter::format_instruction(&instruction, true, None);
     assert!(formatted_current.starts_with("►"));
     assert!(formatted_current.contains("local.get"));
 
     // Test non-current instruction formatting
-    let formatted_normal = Formatter::format_instruction(&instruction, false);
+    let formatted_normal = Formatter::format_instruction(&instruction, false, None);
     assert!(formatted_normal.starts_with(" "));
     assert!(formatted_normal.contains("local.get"));
 }
@@ -294,6 +294,63 @@ fn test_debug_state_instruction_management() {
     assert!(next.is_some());
 }
 
+#[test]
+fn test_stepping_follows_recorded_trace() {
+    use soroban_debugger::debugger::instruction_pointer::StepMode;
+    use soroban_debugger::debugger::{DebugState, InstructionTrace, Stepper, TraceStep};
+
+    let instructions = vec![
+        Instruction::new(
+            0x100,
+            wasmparser::Operator::Call { function_index: 1 },
+            0,
+            0,
+        ),
+        Instruction::new(0x102, wasmparser::Operator::Drop, 0, 1),
+        Instruction::new(0x200, wasmparser::Operator::I32Const { value: 1 }, 1, 0),
+        Instruction::new(0x202, wasmparser::Operator::Return, 1, 1),
+    ];
+    let step = |instruction_index, depth| TraceStep {
+        instruction_index,
+        depth,
+    };
+
+    let mut debug_state = DebugState::new();
+    debug_state.set_instructions(instructions);
+    debug_state.enable_instruction_debug();
+    // call -> callee body -> back in the caller
+    debug_state.set_trace(InstructionTrace::new(vec![
+        step(0, 0),
+        step(2, 1),
+        step(3, 1),
+        step(1, 0),
+    ]));
+
+    let mut stepper = Stepper::new();
+    stepper.start(StepMode::StepInto, &mut debug_state);
+
+    // Step into follows the call
+    assert!(stepper.step_into(&mut debug_state));
+    assert_eq!(debug_state.current_instruction().unwrap().offset, 0x200);
+    assert_eq!(debug_state.instruction_pointer().call_stack_depth(), 1);
+
+    // Step out returns to the caller
+    assert!(stepper.step_out(&mut debug_state));
+    assert_eq!(debug_state.current_instruction().unwrap().offset, 0x102);
+    assert_eq!(debug_state.instruction_pointer().call_stack_depth(), 0);
+
+    // Step back walks the trace in reverse
+    assert!(stepper.step_back(&mut debug_state));
+    assert_eq!(debug_state.current_instruction().unwrap().offset, 0x202);
+    assert_eq!(debug_state.instruction_pointer().trace_position(), 2);
+
+    // Step over from the call skips the callee
+    debug_state.reset();
+    assert!(stepper.step_over(&mut debug_state));
+    assert_eq!(debug_state.current_instruction().unwrap().offset, 0x102);
+    assert!(!stepper.step_into(&mut debug_state));
+}
+
 // Performance test to ensure instruction parsing is acceptable
 #[test]
 fn test_instruction_parsing_performance() {
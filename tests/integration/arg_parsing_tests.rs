@@ -136,16 +136,46 @@ fn test_parse_mixed_typed_and_bare() {
     assert_eq!(result.unwrap().len(), 4);
 }
 
+// ── Addresses, bytes and collections ─────────────────────────────────
+
+#[test]
+fn test_parse_transfer_style_arguments() {
+    let parser = create_parser();
+    let result = parser.parse_args_string(
+        r#"[
+            {"type": "address", "value": "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF"},
+            {"type": "address", "value": "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABSC4"},
+            {"type": "i128", "value": 100}
+        ]"#,
+    );
+    assert!(result.is_ok(), "Failed to parse: {:?}", result.err());
+    assert_eq!(result.unwrap().len(), 3);
+}
+
+#[test]
+fn test_parse_typed_collections() {
+    let parser = create_parser();
+    let result = parser.parse_args_string(
+        r#"[
+            {"type": "bytesn", "length": 4, "value": "0xdeadbeef"},
+            {"type": "vec", "element": "u32", "value": [1, 2, 3]},
+            {"type": "map", "key_type": "symbol", "value_type": "i128", "value": {"alice": 5}}
+        ]"#,
+    );
+    assert!(result.is_ok(), "Failed to parse: {:?}", result.err());
+    assert_eq!(result.unwrap().len(), 3);
+}
+
 // ── Error handling ───────────────────────────────────────────────────
 
 #[test]
 fn test_error_unsupported_type() {
     let parser = create_parser();
-    let result = parser.parse_args_string(r#"[{"type": "bytes", "value": "abc"}]"#);
+    let result = parser.parse_args_string(r#"[{"type": "f64", "value": 1.5}]"#);
     assert!(result.is_err());
     let err_msg = result.unwrap_err().to_string();
     assert!(
-        err_msg.contains("Unsupported type") || err_msg.contains("bytes"),
+        err_msg.contains("Unsupported type") || err_msg.contains("f64"),
         "Expected unsupported type error, got: {}",
         err_msg
     );